@@ -3,10 +3,19 @@ import { bindings } from "./bindings";
 const textEncoder = new TextEncoder();
 
 type BlockIDAndLinesPair = [id: string, range: { start: number; end: number }];
+/**
+ * 范围以 UTF-16 偏移表示（即 JavaScript 字符串的索引），不含结尾。块级元素附着有
+ * 块级附加信息（`{! … }`）时，范围从附加信息的开头算起。
+ */
+type BlockIDAndUTF16RangePair = [
+  id: string,
+  range: { start: number; end: number },
+];
 
 export interface ParseAndRenderResult {
   html: string;
   blockIDAndLinesPairs: BlockIDAndLinesPair[];
+  blockIDAndUTF16RangePairs: BlockIDAndUTF16RangePair[];
   devEventsInDebugFormat?: string;
 }
 
@@ -17,9 +26,20 @@ export interface ParseAndRenderOptions {
 
 export interface TagNameMap {
   "code-block": string;
+  "block-extension": string;
+  "collapse": string;
+  "callout-note": string;
+  "callout-tip": string;
+  "callout-important": string;
+  "callout-warning": string;
+  "callout-caution": string;
   "ref-link": string;
   "dicexp": string;
   "wiki-link": string;
+  "inline-extension": string;
+  "scratch-off": string;
+  "external-link": string;
+  "preview-warn": string;
 }
 
 export function parseAndRender(
@@ -39,13 +59,17 @@ export function parseAndRender(
 
   const output = result.clone_ok()!;
 
-  const blockIDToLinesMap = deserializeBlockIDToLinesMap(
+  const blockIDToLinesMap = deserializeBlockIDToRangeMap(
     output.clone_block_id_to_lines_map(),
   );
+  const blockIDToUTF16RangesMap = deserializeBlockIDToRangeMap(
+    output.clone_block_id_to_utf16_ranges_map(),
+  );
 
   const ret = {
     html: output.clone_html(),
     blockIDAndLinesPairs: blockIDToLinesMap,
+    blockIDAndUTF16RangePairs: blockIDToUTF16RangesMap,
     ...("clone_dev_events_in_debug_format" in output
       ? {
         devEventsInDebugFormat:
@@ -59,18 +83,31 @@ export function parseAndRender(
 function serializeTagNameMap(tagNameMap: TagNameMap): string {
   return [
     tagNameMap["code-block"],
+    tagNameMap["block-extension"],
+    tagNameMap["collapse"],
+    tagNameMap["callout-note"],
+    tagNameMap["callout-tip"],
+    tagNameMap["callout-important"],
+    tagNameMap["callout-warning"],
+    tagNameMap["callout-caution"],
     tagNameMap["ref-link"],
     tagNameMap["dicexp"],
     tagNameMap["wiki-link"],
+    tagNameMap["inline-extension"],
+    tagNameMap["scratch-off"],
+    tagNameMap["external-link"],
+    tagNameMap["preview-warn"],
   ].join("\0");
 }
 
-function deserializeBlockIDToLinesMap(input: string): BlockIDAndLinesPair[] {
+function deserializeBlockIDToRangeMap(
+  input: string,
+): [id: string, range: { start: number; end: number }][] {
   if (!input) return [];
 
   return input
     .split(";")
-    .map((x): BlockIDAndLinesPair => {
+    .map((x): [id: string, range: { start: number; end: number }] => {
       const idAndRange = x.split(":");
       const id = idAndRange[0]!;
       const range = idAndRange[1]?.split("-")!;
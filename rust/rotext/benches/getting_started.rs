@@ -31,7 +31,15 @@ fn rendering(bencher: divan::Bencher) {
                 file_content.as_bytes(),
                 rotext::NewHtmlRendererOptions {
                     tag_name_map: Default::default(),
+                    extension_registry: Default::default(),
+                    dicexp_result_provider: None,
+                    ref_link_resolver: None,
+                    ref_link_context: Default::default(),
+                    wiki_link_resolver: None,
                     initial_output_string_capacity: file_content.len() * 3,
+                    should_include_heading_ids: false,
+                    heading_level_adjustment: Default::default(),
+                    render_mode: Default::default(),
                     #[cfg(feature = "block-id")]
                     should_include_block_ids: true,
                 },
@@ -50,7 +58,15 @@ fn parsing_and_rendering(bencher: divan::Bencher) {
             file_content.as_bytes(),
             rotext::NewHtmlRendererOptions {
                 tag_name_map: Default::default(),
+                extension_registry: Default::default(),
+                dicexp_result_provider: None,
+                ref_link_resolver: None,
+                ref_link_context: Default::default(),
+                wiki_link_resolver: None,
                 initial_output_string_capacity: file_content.len() * 3,
+                should_include_heading_ids: false,
+                heading_level_adjustment: Default::default(),
+                render_mode: Default::default(),
                 #[cfg(feature = "block-id")]
                 should_include_block_ids: true,
             },
@@ -0,0 +1,205 @@
+//! 附加信息（块级的 `{! … }` 与行内的 `[! … ]`）的解析。
+//!
+//! 一组附加信息由开启部分、闭合部分以及二者之间由空白分隔的若干项组成，每项
+//! 为以下两种形式之一：
+//! - `.<name>`：类；
+//! - `<key>="<value>"`/`<key>='<value>'`：属性。值中的字符引用会被解码（见
+//!   [decode_character_references]）。
+
+use std::{borrow::Cow, ops::Range};
+
+/// 附加信息中的一项。范围均是在输入中的范围。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Attribute {
+    Class(Range<usize>),
+    KeyValue {
+        key: Range<usize>,
+        value: Range<usize>,
+    },
+}
+
+/// 尝试解析位于 `start` 的一组附加信息（块级或行内的均可）。若成功，返回闭合
+/// 部分之后的位置。
+///
+/// 解析过程中遇到的各项会被依次传给 `on_item`，即使最终解析失败。
+pub fn parse_group(
+    input: &[u8],
+    start: usize,
+    mut on_item: impl FnMut(Attribute),
+) -> Option<usize> {
+    let closing = match input.get(start..start + 2)? {
+        b"{!" => b'}',
+        b"[!" => b']',
+        _ => return None,
+    };
+    let items_start = start + 2;
+    let mut i = items_start;
+
+    loop {
+        let is_after_whitespace = matches!(input.get(i), Some(&char) if is_separator(char));
+        while matches!(input.get(i), Some(&char) if is_separator(char)) {
+            i += 1;
+        }
+
+        let &char = input.get(i)?;
+        if char == closing {
+            break Some(i + 1);
+        }
+        if i > items_start && !is_after_whitespace {
+            // 各项之间需要有空白。
+            return None;
+        }
+
+        if char == b'.' {
+            let name_start = i + 1;
+            i = name_start;
+            while matches!(input.get(i), Some(&char) if !is_separator(char) && char != closing) {
+                i += 1;
+            }
+            if i == name_start {
+                return None;
+            }
+            on_item(Attribute::Class(name_start..i));
+        } else {
+            let key_start = i;
+            while matches!(input.get(i), Some(&char) if is_valid_character_in_key(char)) {
+                i += 1;
+            }
+            if i == key_start || input.get(i) != Some(&b'=') {
+                return None;
+            }
+            let key = key_start..i;
+            i += "=".len();
+
+            let &quote = input.get(i).filter(|char| matches!(char, b'"' | b'\''))?;
+            let value_start = i + 1;
+            let value_len = input[value_start..]
+                .iter()
+                .position(|char| *char == quote)?;
+            i = value_start + value_len + 1;
+            on_item(Attribute::KeyValue {
+                key,
+                value: value_start..(value_start + value_len),
+            });
+        }
+    }
+}
+
+/// 将 `range` 中各组附加信息的各项依次传给 `on_item`。
+///
+/// 调用者应保证 `range` 是若干组已由 [parse_group] 确认有效的附加信息，各组之
+/// 间只有空白。
+pub fn for_each_item(input: &[u8], range: Range<usize>, mut on_item: impl FnMut(Attribute)) {
+    let mut i = range.start;
+    while i < range.end {
+        if is_separator(input[i]) {
+            i += 1;
+            continue;
+        }
+        let end = parse_group(input, i, &mut on_item);
+        debug_assert!(end.is_some());
+        let Some(end) = end else {
+            break;
+        };
+        i = end;
+    }
+}
+
+/// 返回 `input[range]` 是否只由空白组成。
+pub fn is_blank(input: &[u8], range: Range<usize>) -> bool {
+    input[range].iter().all(|char| is_separator(*char))
+}
+
+fn is_separator(char: u8) -> bool {
+    matches!(char, b' ' | b'\t' | b'\r' | b'\n')
+}
+
+fn is_valid_character_in_key(char: u8) -> bool {
+    char.is_ascii_alphanumeric() || matches!(char, b'-' | b'_')
+}
+
+/// 返回 `range` 中各组附加信息里，第一个键为 `key`（忽略大小写）的属性的值在输入
+/// 中的范围。
+///
+/// 对 `range` 的要求同 [for_each_item]。
+pub fn find_value(input: &[u8], range: Range<usize>, key: &[u8]) -> Option<Range<usize>> {
+    let mut found = None;
+    for_each_item(input, range, |item| {
+        if let Attribute::KeyValue {
+            key: item_key,
+            value,
+        } = item
+        {
+            if found.is_none() && input[item_key].eq_ignore_ascii_case(key) {
+                found = Some(value);
+            }
+        }
+    });
+    found
+}
+
+/// 像 HTML 的属性值那样，解码 `value` 中的字符引用，包括数字字符引用（`&#<十进
+/// 制>;`、`&#x<十六进制>;`）以及 `&amp;`、`&lt;`、`&gt;`、`&quot;`、`&apos;`、
+/// `&nbsp;` 这几个具名字符引用。无法识别的部分保持原样。
+pub fn decode_character_references(value: &[u8]) -> Cow<'_, [u8]> {
+    if !value.contains(&b'&') {
+        return Cow::Borrowed(value);
+    }
+
+    let mut result = Vec::with_capacity(value.len());
+    let mut i = 0;
+    while i < value.len() {
+        if value[i] == b'&' {
+            if let Some((char, len)) = parse_character_reference(&value[i..]) {
+                let mut buffer = [0; 4];
+                result.extend(char.encode_utf8(&mut buffer).as_bytes());
+                i += len;
+                continue;
+            }
+        }
+        result.push(value[i]);
+        i += 1;
+    }
+
+    Cow::Owned(result)
+}
+
+/// 若 `input` 以字符引用开头，返回其代表的字符以及其长度。
+fn parse_character_reference(input: &[u8]) -> Option<(char, usize)> {
+    let name_len = input[1..]
+        .iter()
+        .take_while(|char| char.is_ascii_alphanumeric() || **char == b'#')
+        .count();
+    let name = &input[1..1 + name_len];
+    if input.get(1 + name_len) != Some(&b';') {
+        return None;
+    }
+
+    let char = match name {
+        b"amp" => '&',
+        b"lt" => '<',
+        b"gt" => '>',
+        b"quot" => '"',
+        b"apos" => '\'',
+        b"nbsp" => '\u{a0}',
+        _ => {
+            let number = name.strip_prefix(b"#")?;
+            let (digits, radix) = match number {
+                [b'x' | b'X', digits @ ..] => (digits, 16),
+                digits => (digits, 10),
+            };
+            if digits.is_empty() || !digits.iter().all(|char| (*char as char).is_digit(radix)) {
+                return None;
+            }
+            // 数字过大时与无效的码位一样，以替换字符代替。
+            // SAFETY: `digits` 中只有 ASCII 字符。
+            let code = u32::from_str_radix(unsafe { std::str::from_utf8_unchecked(digits) }, radix)
+                .unwrap_or(u32::MAX);
+            char::from_u32(code)
+                .filter(|char| *char != '\0')
+                .unwrap_or(char::REPLACEMENT_CHARACTER)
+        }
+    };
+
+    Some((char, 1 + name_len + 1))
+}
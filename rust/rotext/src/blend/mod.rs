@@ -4,6 +4,8 @@ mod tests;
 #[cfg(debug_assertions)]
 use crate::events::is_event_of;
 use crate::{
+    block,
+    diagnostics::{Diagnostic, DiagnosticCollector},
     events::{ev, Event},
     inline::{self},
     utils::{internal::peekable::Peekable, stack::Stack},
@@ -34,6 +36,8 @@ pub struct BlockEventStreamInlineSegmentMapper<
 > {
     input: &'a [u8],
     state: State<'a, TBlockParser, TInlineStack>,
+    /// 行内阶段的诊断信息。各行内阶段解析器在结束时将其诊断信息转移至此。
+    inline_diagnostics: DiagnosticCollector,
 }
 
 impl<
@@ -46,9 +50,16 @@ impl<
         Self {
             input,
             state: State::Normal(Some(block_parser)),
+            inline_diagnostics: DiagnosticCollector::default(),
         }
     }
 
+    /// 开始收集行内阶段的诊断信息。块级阶段的诊断信息需要另外在块级阶段解析器
+    /// 上开启。
+    pub fn enable_diagnostics(&mut self) {
+        self.inline_diagnostics.enable();
+    }
+
     /// 返回的事件属于 `Blend` 分组。
     #[inline(always)]
     fn next(&mut self) -> Option<crate::Result<Event>> {
@@ -63,7 +74,10 @@ impl<
                     if next.is_block_event_that_opens_inline_phase() {
                         let block_parser = unsafe { block_parser.take().unwrap_unchecked() };
                         let segment_stream = WhileInlineSegment::new(block_parser);
-                        let inline_parser = inline::Parser::new(self.input);
+                        let mut inline_parser = inline::Parser::new(self.input);
+                        if self.inline_diagnostics.is_enabled() {
+                            inline_parser.enable_diagnostics();
+                        }
                         self.state = State::ParsingInline {
                             inline_parser,
                             segment_stream: Some(Peekable::new(segment_stream)),
@@ -92,6 +106,8 @@ impl<
                         }
                         Some(Err(err)) => break Err(err),
                         None => {
+                            self.inline_diagnostics
+                                .extend(inline_parser.take_diagnostics());
                             let segment_stream =
                                 unsafe { segment_stream.take().unwrap_unchecked() };
                             let (block_parser, leftover, err) = segment_stream.take_inner().drop();
@@ -117,6 +133,23 @@ impl<
     }
 }
 
+impl<'a, TBlockStack: Stack<block::StackEntry>, TInlineStack: Stack<inline::StackEntry>>
+    BlockEventStreamInlineSegmentMapper<'a, block::Parser<'a, TBlockStack>, TInlineStack>
+{
+    /// 取走目前为止收集到的块级阶段与行内阶段的诊断信息，按其范围的开头排序。
+    ///
+    /// 应在迭代结束之后调用。
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        let mut diagnostics = self.inline_diagnostics.take();
+        if let State::Normal(Some(block_parser)) = &mut self.state {
+            diagnostics.extend(block_parser.take_diagnostics());
+        }
+        diagnostics.sort_by_key(|diagnostic| diagnostic.range.start);
+
+        diagnostics
+    }
+}
+
 impl<
         // 承载的事件属于 `Block` 分组。
         TBlockParser: Iterator<Item = crate::Result<Event>>,
@@ -18,3 +18,140 @@ fn it_works() {
     let ctx = Context::new();
     test_suites::blend::run(&ctx);
 }
+
+#[cfg(feature = "source-span")]
+#[test]
+fn it_attaches_source_spans_to_structural_events() {
+    use crate::events::EventType;
+
+    let table: &[(&str, &[&str])] = &[
+        ("a", &["EnterParagraph ", "ExitBlock "]),
+        ("= a =", &["EnterHeading1 =", "ExitBlock ="]),
+        ("== a", &["EnterHeading2 ==", "ExitBlock "]),
+        ("---", &["ThematicBreak ---"]),
+        (
+            "> a",
+            &[
+                "EnterBlockQuote >",
+                "EnterParagraph ",
+                "ExitBlock ",
+                "ExitBlock ",
+            ],
+        ),
+        (
+            "* a",
+            &[
+                "EnterUnorderedList ",
+                "EnterListItem *",
+                "EnterParagraph ",
+                "ExitBlock ",
+                "ExitBlock ",
+                "ExitBlock ",
+            ],
+        ),
+        (
+            "```js\na\n```",
+            &[
+                "EnterCodeBlock ```",
+                "IndicateCodeBlockCode ",
+                "ExitBlock ```",
+            ],
+        ),
+        (
+            "{|\n|+ a\n|-\n!! b || c\n|}",
+            &[
+                "EnterTable {|",
+                "IndicateTableCaption |+",
+                "EnterParagraph ",
+                "ExitBlock ",
+                "IndicateTableRow |-",
+                "IndicateTableHeaderCell !!",
+                "EnterParagraph ",
+                "ExitBlock ",
+                "IndicateTableDataCell ||",
+                "EnterParagraph ",
+                "ExitBlock ",
+                "ExitBlock |}",
+            ],
+        ),
+        (
+            "{{#Note??x=1||a\n}}",
+            &[
+                "EnterBlockExtension {{#Note",
+                "IndicateExtensionParameter ??",
+                "IndicateBlockExtensionSlot ||",
+                "EnterParagraph ",
+                "ExitBlock ",
+                "ExitBlock }}",
+            ],
+        ),
+        (
+            "['a [~b']",
+            &[
+                "EnterParagraph ",
+                "EnterStrong ['",
+                "EnterStrikethrough [~",
+                "ExitInline ",
+                "ExitInline ']",
+                "ExitBlock ",
+            ],
+        ),
+        (
+            "[``a``] [`b",
+            &[
+                "EnterParagraph ",
+                "EnterCodeSpan [``",
+                "ExitInline ``]",
+                "EnterCodeSpan [`",
+                "ExitInline ",
+                "ExitBlock ",
+            ],
+        ),
+        (
+            "[[a]] [[b|c]]",
+            &[
+                "EnterParagraph ",
+                "EnterWikiLink [[",
+                "ExitInline ]]",
+                "EnterWikiLink [[",
+                "ExitInline ]]",
+                "ExitBlock ",
+            ],
+        ),
+        (
+            "[{#Ext?p=1|a}]",
+            &[
+                "EnterParagraph ",
+                "EnterInlineExtension [{#Ext",
+                "IndicateExtensionParameter ?",
+                "IndicateInlineExtensionSlot |",
+                "ExitInline }]",
+                "ExitBlock ",
+            ],
+        ),
+        (
+            "[=1]",
+            &[
+                "EnterParagraph ",
+                "EnterDicexp [=",
+                "ExitInline ]",
+                "ExitBlock ",
+            ],
+        ),
+    ];
+
+    for (input, expected) in table {
+        let actual: Vec<_> = crate::parse(input.as_bytes())
+            .map(|ev| ev.unwrap())
+            .filter_map(|ev| {
+                let span = ev.span()?;
+                Some(format!(
+                    "{:?} {}",
+                    EventType::from(ev.discriminant()),
+                    &input[span.range()]
+                ))
+            })
+            .collect();
+        assert_eq!(expected, &actual, "input: {:?}", input);
+    }
+}
@@ -4,12 +4,15 @@ mod tests;
 use crate::{
     block::{
         ev,
+        parser_inner::ParserInner,
         types::{CursorContext, YieldContext},
+        StackEntry,
     },
     common::m,
+    diagnostics::DiagnosticCode,
     events::VerbatimEscaping,
     types::Tym,
-    utils::internal::string::count_continuous_character,
+    utils::{internal::string::count_continuous_character, stack::Stack},
 };
 
 #[derive(Debug, PartialEq, Eq)]
@@ -52,12 +55,11 @@ fn parse_verbatim_escaping<TCtx: CursorContext>(input: &[u8], ctx: &mut TCtx) ->
     let backticks = "`".len() + count;
 
     let mut start = ctx.cursor();
-    let has_leading_space = if let Some(&char) = input.get(start) {
+    // 开头的字符若是换行，需要留给之后的循环处理，以便计入行数。
+    let has_leading_space = input.get(start) == Some(&b' ');
+    if has_leading_space {
         ctx.move_cursor_forward(1);
-        char == b' '
-    } else {
-        false
-    };
+    }
 
     let mut continuous_backticks = 0;
     while let Some(char) = input.get(ctx.cursor()).copied() {
@@ -131,19 +133,25 @@ fn parse_comment<TCtx: CursorContext>(input: &[u8], ctx: &mut TCtx) {
             }
             b'\r' | b'\n' => {
                 ctx.increase_current_line();
-                ctx.move_cursor_forward(1);
                 if char == b'\r' && matches!(input.get(ctx.cursor() + 1), Some(b'\n')) {
                     ctx.move_cursor_forward(1);
                 }
+                ctx.move_cursor_forward(1);
             }
             _ => ctx.move_cursor_forward(1),
         }
     }
 }
 
-pub fn process_verbatim_escaping<TCtx: YieldContext>(
-    ctx: &mut TCtx,
+pub fn process_verbatim_escaping<TStack: Stack<StackEntry>>(
+    inner: &mut ParserInner<TStack>,
     verbatim_escaping: VerbatimEscaping,
 ) -> Tym<1> {
-    ctx.r#yield(ev!(Block, VerbatimEscaping(verbatim_escaping)))
+    if verbatim_escaping.is_closed_forcedly {
+        inner.diagnostics.report(
+            DiagnosticCode::UnclosedVerbatimEscaping,
+            verbatim_escaping.content.clone(),
+        );
+    }
+    inner.r#yield(ev!(Block, VerbatimEscaping(verbatim_escaping)))
 }
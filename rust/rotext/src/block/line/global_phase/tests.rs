@@ -73,6 +73,15 @@ mod for_fn_parse {
                 current_line: LineNumber::new_universal(1),
             },
         );
+
+        test(
+            b"<%a\r\n\nb%>",
+            Some(Output::None),
+            MockCursorContext {
+                cursor: 9,
+                current_line: LineNumber::new_universal(3),
+            },
+        );
     }
 
     #[test]
@@ -324,6 +333,18 @@ mod for_fn_parse_verbatim_escaping {
                 current_line: LineNumber::new_universal(2),
             },
         );
+        test(
+            b"\nfoo`>",
+            VerbatimEscaping {
+                content: 0..4,
+                is_closed_forcedly: false,
+                line_after: LineNumber::new_universal(2),
+            },
+            MockCursorContext {
+                cursor: 6,
+                current_line: LineNumber::new_universal(2),
+            },
+        );
         test(
             b"``foo`\n```>",
             VerbatimEscaping {
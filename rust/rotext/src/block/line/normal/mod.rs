@@ -4,7 +4,10 @@ mod tests;
 use std::ops::Range;
 
 use crate::{
-    block::{branch::braced::table, types::CursorContext},
+    block::{
+        branch::braced::{block_extension, table},
+        types::CursorContext,
+    },
     events::{NewLine, VerbatimEscaping},
     utils::internal::string::count_continuous_character_with_maximum,
 };
@@ -15,6 +18,7 @@ use super::{global_phase, parse_common_end, CommonEnd, ParseCommonEndOutput};
 pub struct EndCondition {
     pub on_atx_closing: Option<AtxClosing>,
     pub on_table_related: Option<TableRelated>,
+    pub on_block_extension_related: Option<BlockExtensionRelated>,
 }
 /// 类似于 CommonMark 中 ATX 风格的 Headings 中的闭合部分，位于空格之后，外部结构的结
 /// 尾之前（除了常规的换行和文档结束，“结尾” 还可能是 [braced] 的闭合部分），标记都
@@ -30,6 +34,11 @@ pub struct AtxClosing {
 pub struct TableRelated {
     pub is_caption_applicable: bool,
 }
+#[derive(Debug, Clone)]
+pub struct BlockExtensionRelated {
+    /// 是否处于块级扩展的头部之中。若是，`??` 也会被视为终止界限。
+    pub is_in_header: bool,
+}
 
 /// [parse] 结束解析的原因。
 ///
@@ -41,6 +50,7 @@ pub enum End {
     NewLine(Option<NewLine>),
     VerbatimEscaping(VerbatimEscaping),
     TableRelated(table::TableRelatedEnd),
+    BlockExtensionRelated(block_extension::BlockExtensionRelatedEnd),
     None,
 }
 impl From<table::TableRelatedEnd> for End {
@@ -48,6 +58,11 @@ impl From<table::TableRelatedEnd> for End {
         Self::TableRelated(value)
     }
 }
+impl From<block_extension::BlockExtensionRelatedEnd> for End {
+    fn from(value: block_extension::BlockExtensionRelatedEnd) -> Self {
+        Self::BlockExtensionRelated(value)
+    }
+}
 impl From<CommonEnd> for End {
     fn from(value: CommonEnd) -> Self {
         match value {
@@ -181,7 +196,7 @@ pub fn parse<TCtx: CursorContext>(
 
     match end {
         End::VerbatimEscaping(_) | End::None => range.end += spaces,
-        End::Eof | End::NewLine(_) | End::TableRelated(_) => {}
+        End::Eof | End::NewLine(_) | End::TableRelated(_) | End::BlockExtensionRelated(_) => {}
     }
 
     (range, end)
@@ -195,7 +210,8 @@ struct ParseFollwingEndThatCanCloseHeadingOutput {
 ///
 /// “紧随” 是指中间最多只能有空格相隔。
 ///
-/// 可以闭合当前 Heading 的 [End] 有 [CommonEnd] 与 [table::TableRelatedEnd]。
+/// 可以闭合当前 Heading 的 [End] 有 [CommonEnd]、[table::TableRelatedEnd] 与
+/// [block_extension::BlockExtensionRelatedEnd]。
 fn parse_following_end_that_can_close_heading<TCtx: CursorContext>(
     input: &[u8],
     ctx: &mut TCtx,
@@ -243,7 +259,7 @@ struct ParseEndWhenConfirmedNoAtxClosingOptions {
 }
 /// 确定了没有 ATX Heading 闭合部分之后进行的接下来的解析。
 ///
-/// 目前只可能返回 [End::TableRelated]。
+/// 目前只可能返回 [End::TableRelated] 或 [End::BlockExtensionRelated]。
 fn parse_end_when_confirmed_no_atx_closing<TCtx: CursorContext>(
     input: &[u8],
     ctx: &mut TCtx,
@@ -257,6 +273,13 @@ fn parse_end_when_confirmed_no_atx_closing<TCtx: CursorContext>(
             return Some(End::TableRelated(end));
         }
     }
+    if let Some(end_condition) = &end_condition.on_block_extension_related {
+        if let Some(end) =
+            block_extension::parse_end(input, ctx, first_char, end_condition.is_in_header)
+        {
+            return Some(End::BlockExtensionRelated(end));
+        }
+    }
 
     None
 }
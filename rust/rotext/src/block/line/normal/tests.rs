@@ -1,8 +1,11 @@
 mod for_fn_parse {
     use crate::{
         block::{
-            branch::braced::table,
-            line::normal::{parse, AtxClosing, ContentBefore, End, EndCondition, TableRelated},
+            branch::braced::{block_extension, table},
+            line::normal::{
+                parse, AtxClosing, BlockExtensionRelated, ContentBefore, End, EndCondition,
+                TableRelated,
+            },
             test_support::mocks::MockCursorContext,
         },
         common::m,
@@ -40,6 +43,7 @@ mod for_fn_parse {
         let end_condition = EndCondition {
             on_atx_closing: None,
             on_table_related: None,
+            on_block_extension_related: None,
         };
 
         test(
@@ -134,6 +138,7 @@ mod for_fn_parse {
         let end_condition = EndCondition {
             on_atx_closing: None,
             on_table_related: None,
+            on_block_extension_related: None,
         };
 
         for input in [&b"<`VE`>"[..], &b"<`VE`>after"[..]] {
@@ -209,6 +214,7 @@ mod for_fn_parse {
         let end_condition = EndCondition {
             on_atx_closing: None,
             on_table_related: None,
+            on_block_extension_related: None,
         };
 
         for input in [&b"<%C%>"[..], &b"<%C%>after"[..]] {
@@ -264,6 +270,7 @@ mod for_fn_parse {
         let end_condition = EndCondition {
             on_atx_closing: None,
             on_table_related: None,
+            on_block_extension_related: None,
         };
 
         test(
@@ -287,6 +294,7 @@ mod for_fn_parse {
                 count: 2,
             }),
             on_table_related: None,
+            on_block_extension_related: None,
         };
 
         test(
@@ -393,6 +401,7 @@ mod for_fn_parse {
                 count: 2,
             }),
             on_table_related: None,
+            on_block_extension_related: None,
         };
 
         test(
@@ -418,6 +427,7 @@ mod for_fn_parse {
         let end_condition = EndCondition {
             on_atx_closing: None,
             on_table_related: None,
+            on_block_extension_related: None,
         };
 
         test(
@@ -440,6 +450,7 @@ mod for_fn_parse {
             on_table_related: Some(TableRelated {
                 is_caption_applicable: false,
             }),
+            on_block_extension_related: None,
         };
 
         test(
@@ -528,6 +539,7 @@ mod for_fn_parse {
             on_table_related: Some(TableRelated {
                 is_caption_applicable: true,
             }),
+            on_block_extension_related: None,
         };
 
         test(
@@ -543,6 +555,72 @@ mod for_fn_parse {
         );
     }
 
+    #[test]
+    fn it_can_process_block_extension_related_ends() {
+        let end_condition = EndCondition {
+            on_atx_closing: None,
+            on_table_related: None,
+            on_block_extension_related: Some(BlockExtensionRelated {
+                is_in_header: false,
+            }),
+        };
+
+        test(
+            b"foo }}",
+            end_condition.clone(),
+            0,
+            b"foo",
+            block_extension::BlockExtensionRelatedEnd::Closing.into(),
+            MockCursorContext {
+                cursor: 6,
+                current_line: LineNumber::new_universal(1),
+            },
+        );
+        test(
+            b"foo||",
+            end_condition.clone(),
+            0,
+            b"foo",
+            block_extension::BlockExtensionRelatedEnd::DoublePipes.into(),
+            MockCursorContext {
+                cursor: 5,
+                current_line: LineNumber::new_universal(1),
+            },
+        );
+        test(
+            b"foo??",
+            end_condition.clone(),
+            0,
+            b"foo??",
+            End::Eof,
+            MockCursorContext {
+                cursor: 5,
+                current_line: LineNumber::new_universal(1),
+            },
+        );
+    }
+
+    #[test]
+    fn it_can_process_block_extension_parameter_indicators_if_in_header() {
+        let end_condition = EndCondition {
+            on_atx_closing: None,
+            on_table_related: None,
+            on_block_extension_related: Some(BlockExtensionRelated { is_in_header: true }),
+        };
+
+        test(
+            b"foo ??",
+            end_condition.clone(),
+            0,
+            b"foo",
+            block_extension::BlockExtensionRelatedEnd::ParameterIndicator.into(),
+            MockCursorContext {
+                cursor: 6,
+                current_line: LineNumber::new_universal(1),
+            },
+        );
+    }
+
     #[test]
     fn it_can_process_atx_closing_ends_followed_by_table_related_end() {
         let end_condition = EndCondition {
@@ -553,6 +631,7 @@ mod for_fn_parse {
             on_table_related: Some(TableRelated {
                 is_caption_applicable: true,
             }),
+            on_block_extension_related: None,
         };
 
         test(
@@ -14,8 +14,9 @@ pub use stack_wrapper::StackEntry;
 
 use crate::{
     common::m,
-    events::{ev, ThematicBreak},
-    types::{cast_tym, Tym, TYM_UNIT},
+    diagnostics::{Diagnostic, DiagnosticCode},
+    events::{ev, ExtensionParameter, Indication, TableCell, ThematicBreak},
+    types::{cast_tym, SourceSpan, Tym, TYM_UNIT},
     utils::{internal::string::count_continuous_character, stack::Stack},
     Event,
 };
@@ -27,8 +28,9 @@ use state::{
 
 use parser_inner::ParserInner;
 use stack_wrapper::{
-    GeneralItemLike, ItemLikeContainer, Meta, StackEntryItemLike, StackEntryItemLikeContainer,
-    StackEntryTable, TopLeaf, TopLeafCodeBlock, TopLeafHeading, TopLeafParagraph,
+    BlockExtensionHeaderState, GeneralItemLike, ItemLikeContainer, Meta, StackEntryBlockExtension,
+    StackEntryItemLike, StackEntryItemLikeContainer, StackEntryTable, TopLeaf, TopLeafCodeBlock,
+    TopLeafHeading, TopLeafParagraph,
 };
 use types::{CursorContext, YieldContext};
 
@@ -47,6 +49,18 @@ pub struct Parser<'a, TStack: Stack<StackEntry>> {
     is_errored: bool,
 }
 
+/// 块级阶段解析器开始解析的位置，用于增量解析。
+#[cfg(feature = "incremental")]
+#[derive(Debug, Clone, Copy)]
+pub struct StartingPoint {
+    /// 开始解析的位置，应位于行首。
+    pub cursor: usize,
+    /// `cursor` 所在的行号。
+    pub line: usize,
+    /// 已经分配出去的最大的块级元素 ID。（之后的 ID 从下一个开始分配。）
+    pub last_block_id: usize,
+}
+
 impl<'a, TStack: Stack<StackEntry>> Parser<'a, TStack> {
     pub fn new(input: &'a [u8]) -> Self {
         Self {
@@ -60,6 +74,25 @@ impl<'a, TStack: Stack<StackEntry>> Parser<'a, TStack> {
         }
     }
 
+    /// 从 `point` 开始解析 `input`，就像 `point.cursor` 之前的内容不存在一样。
+    #[cfg(feature = "incremental")]
+    pub fn new_at(input: &'a [u8], point: StartingPoint) -> Self {
+        Self {
+            inner: ParserInner::new_at(point),
+            ..Self::new(input)
+        }
+    }
+
+    /// 开始收集诊断信息（见 [crate::diagnostics]）。
+    pub fn enable_diagnostics(&mut self) {
+        self.inner.diagnostics.enable();
+    }
+
+    /// 取走目前为止收集到的诊断信息。
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        self.inner.diagnostics.take()
+    }
+
     /// 返回的事件属于 `Block` 分组。
     pub fn next(&mut self) -> Option<crate::Result<Event>> {
         #[cfg(debug_assertions)]
@@ -153,6 +186,16 @@ impl<'a, TStack: Stack<StackEntry>> Parser<'a, TStack> {
                         self.state = expecting.into();
                         continue;
                     }
+                    if !self
+                        .item_likes_state
+                        .has_unprocessed_item_likes_at_current_line()
+                        && self.inner.stack.block_extension_header().is_some()
+                    {
+                        // 块级扩展的头部可以跨越多行，头部中不会出现 item-like。
+                        expecting = Expecting::BracedOpening;
+                        self.state = expecting.into();
+                        continue;
+                    }
                     break branch::item_like::parse_opening_and_process(
                         self.input,
                         &mut self.state,
@@ -206,7 +249,7 @@ impl<'a, TStack: Stack<StackEntry>> Parser<'a, TStack> {
         if let Some(top_leaf) = inner.stack.pop_top_leaf() {
             let tym: Tym<2> = match top_leaf {
                 TopLeaf::Paragraph(top_leaf) => leaf::paragraph::exit(inner, top_leaf).into(),
-                TopLeaf::Heading(top_leaf) => leaf::heading::exit(inner, top_leaf).into(),
+                TopLeaf::Heading(top_leaf) => leaf::heading::exit(inner, top_leaf, None).into(),
                 TopLeaf::CodeBlock(top_leaf) => leaf::code_block::exit(inner, top_leaf),
             };
             return Ok((cast_tym!(tym), None));
@@ -229,10 +272,29 @@ impl<'a, TStack: Stack<StackEntry>> Parser<'a, TStack> {
             }
             ExitingUntil::TopIsTable {
                 should_also_exit_table,
-            } => (inner.stack.top_is_table(), should_also_exit_table),
+            } => {
+                let is_done = inner.stack.top_is_table();
+                (is_done, !is_done || should_also_exit_table)
+            }
+            ExitingUntil::TopIsBlockExtension {
+                should_also_exit_block_extension,
+            } => {
+                let is_done = inner.stack.top_is_block_extension();
+                (is_done, !is_done || should_also_exit_block_extension)
+            }
             ExitingUntil::TopIsAwareOfDoublePipes => {
+                let span = inner.span_before_cursor("||".len());
                 if inner.stack.top_is_table() {
-                    to_be_yielded_based_on_context = Some(ev!(Block, IndicateTableDataCell));
+                    let attributes = inner.take_attributes();
+                    to_be_yielded_based_on_context = Some(ev!(
+                        Block,
+                        IndicateTableDataCell(TableCell { attributes, span })
+                    ));
+                    (true, false)
+                } else if inner.stack.top_is_block_extension() {
+                    inner.stack.set_block_extension_header(None);
+                    to_be_yielded_based_on_context =
+                        Some(ev!(Block, IndicateBlockExtensionSlot(Indication { span })));
                     (true, false)
                 } else {
                     (false, true)
@@ -250,7 +312,39 @@ impl<'a, TStack: Stack<StackEntry>> Parser<'a, TStack> {
                 StackEntry::ItemLikeContainer(stack_entry) => {
                     branch::item_like::exit_container(inner, stack_entry)?
                 }
-                StackEntry::Table(stack_entry) => branch::braced::table::exit(inner, stack_entry)?,
+                StackEntry::Table(stack_entry) => {
+                    let closing = if matches!(
+                        exiting.until,
+                        ExitingUntil::TopIsTable {
+                            should_also_exit_table: true
+                        }
+                    ) {
+                        inner.span_before_cursor("|}".len())
+                    } else {
+                        inner
+                            .diagnostics
+                            .report(DiagnosticCode::UnclosedTable, stack_entry.opening.clone());
+                        inner.span_after_last_yielded()
+                    };
+                    branch::braced::table::exit(inner, stack_entry, closing)?
+                }
+                StackEntry::BlockExtension(stack_entry) => {
+                    let closing = if matches!(
+                        exiting.until,
+                        ExitingUntil::TopIsBlockExtension {
+                            should_also_exit_block_extension: true
+                        }
+                    ) {
+                        inner.span_before_cursor("}}".len())
+                    } else {
+                        inner.diagnostics.report(
+                            DiagnosticCode::UnclosedBlockExtension,
+                            stack_entry.opening.clone(),
+                        );
+                        inner.span_after_last_yielded()
+                    };
+                    branch::braced::block_extension::exit(inner, stack_entry, closing)?
+                }
             }
         } else {
             TYM_UNIT.into()
@@ -325,6 +419,8 @@ impl<TStack: Stack<StackEntry>> Iterator for Parser<'_, TStack> {
 }
 
 mod branch {
+    use std::ops::Range;
+
     use super::*;
 
     pub mod item_like {
@@ -340,24 +436,30 @@ mod branch {
             use GeneralItemLike as I;
             use ItemLikeContainer as G;
 
+            if first_char == m!('{') && block_attributes::parse_and_record(input, inner) {
+                return Ok(TYM_UNIT.into());
+            }
+
+            let marker = inner.cursor()..(inner.cursor() + 1);
             match first_char {
                 m!('>') if is_indeed_opening_and_consume_if_true(input, inner) => {
-                    process_greater_than_opening(inner, item_likes_state).map(|tym| cast_tym!(tym))
+                    process_greater_than_opening(inner, item_likes_state, marker)
+                        .map(|tym| cast_tym!(tym))
                 }
                 m!('#') if is_indeed_opening_and_consume_if_true(input, inner) => {
-                    process_general_opening(state, inner, item_likes_state, G::OL, I::LI)
+                    process_general_opening(state, inner, item_likes_state, G::OL, I::LI, marker)
                         .map(|tym| cast_tym!(tym))
                 }
                 m!('*') if is_indeed_opening_and_consume_if_true(input, inner) => {
-                    process_general_opening(state, inner, item_likes_state, G::UL, I::LI)
+                    process_general_opening(state, inner, item_likes_state, G::UL, I::LI, marker)
                         .map(|tym| cast_tym!(tym))
                 }
                 m!(';') if is_indeed_opening_and_consume_if_true(input, inner) => {
-                    process_general_opening(state, inner, item_likes_state, G::DL, I::DT)
+                    process_general_opening(state, inner, item_likes_state, G::DL, I::DT, marker)
                         .map(|tym| cast_tym!(tym))
                 }
                 m!(':') if is_indeed_opening_and_consume_if_true(input, inner) => {
-                    process_general_opening(state, inner, item_likes_state, G::DL, I::DD)
+                    process_general_opening(state, inner, item_likes_state, G::DL, I::DD, marker)
                         .map(|tym| cast_tym!(tym))
                 }
                 _ => match item_likes_state {
@@ -381,9 +483,11 @@ mod branch {
             }
         }
 
+        /// `marker` 是 `>` 在输入中的范围。
         fn process_greater_than_opening<TStack: Stack<StackEntry>>(
             inner: &mut ParserInner<TStack>,
             item_likes_state: &mut ItemLikesState,
+            marker: Range<usize>,
         ) -> crate::Result<Tym<1>> {
             let tym = match item_likes_state {
                 ItemLikesState::MatchingLastLine(matching_last_line) => {
@@ -397,26 +501,32 @@ mod branch {
                     TYM_UNIT.into()
                 }
                 ItemLikesState::ProcessingNew => {
-                    let id = inner.pop_block_id();
-                    inner
-                        .stack
-                        .push_item_like_container(StackEntryItemLikeContainer {
-                            meta: Meta::new(id, inner.current_line()),
-                            r#type: ItemLikeContainer::BlockQuote,
-                        })?;
-                    inner.r#yield(ev!(Block, EnterBlockQuote(id.into())))
+                    let stack_entry = StackEntryItemLikeContainer {
+                        meta: Meta::new(
+                            inner.pop_block_id(),
+                            inner.current_line(),
+                            inner.take_attributes(),
+                            SourceSpan::new_universal(marker),
+                        ),
+                        r#type: ItemLikeContainer::BlockQuote,
+                    };
+                    let ev = stack_entry.make_enter_event();
+                    inner.stack.push_item_like_container(stack_entry)?;
+                    inner.r#yield(ev)
                 }
             };
 
             Ok(tym)
         }
 
+        /// `marker` 是 item-like 的标记（如 `#`）在输入中的范围。
         fn process_general_opening<TStack: Stack<StackEntry>>(
             state: &mut State,
             inner: &mut ParserInner<TStack>,
             item_likes_state: &mut ItemLikesState,
             container: ItemLikeContainer,
             item_like: GeneralItemLike,
+            marker: Range<usize>,
         ) -> crate::Result<Tym<2>> {
             let tym = match item_likes_state {
                 ItemLikesState::MatchingLastLine(matching_last_line) => {
@@ -434,7 +544,9 @@ mod branch {
                             ExitingAndThen::EnterItemLikeAndExpectItemLike {
                                 container: None,
                                 item_like: make_stack_entry_from_general_item_like(
-                                    item_like, inner,
+                                    item_like,
+                                    inner,
+                                    marker.clone(),
                                 ),
                             },
                         )
@@ -447,10 +559,14 @@ mod branch {
                             },
                             ExitingAndThen::EnterItemLikeAndExpectItemLike {
                                 container: Some(make_stack_entry_from_item_like_container(
-                                    container, inner,
+                                    container,
+                                    inner,
+                                    marker.start,
                                 )),
                                 item_like: make_stack_entry_from_general_item_like(
-                                    item_like, inner,
+                                    item_like,
+                                    inner,
+                                    marker.clone(),
                                 ),
                             },
                         )
@@ -460,14 +576,18 @@ mod branch {
                 }
                 ItemLikesState::ProcessingNew => {
                     let tym_a = {
-                        let stack_entry =
-                            make_stack_entry_from_item_like_container(container, inner);
+                        let stack_entry = make_stack_entry_from_item_like_container(
+                            container,
+                            inner,
+                            marker.start,
+                        );
                         let ev = stack_entry.make_enter_event();
                         inner.stack.push_item_like_container(stack_entry)?;
                         inner.r#yield(ev)
                     };
                     let tym_b = {
-                        let stack_entry = make_stack_entry_from_general_item_like(item_like, inner);
+                        let stack_entry =
+                            make_stack_entry_from_general_item_like(item_like, inner, marker);
                         let ev = stack_entry.make_enter_event();
                         inner.stack.push_item_like(stack_entry)?;
                         inner.r#yield(ev)
@@ -497,19 +617,33 @@ mod branch {
         fn make_stack_entry_from_general_item_like<TStack: Stack<StackEntry>>(
             item_like: GeneralItemLike,
             inner: &mut ParserInner<TStack>,
+            marker: Range<usize>,
         ) -> StackEntryItemLike {
             StackEntryItemLike {
-                meta: Meta::new(inner.pop_block_id(), inner.current_line()),
+                meta: Meta::new(
+                    inner.pop_block_id(),
+                    inner.current_line(),
+                    inner.take_attributes_if(true),
+                    SourceSpan::new_universal(marker),
+                ),
                 r#type: item_like,
             }
         }
 
+        /// 容器本身没有标记，其范围是位于 `marker_start` 的（第一项的标记开头处的）
+        /// 空范围。
         fn make_stack_entry_from_item_like_container<TStack: Stack<StackEntry>>(
             item_like: ItemLikeContainer,
             inner: &mut ParserInner<TStack>,
+            marker_start: usize,
         ) -> StackEntryItemLikeContainer {
             StackEntryItemLikeContainer {
-                meta: Meta::new(inner.pop_block_id(), inner.current_line()),
+                meta: Meta::new(
+                    inner.pop_block_id(),
+                    inner.current_line(),
+                    inner.take_attributes_if(false),
+                    SourceSpan::new_universal(marker_start..marker_start),
+                ),
                 r#type: item_like,
             }
         }
@@ -518,7 +652,9 @@ mod branch {
             inner: &mut ParserInner<TStack>,
             stack_entry: StackEntryItemLikeContainer,
         ) -> crate::Result<Tym<1>> {
-            let tym = inner.r#yield(stack_entry.make_exit_event(inner.current_line()));
+            let tym = inner.r#yield(
+                stack_entry.make_exit_event(inner.current_line(), inner.span_after_last_yielded()),
+            );
 
             Ok(tym)
         }
@@ -527,7 +663,9 @@ mod branch {
             inner: &mut ParserInner<TStack>,
             stack_entry: StackEntryItemLike,
         ) -> crate::Result<Tym<1>> {
-            let tym = inner.r#yield(stack_entry.make_exit_event(inner.current_line()));
+            let tym = inner.r#yield(
+                stack_entry.make_exit_event(inner.current_line(), inner.span_after_last_yielded()),
+            );
 
             Ok(tym)
         }
@@ -542,12 +680,24 @@ mod branch {
             inner: &mut ParserInner<TStack>,
             first_char: u8,
         ) -> crate::Result<Tym<3>> {
+            if let Some(header) = inner.stack.block_extension_header() {
+                return block_extension::parse_header_and_process(
+                    input, state, inner, header, first_char,
+                );
+            }
+
             match first_char {
                 m!('{') => match input.get(inner.cursor() + 1) {
+                    Some(m!('!')) if block_attributes::parse_and_record(input, inner) => {
+                        Ok(TYM_UNIT.into())
+                    }
                     Some(m!('|')) => {
                         inner.move_cursor_forward("{|".len());
                         table::enter(state, inner).map(|tym| cast_tym!(tym))
                     }
+                    Some(m!('{')) => {
+                        block_extension::parse_opening_and_process(input, state, inner)
+                    }
                     _ => leaf::paragraph::enter_if_not_blank(input, state, inner, 1)
                         .map(|tym| cast_tym!(tym)),
                 },
@@ -566,8 +716,15 @@ mod branch {
                 *state = Expecting::BracedOpening.into();
 
                 let id = inner.pop_block_id();
+                let opening = (inner.cursor() - "{|".len())..inner.cursor();
                 let stack_entry = StackEntryTable {
-                    meta: Meta::new(id, inner.current_line()),
+                    meta: Meta::new(
+                        id,
+                        inner.current_line(),
+                        inner.take_attributes(),
+                        SourceSpan::new_universal(opening.clone()),
+                    ),
+                    opening,
                 };
                 let ev = stack_entry.make_enter_event();
                 inner.stack.push_table(stack_entry)?;
@@ -585,7 +742,25 @@ mod branch {
                 DoublePipes,
             }
             impl TableRelatedEnd {
-                pub fn process(self, state: &mut State) -> Tym<0> {
+                pub fn process<TStack: Stack<StackEntry>>(
+                    self,
+                    state: &mut State,
+                    inner: &mut ParserInner<TStack>,
+                ) -> Tym<0> {
+                    // 此时 cursor 位于指示标记之后，而各指示标记的长度都是 2。
+                    let span = inner.span_before_cursor(2);
+                    // 此时尚未被取走的块级附加信息附着于新的单元格。对于其他的情况，
+                    // 块级附加信息无处附着，直接丢弃。（数据单元格的事件在退出后才产
+                    // 出，届时再取走附加信息。）
+                    let attributes = match self {
+                        TableRelatedEnd::TableHeaderCellIndicator => inner.take_attributes(),
+                        TableRelatedEnd::DoublePipes => None,
+                        _ => {
+                            inner.take_attributes();
+                            None
+                        }
+                    };
+
                     *state = match self {
                         TableRelatedEnd::TableClosing => Exiting::new(
                             ExitingUntil::TopIsTable {
@@ -600,7 +775,7 @@ mod branch {
                             },
                             ExitingAndThen::YieldAndExpectBracedOpening(ev!(
                                 Block,
-                                IndicateTableCaption
+                                IndicateTableCaption(Indication { span })
                             )),
                         )
                         .into(),
@@ -610,7 +785,7 @@ mod branch {
                             },
                             ExitingAndThen::YieldAndExpectBracedOpening(ev!(
                                 Block,
-                                IndicateTableRow
+                                IndicateTableRow(Indication { span })
                             )),
                         )
                         .into(),
@@ -620,7 +795,7 @@ mod branch {
                             },
                             ExitingAndThen::YieldAndExpectBracedOpening(ev!(
                                 Block,
-                                IndicateTableHeaderCell
+                                IndicateTableHeaderCell(TableCell { attributes, span })
                             )),
                         )
                         .into(),
@@ -660,11 +835,13 @@ mod branch {
                 Some(end)
             }
 
+            /// `closing` 是闭合部分（`|}`）在输入中的范围。
             pub fn exit<TStack: Stack<StackEntry>>(
                 inner: &mut ParserInner<TStack>,
                 stack_entry: StackEntryTable,
+                closing: SourceSpan,
             ) -> crate::Result<Tym<1>> {
-                let tym = inner.r#yield(stack_entry.make_exit_event(inner.current_line()));
+                let tym = inner.r#yield(stack_entry.make_exit_event(inner.current_line(), closing));
 
                 Ok(tym)
             }
@@ -682,10 +859,359 @@ mod branch {
                 }
             }
         }
+
+        pub mod block_extension {
+            use super::*;
+
+            /// 调用者应保证 `inner.cursor()` 位于 `{{` 的开头。
+            pub(super) fn parse_opening_and_process<TStack: Stack<StackEntry>>(
+                input: &[u8],
+                state: &mut State,
+                inner: &mut ParserInner<TStack>,
+            ) -> crate::Result<Tym<3>> {
+                let mut cursor = inner.cursor() + "{{".len();
+                cursor += count_continuous_character(input, b' ', cursor);
+                if input.get(cursor) != Some(&m!('#')) {
+                    return leaf::paragraph::enter_if_not_blank(input, state, inner, 1);
+                }
+                cursor += "#".len();
+
+                let name = trim_spaces(input, cursor..find_end_of_header_item(input, cursor));
+                if name.is_empty() {
+                    return leaf::paragraph::enter_if_not_blank(input, state, inner, 1);
+                }
+                let opening = inner.cursor()..name.end;
+                inner.move_cursor_forward(name.end - inner.cursor());
+
+                enter(state, inner, opening, name).map(|tym| cast_tym!(tym))
+            }
+
+            fn enter<TStack: Stack<StackEntry>>(
+                state: &mut State,
+                inner: &mut ParserInner<TStack>,
+                opening: Range<usize>,
+                name: Range<usize>,
+            ) -> crate::Result<Tym<1>> {
+                *state = Expecting::BracedOpening.into();
+
+                let id = inner.pop_block_id();
+                let stack_entry = StackEntryBlockExtension {
+                    meta: Meta::new(
+                        id,
+                        inner.current_line(),
+                        inner.take_attributes(),
+                        SourceSpan::new_universal(opening.clone()),
+                    ),
+                    opening,
+                    name,
+                };
+                let ev = stack_entry.make_enter_event();
+                inner.stack.push_block_extension(stack_entry)?;
+                let tym = inner.r#yield(ev);
+
+                Ok(tym)
+            }
+
+            /// 处理块级扩展头部中的一项内容。
+            ///
+            /// 调用者应保证栈顶是处于头部之中的块级扩展。
+            pub(super) fn parse_header_and_process<TStack: Stack<StackEntry>>(
+                input: &[u8],
+                state: &mut State,
+                inner: &mut ParserInner<TStack>,
+                header: BlockExtensionHeaderState,
+                first_char: u8,
+            ) -> crate::Result<Tym<3>> {
+                match header {
+                    BlockExtensionHeaderState::ExpectingItem => {
+                        if let Some(end) = parse_end(input, inner, first_char, true) {
+                            let tym = end.process(state, inner);
+                            return Ok(cast_tym!(tym));
+                        }
+
+                        match first_char {
+                            b'\r' | b'\n' => {
+                                inner.move_cursor_forward(1);
+                                if first_char == b'\r' && input.get(inner.cursor()) == Some(&b'\n')
+                                {
+                                    inner.move_cursor_forward(1);
+                                }
+                                inner.increase_current_line();
+                                Ok(TYM_UNIT.into())
+                            }
+                            _ => {
+                                // 没有 `||` 就出现了其他内容，视为隐式地开启了第一个子内容。
+                                inner.stack.set_block_extension_header(None);
+                                let span = inner.span_before_cursor(0);
+                                let tym = inner.r#yield(ev!(
+                                    Block,
+                                    IndicateBlockExtensionSlot(Indication { span })
+                                ));
+                                Ok(cast_tym!(tym))
+                            }
+                        }
+                    }
+                    BlockExtensionHeaderState::ExpectingParameter { indicator } => {
+                        let name = parse_parameter_name(input, inner);
+                        let tym_a = inner.r#yield(ev!(
+                            Block,
+                            IndicateExtensionParameter(ExtensionParameter {
+                                name,
+                                span: indicator
+                            })
+                        ));
+                        let tym_bc = parse_parameter_value_and_process(input, state, inner, false);
+
+                        Ok(tym_a.add(tym_bc))
+                    }
+                    BlockExtensionHeaderState::InParameterValue => {
+                        let tym = parse_parameter_value_and_process(input, state, inner, true);
+                        Ok(cast_tym!(tym))
+                    }
+                }
+            }
+
+            /// 若存在参数名称，返回参数名称，并将 cursor 移至参数值的开头。
+            fn parse_parameter_name<TStack: Stack<StackEntry>>(
+                input: &[u8],
+                inner: &mut ParserInner<TStack>,
+            ) -> Option<Range<usize>> {
+                let end = find_end_of_header_item(input, inner.cursor());
+                let equal_sign = input[inner.cursor()..end]
+                    .iter()
+                    .position(|char| *char == m!('='))
+                    .map(|i| inner.cursor() + i)?;
+                let is_in_global_phase_syntax = input[inner.cursor()..equal_sign]
+                    .windows(2)
+                    .any(|chars| chars[0] == m!('<') && matches!(chars[1], m!('`') | m!('%')));
+                if is_in_global_phase_syntax {
+                    // 逐字转义或注释之中的 `=` 不能分隔参数名称与参数值。
+                    return None;
+                }
+
+                let name = trim_spaces(input, inner.cursor()..equal_sign);
+                if name.is_empty() {
+                    return None;
+                }
+
+                let value_start = equal_sign
+                    + "=".len()
+                    + count_continuous_character(input, b' ', equal_sign + "=".len());
+                inner.move_cursor_forward(value_start - inner.cursor());
+
+                Some(name)
+            }
+
+            fn parse_parameter_value_and_process<TStack: Stack<StackEntry>>(
+                input: &[u8],
+                state: &mut State,
+                inner: &mut ParserInner<TStack>,
+                is_continuing: bool,
+            ) -> Tym<2> {
+                let (mut content, end) = line::normal::parse(
+                    input,
+                    inner,
+                    line::normal::EndCondition {
+                        on_atx_closing: None,
+                        on_table_related: None,
+                        on_block_extension_related: Some(line::normal::BlockExtensionRelated {
+                            is_in_header: true,
+                        }),
+                    },
+                    line::normal::ContentBefore::NotSpace(0),
+                );
+
+                if is_continuing
+                    && inner.current_expecting.spaces_before() > 0
+                    && (!content.is_empty() || end.is_verbatim_escaping())
+                {
+                    // 在参数值的中间，那就不能忽略空格。
+                    content.start -= inner.current_expecting.spaces_before();
+                }
+
+                let tym_a = if !content.is_empty() {
+                    inner.r#yield(ev!(Block, Text(content)))
+                } else {
+                    TYM_UNIT.into()
+                };
+
+                let tym_b = match end {
+                    line::normal::End::Eof | line::normal::End::NewLine(_) => {
+                        inner.stack.set_block_extension_header(Some(
+                            BlockExtensionHeaderState::ExpectingItem,
+                        ));
+                        TYM_UNIT.into()
+                    }
+                    line::normal::End::VerbatimEscaping(verbatim_escaping) => {
+                        inner.stack.set_block_extension_header(Some(
+                            BlockExtensionHeaderState::InParameterValue,
+                        ));
+                        line::global_phase::process_verbatim_escaping(inner, verbatim_escaping)
+                    }
+                    line::normal::End::None => {
+                        inner.stack.set_block_extension_header(Some(
+                            BlockExtensionHeaderState::InParameterValue,
+                        ));
+                        TYM_UNIT.into()
+                    }
+                    line::normal::End::BlockExtensionRelated(end) => {
+                        let tym = end.process(state, inner);
+                        cast_tym!(tym)
+                    }
+                    line::normal::End::TableRelated(_) => unreachable!(),
+                };
+
+                tym_a.add(tym_b)
+            }
+
+            #[derive(Debug, PartialEq, Eq)]
+            pub enum BlockExtensionRelatedEnd {
+                Closing,
+                DoublePipes,
+                /// 只会在块级扩展的头部之中出现。
+                ParameterIndicator,
+            }
+            impl BlockExtensionRelatedEnd {
+                pub fn process<TStack: Stack<StackEntry>>(
+                    self,
+                    state: &mut State,
+                    inner: &mut ParserInner<TStack>,
+                ) -> Tym<0> {
+                    match self {
+                        BlockExtensionRelatedEnd::Closing => {
+                            *state = Exiting::new(
+                                ExitingUntil::TopIsBlockExtension {
+                                    should_also_exit_block_extension: true,
+                                },
+                                ExitingAndThen::ExpectBracedOpening,
+                            )
+                            .into();
+                        }
+                        BlockExtensionRelatedEnd::DoublePipes => {
+                            *state = Exiting::new(
+                                ExitingUntil::TopIsAwareOfDoublePipes,
+                                ExitingAndThen::YieldBasedOnContextAndExpectBracedOpening,
+                            )
+                            .into();
+                        }
+                        BlockExtensionRelatedEnd::ParameterIndicator => {
+                            inner.stack.set_block_extension_header(Some(
+                                BlockExtensionHeaderState::ExpectingParameter {
+                                    indicator: inner.span_before_cursor("??".len()),
+                                },
+                            ));
+                        }
+                    }
+
+                    cast_tym!(TYM_UNIT)
+                }
+            }
+
+            pub fn parse_end<TCtx: CursorContext>(
+                input: &[u8],
+                ctx: &mut TCtx,
+                first_char: u8,
+                is_in_header: bool,
+            ) -> Option<BlockExtensionRelatedEnd> {
+                let &second_char = input.get(ctx.cursor() + 1)?;
+                let end = match (first_char, second_char) {
+                    (m!('}'), m!('}')) => BlockExtensionRelatedEnd::Closing,
+                    (m!('|'), m!('|')) => BlockExtensionRelatedEnd::DoublePipes,
+                    (m!('?'), m!('?')) if is_in_header => {
+                        BlockExtensionRelatedEnd::ParameterIndicator
+                    }
+                    _ => return None,
+                };
+                ctx.move_cursor_forward(2);
+                Some(end)
+            }
+
+            /// `closing` 是闭合部分（`}}`）在输入中的范围。
+            pub fn exit<TStack: Stack<StackEntry>>(
+                inner: &mut ParserInner<TStack>,
+                stack_entry: StackEntryBlockExtension,
+                closing: SourceSpan,
+            ) -> crate::Result<Tym<1>> {
+                let tym = inner.r#yield(stack_entry.make_exit_event(inner.current_line(), closing));
+
+                Ok(tym)
+            }
+
+            pub fn make_block_extension_related_end_condition<TStack: Stack<StackEntry>>(
+                inner: &ParserInner<TStack>,
+            ) -> Option<line::normal::BlockExtensionRelated> {
+                if inner.stack.block_extensions_in_stack() > 0 {
+                    Some(line::normal::BlockExtensionRelated {
+                        is_in_header: false,
+                    })
+                } else {
+                    None
+                }
+            }
+
+            /// 返回位于 `start` 及其之后，最近的头部中的一项内容（名称或参数）的结尾，
+            /// 即 `??`、`||`、`}}`、换行或文档结尾的位置。
+            fn find_end_of_header_item(input: &[u8], start: usize) -> usize {
+                let mut i = start;
+                while let Some(&char) = input.get(i) {
+                    match char {
+                        b'\r' | b'\n' => break,
+                        m!('?') | m!('|') | m!('}') if input.get(i + 1) == Some(&char) => break,
+                        _ => i += 1,
+                    }
+                }
+                i
+            }
+
+            fn trim_spaces(input: &[u8], mut range: Range<usize>) -> Range<usize> {
+                range.start +=
+                    count_continuous_character(input, b' ', range.start).min(range.len());
+                while range.end > range.start && input[range.end - 1] == b' ' {
+                    range.end -= 1;
+                }
+                range
+            }
+        }
+    }
+}
+
+mod block_attributes {
+    use crate::attributes;
+
+    use super::*;
+
+    /// 若 `inner.cursor()` 处是一组有效的块级附加信息，记录它，将 cursor 移至其之
+    /// 后，并返回 `true`。
+    pub fn parse_and_record<TStack: Stack<StackEntry>>(
+        input: &[u8],
+        inner: &mut ParserInner<TStack>,
+    ) -> bool {
+        let start = inner.cursor();
+        let Some(end) = attributes::parse_group(input, start, |_| {}) else {
+            return false;
+        };
+
+        // 块级附加信息可以跨越多行。
+        for (i, char) in input[start..end].iter().enumerate() {
+            let is_new_line = match char {
+                b'\n' => true,
+                b'\r' => input.get(start + i + 1) != Some(&b'\n'),
+                _ => false,
+            };
+            if is_new_line {
+                inner.increase_current_line();
+            }
+        }
+        inner.move_cursor_forward(end - start);
+        inner.push_attributes(input, start..end);
+
+        true
     }
 }
 
 mod leaf {
+    use std::ops::Range;
+
     use super::*;
 
     pub fn parse_opening_and_process<TStack: Stack<StackEntry>>(
@@ -698,8 +1224,9 @@ mod leaf {
             m!('-') => {
                 let count = 1 + count_continuous_character(input, m!('-'), inner.cursor() + 1);
                 if count >= 3 {
+                    let marker = inner.cursor()..(inner.cursor() + count);
                     inner.move_cursor_forward(count);
-                    thematic_break::process(inner).map(|tym| cast_tym!(tym))
+                    thematic_break::process(inner, marker).map(|tym| cast_tym!(tym))
                 } else {
                     paragraph::enter_if_not_blank(input, state, inner, count)
                         .map(|tym| cast_tym!(tym))
@@ -708,8 +1235,9 @@ mod leaf {
             m!('=') => {
                 let count = 1 + count_continuous_character(input, m!('='), inner.cursor() + 1);
                 if (1..=6).contains(&count) && input.get(inner.cursor() + count) == Some(&b' ') {
+                    let opening = inner.cursor()..(inner.cursor() + count);
                     inner.move_cursor_forward(count + " ".len());
-                    heading::enter(inner, count).map(|tym| cast_tym!(tym))
+                    heading::enter(inner, count, opening).map(|tym| cast_tym!(tym))
                 } else {
                     paragraph::enter_if_not_blank(input, state, inner, count)
                         .map(|tym| cast_tym!(tym))
@@ -718,8 +1246,9 @@ mod leaf {
             m!('`') => {
                 let count = 1 + count_continuous_character(input, m!('`'), inner.cursor() + 1);
                 if count >= 3 {
+                    let opening = inner.cursor()..(inner.cursor() + count);
                     inner.move_cursor_forward(count);
-                    code_block::enter(inner, count).map(|tym| cast_tym!(tym))
+                    code_block::enter(inner, count, opening).map(|tym| cast_tym!(tym))
                 } else {
                     paragraph::enter_if_not_blank(input, state, inner, count)
                         .map(|tym| cast_tym!(tym))
@@ -735,6 +1264,9 @@ mod leaf {
         inner: &mut ParserInner<TStack>,
         top_leaf: TopLeaf,
     ) -> crate::Result<Tym<3>> {
+        // 块级附加信息只能附着于新的块级元素，对于延续的叶子元素无效。
+        inner.take_attributes();
+
         match top_leaf {
             TopLeaf::Paragraph(top_leaf) => {
                 leaf::paragraph::parse_content_and_process(input, state, inner, top_leaf)
@@ -754,15 +1286,20 @@ mod leaf {
     mod thematic_break {
         use super::*;
 
+        /// `marker` 是分割线的标记（`---`）在输入中的范围。
         pub fn process<TStack: Stack<StackEntry>>(
             inner: &mut ParserInner<TStack>,
+            marker: Range<usize>,
         ) -> crate::Result<Tym<1>> {
             let id = inner.pop_block_id();
+            let attributes = inner.take_attributes();
             let tym = inner.r#yield(ev!(
                 Block,
                 ThematicBreak(ThematicBreak {
                     id,
                     line: inner.current_line(),
+                    attributes,
+                    span: SourceSpan::new_universal(marker),
                 })
             ));
 
@@ -775,13 +1312,20 @@ mod leaf {
 
         use super::*;
 
+        /// `opening` 是开启部分（不含之后的空格）在输入中的范围。
         pub(super) fn enter<TStack: Stack<StackEntry>>(
             inner: &mut ParserInner<TStack>,
             level: usize,
+            opening: Range<usize>,
         ) -> crate::Result<Tym<1>> {
             let id = inner.pop_block_id();
             let top_leaf = TopLeafHeading {
-                meta: Meta::new(id, inner.current_line()),
+                meta: Meta::new(
+                    id,
+                    inner.current_line(),
+                    inner.take_attributes(),
+                    SourceSpan::new_universal(opening),
+                ),
                 level,
                 has_content_before: false,
             };
@@ -809,6 +1353,10 @@ mod leaf {
                     on_table_related: branch::braced::table::make_table_related_end_condition(
                         inner, false,
                     ),
+                    on_block_extension_related:
+                        branch::braced::block_extension::make_block_extension_related_end_condition(
+                            inner,
+                        ),
                 },
                 if inner.current_expecting.spaces_before() > 0 {
                     line::normal::ContentBefore::Space
@@ -824,6 +1372,8 @@ mod leaf {
                 content.start -= inner.current_expecting.spaces_before();
             }
 
+            let closing = find_closing(input, content.end, top_leaf.level);
+
             let tym_a = if !content.is_empty() {
                 inner.r#yield(ev!(Block, __Unparsed(content)))
             } else {
@@ -831,29 +1381,58 @@ mod leaf {
             };
 
             let tym_b = match end {
-                line::normal::End::Eof | line::normal::End::NewLine(_) => exit(inner, top_leaf),
+                line::normal::End::Eof | line::normal::End::NewLine(_) => {
+                    exit(inner, top_leaf, closing)
+                }
                 line::normal::End::VerbatimEscaping(verbatim_escaping) => {
                     top_leaf.has_content_before = true;
                     inner.stack.push_top_leaf(top_leaf.into());
                     line::global_phase::process_verbatim_escaping(inner, verbatim_escaping)
                 }
                 line::normal::End::TableRelated(table_related_end) => {
-                    let tym_a = exit(inner, top_leaf);
-                    let tym_b = table_related_end.process(state);
+                    let tym_a = exit(inner, top_leaf, closing);
+                    let tym_b = table_related_end.process(state, inner);
+
+                    tym_a.add(tym_b)
+                }
+                line::normal::End::BlockExtensionRelated(block_extension_related_end) => {
+                    let tym_a = exit(inner, top_leaf, closing);
+                    let tym_b = block_extension_related_end.process(state, inner);
 
                     tym_a.add(tym_b)
                 }
-                line::normal::End::None => TYM_UNIT.into(),
+                line::normal::End::None => {
+                    // 如遇到了注释。标题在本行中尚未结束。
+                    top_leaf.has_content_before = true;
+                    inner.stack.push_top_leaf(top_leaf.into());
+                    TYM_UNIT.into()
+                }
             };
 
             Ok(tym_a.add(tym_b))
         }
 
+        /// `closing` 是闭合部分在输入中的范围，为 `None` 代表没有闭合部分。
         pub fn exit<TStack: Stack<StackEntry>>(
             inner: &mut ParserInner<TStack>,
             top_leaf: TopLeafHeading,
+            closing: Option<Range<usize>>,
         ) -> Tym<1> {
-            inner.r#yield(top_leaf.make_exit_event(inner.current_line()))
+            let span = match closing {
+                Some(closing) => SourceSpan::new_universal(closing),
+                None => inner.span_after_last_yielded(),
+            };
+            inner.r#yield(top_leaf.make_exit_event(inner.current_line(), span))
+        }
+
+        /// 寻找位于 `content_end` 之后的闭合部分（与开启部分等长的一串 `=`）。
+        ///
+        /// [line::normal::parse] 返回的内容不含闭合部分，因此内容之后若在空格之后
+        /// 紧接着一串数量与标题等级相同的 `=`，那便是闭合部分。
+        fn find_closing(input: &[u8], content_end: usize, level: usize) -> Option<Range<usize>> {
+            let start = content_end + count_continuous_character(input, b' ', content_end);
+            let count = count_continuous_character(input, m!('='), start);
+            (count == level).then_some(start..(start + count))
         }
     }
 
@@ -862,13 +1441,20 @@ mod leaf {
 
         use super::*;
 
+        /// `opening` 是开启部分（一串 “`”）在输入中的范围。
         pub(super) fn enter<TStack: Stack<StackEntry>>(
             inner: &mut ParserInner<TStack>,
             backticks: usize,
+            opening: Range<usize>,
         ) -> crate::Result<Tym<1>> {
             let id = inner.pop_block_id();
             let top_leaf = TopLeafCodeBlock {
-                meta: Meta::new(id, inner.current_line()),
+                meta: Meta::new(
+                    id,
+                    inner.current_line(),
+                    inner.take_attributes(),
+                    SourceSpan::new_universal(opening),
+                ),
                 backticks,
                 indent: inner.current_expecting.spaces_before(),
                 state: TopLeafCodeBlockState::InInfoString,
@@ -907,7 +1493,9 @@ mod leaf {
                             top_leaf.state = TopLeafCodeBlockState::InCode(
                                 TopLeafCodeBlockStateInCode::AtFirstLineBeginning,
                             );
-                            inner.r#yield(ev!(Block, IndicateCodeBlockCode))
+                            // 代码部分没有指示标记，其开始于信息字符串所在行的下一行。
+                            let span = inner.span_before_cursor(0);
+                            inner.r#yield(ev!(Block, IndicateCodeBlockCode(Indication { span })))
                         }
                         line::verbatim::End::VerbatimEscaping(verbatim_escaping) => {
                             line::global_phase::process_verbatim_escaping(inner, verbatim_escaping)
@@ -985,7 +1573,14 @@ mod leaf {
                             line::global_phase::process_verbatim_escaping(inner, verbatim_escaping)
                         }
                         line::verbatim::End::Fence => {
-                            exit_when_indicator_already_yielded(inner, top_leaf)
+                            // 此时 cursor 位于闭合栅栏之后。
+                            let fence_len = input[..inner.cursor()]
+                                .iter()
+                                .rev()
+                                .take_while(|char| **char == m!('`'))
+                                .count();
+                            let span = inner.span_before_cursor(fence_len);
+                            exit_when_indicator_already_yielded(inner, top_leaf, span)
                         }
                         line::verbatim::End::None => {
                             top_leaf.state =
@@ -1002,11 +1597,13 @@ mod leaf {
             Ok(tym)
         }
 
+        /// `closing` 是闭合栅栏在输入中的范围。
         fn exit_when_indicator_already_yielded<TStack: Stack<StackEntry>>(
             inner: &mut ParserInner<TStack>,
             top_leaf: TopLeafCodeBlock,
+            closing: SourceSpan,
         ) -> Tym<1> {
-            inner.r#yield(top_leaf.make_exit_event(inner.current_line()))
+            inner.r#yield(top_leaf.make_exit_event(inner.current_line(), closing))
         }
 
         pub fn exit<TStack: Stack<StackEntry>>(
@@ -1014,12 +1611,14 @@ mod leaf {
             top_leaf: TopLeafCodeBlock,
         ) -> Tym<2> {
             let tym_a = if matches!(top_leaf.state, TopLeafCodeBlockState::InInfoString) {
-                inner.r#yield(ev!(Block, IndicateCodeBlockCode))
+                let span = inner.span_after_last_yielded();
+                inner.r#yield(ev!(Block, IndicateCodeBlockCode(Indication { span })))
             } else {
                 TYM_UNIT.into()
             };
 
-            let tym_b = exit_when_indicator_already_yielded(inner, top_leaf);
+            let closing = inner.span_after_last_yielded();
+            let tym_b = exit_when_indicator_already_yielded(inner, top_leaf, closing);
 
             tym_a.add(tym_b)
         }
@@ -1050,8 +1649,11 @@ mod leaf {
             // [line::normal::parse] 的过程中可能会涉及到逐字转义，导致行数增加，因此
             // 需要提前取得行数。
             let line_start = inner.current_line();
+            // 段落没有开启部分，以其开头处的空范围代替。
+            let span = inner.span_before_cursor(0);
 
             let has_just_entered_table = inner.has_just_entered_table();
+            let has_attributes_on_previous_lines = inner.has_attributes_on_previous_lines();
             let (content, mut end) = line::normal::parse(
                 input,
                 inner,
@@ -1061,6 +1663,10 @@ mod leaf {
                         inner,
                         has_just_entered_table,
                     ),
+                    on_block_extension_related:
+                        branch::braced::block_extension::make_block_extension_related_end_condition(
+                            inner,
+                        ),
                 },
                 line::normal::ContentBefore::NotSpace(content_before),
             );
@@ -1068,7 +1674,7 @@ mod leaf {
             let tym_ab = if !content.is_empty() || end.is_verbatim_escaping() {
                 let id = inner.pop_block_id();
                 let top_leaf = TopLeafParagraph {
-                    meta: Meta::new(id, line_start),
+                    meta: Meta::new(id, line_start, inner.take_attributes(), span),
                     new_line: end.try_take_new_line(),
                 };
                 let ev = top_leaf.make_enter_event();
@@ -1083,6 +1689,10 @@ mod leaf {
 
                 tym_a.add(tym_b)
             } else {
+                if has_attributes_on_previous_lines {
+                    // 块级附加信息不会越过空行附着于之后的块级元素。
+                    inner.take_attributes();
+                }
                 TYM_UNIT.into()
             };
 
@@ -1107,7 +1717,11 @@ mod leaf {
                     cast_tym!(tym)
                 }
                 line::normal::End::TableRelated(table_related_end) => {
-                    let tym = table_related_end.process(state, );
+                    let tym = table_related_end.process(state, inner);
+                    cast_tym!(tym)
+                }
+                line::normal::End::BlockExtensionRelated(block_extension_related_end) => {
+                    let tym = block_extension_related_end.process(state, inner);
                     cast_tym!(tym)
                 }
                 line::normal::End::None => TYM_UNIT.into()
@@ -1128,6 +1742,10 @@ mod leaf {
                     on_table_related: branch::braced::table::make_table_related_end_condition(
                         inner, false,
                     ),
+                    on_block_extension_related:
+                        branch::braced::block_extension::make_block_extension_related_end_condition(
+                            inner,
+                        ),
                 },
                 if inner.current_expecting.spaces_before() > 0 {
                     line::normal::ContentBefore::Space
@@ -1172,7 +1790,9 @@ mod leaf {
             inner: &mut ParserInner<TStack>,
             top_leaf: TopLeafParagraph,
         ) -> Tym<1> {
-            inner.r#yield(top_leaf.make_exit_event(inner.current_line()))
+            inner.r#yield(
+                top_leaf.make_exit_event(inner.current_line(), inner.span_after_last_yielded()),
+            )
         }
     }
 }
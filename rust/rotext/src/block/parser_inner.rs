@@ -1,6 +1,10 @@
+use std::ops::Range;
+
 use crate::{
+    attributes,
+    diagnostics::DiagnosticCollector,
     events::ev,
-    types::{BlockId, LineNumber, Tym},
+    types::{BlockId, LineNumber, SourceSpan, Tym},
     utils::{internal::array_queue::ArrayQueue, stack::Stack},
     Event,
 };
@@ -31,6 +35,21 @@ pub struct ParserInner<TStack: Stack<StackEntry>> {
     pub current_expecting: CurrentExpecting,
 
     has_just_entered_table: bool,
+
+    /// 尚未附着于块级元素的块级附加信息。
+    pending_attributes: Option<PendingAttributes>,
+
+    pub diagnostics: DiagnosticCollector,
+
+    /// 最近产出的（换行以外的）事件在输入中的结尾。
+    #[cfg(feature = "source-span")]
+    last_yielded_end: usize,
+}
+
+struct PendingAttributes {
+    range: Range<usize>,
+    /// 附加信息的结尾之后是否尚未换行。
+    is_at_same_line: bool,
 }
 
 impl<TStack: Stack<StackEntry>> ParserInner<TStack> {
@@ -44,6 +63,23 @@ impl<TStack: Stack<StackEntry>> ParserInner<TStack> {
             block_id_generator: BlockIdGenerator::new(),
             current_expecting: CurrentExpecting::new(),
             has_just_entered_table: false,
+            pending_attributes: None,
+            diagnostics: DiagnosticCollector::default(),
+            #[cfg(feature = "source-span")]
+            last_yielded_end: 0,
+        }
+    }
+
+    /// 见 [super::Parser::new_at]。
+    #[cfg(feature = "incremental")]
+    pub fn new_at(point: super::StartingPoint) -> Self {
+        Self {
+            cursor: point.cursor,
+            current_line: LineNumber::new_universal(point.line),
+            #[cfg(feature = "block-id")]
+            block_id_generator: BlockIdGenerator::new_after(point.last_block_id),
+            last_yielded_end: point.cursor,
+            ..Self::new()
         }
     }
 
@@ -65,6 +101,71 @@ impl<TStack: Stack<StackEntry>> ParserInner<TStack> {
         }
     }
 
+    /// 记录一组块级附加信息，其位于 `range`，且结尾就是当前的位置。若之前记录的附
+    /// 加信息与这组附加信息之间只有空白，二者会被合并。
+    pub fn push_attributes(&mut self, input: &[u8], range: Range<usize>) {
+        let start = match self.pending_attributes.take() {
+            Some(pending) if attributes::is_blank(input, pending.range.end..range.start) => {
+                pending.range.start
+            }
+            _ => range.start,
+        };
+        self.pending_attributes = Some(PendingAttributes {
+            range: start..range.end,
+            is_at_same_line: true,
+        });
+    }
+
+    /// 取走尚未附着于块级元素的块级附加信息。
+    pub fn take_attributes(&mut self) -> Option<Range<usize>> {
+        self.pending_attributes.take().map(|pending| pending.range)
+    }
+
+    /// 返回是否存在结尾位于之前的行的、尚未附着于块级元素的块级附加信息。
+    pub fn has_attributes_on_previous_lines(&self) -> bool {
+        self.pending_attributes
+            .as_ref()
+            .is_some_and(|pending| !pending.is_at_same_line)
+    }
+
+    /// 若尚未附着于块级元素的块级附加信息的结尾与当前位置是否位于同一行的情况与
+    /// `is_at_same_line` 相符，取走它。
+    ///
+    /// 列表项与描述列表项的标记需要与其附加信息的结尾在同一行，否则附加信息属于
+    /// 整个列表或描述列表。
+    pub fn take_attributes_if(&mut self, is_at_same_line: bool) -> Option<Range<usize>> {
+        if self
+            .pending_attributes
+            .as_ref()
+            .is_some_and(|pending| pending.is_at_same_line == is_at_same_line)
+        {
+            self.take_attributes()
+        } else {
+            None
+        }
+    }
+
+    /// 返回紧挨在当前位置之前、长度为 `len` 的标记的范围。
+    ///
+    /// 处于 [super::state::Exiting] 状态时 cursor 不会移动，因此在退出时，这里
+    /// 仍能取得引起退出的标记（如 `|}`）的范围。
+    pub fn span_before_cursor(&self, len: usize) -> SourceSpan {
+        SourceSpan::new_universal((self.cursor - len)..self.cursor)
+    }
+
+    /// 返回最近产出的事件的结尾处的空范围，用于没有闭合部分的块级元素的退出事
+    /// 件。
+    pub fn span_after_last_yielded(&self) -> SourceSpan {
+        #[cfg(feature = "source-span")]
+        {
+            SourceSpan::new(self.last_yielded_end..self.last_yielded_end)
+        }
+        #[cfg(not(feature = "source-span"))]
+        {
+            SourceSpan::new()
+        }
+    }
+
     pub fn reset_current_expecting(&mut self) {
         self.current_expecting = CurrentExpecting::new();
     }
@@ -89,6 +190,9 @@ impl<TStack: Stack<StackEntry>> CursorContext for ParserInner<TStack> {
 
     fn increase_current_line(&mut self) {
         self.current_line.increase();
+        if let Some(pending) = &mut self.pending_attributes {
+            pending.is_at_same_line = false;
+        }
         self.stack.reset_current_line_for_new_line();
     }
 }
@@ -98,6 +202,21 @@ impl<TStack: Stack<StackEntry>> YieldContext for ParserInner<TStack> {
     fn r#yield(&mut self, ev_to_yield: Event) -> Tym<1> {
         self.has_just_entered_table = matches!(ev_to_yield, ev!(Block, EnterTable(..)));
 
+        #[cfg(feature = "source-span")]
+        match &ev_to_yield {
+            ev!(Block, __Unparsed(content)) | ev!(Block, Text(content)) => {
+                self.last_yielded_end = content.end;
+            }
+            // 逐字转义在解析完毕后立即产出，此时 cursor 位于其闭合部分之后。
+            ev!(Block, VerbatimEscaping(_)) => self.last_yielded_end = self.cursor,
+            ev!(Block, NewLine(_)) => {}
+            ev => {
+                if let Some(span) = ev.span() {
+                    self.last_yielded_end = span.range().end;
+                }
+            }
+        }
+
         self.to_yield.push_back(ev_to_yield);
 
         Tym::<1>::new()
@@ -1,6 +1,8 @@
+use std::ops::Range;
+
 use crate::{
-    events::{ev, ExitBlock, NewLine},
-    types::{BlockId, LineNumber},
+    events::{ev, BlockExtension, BlockWithId, ExitBlock, NewLine},
+    types::{BlockId, LineNumber, SourceSpan},
     utils::stack::Stack,
     Event,
 };
@@ -9,8 +11,13 @@ pub struct StackWrapper<TStack: Stack<StackEntry>> {
     stack: TStack,
     top_leaf: Option<TopLeaf>,
 
+    /// 只在栈顶是块级扩展、且仍处于该扩展的头部（名称与参数序列所在之处）时为
+    /// `Some`。
+    block_extension_header: Option<BlockExtensionHeaderState>,
+
     item_likes_in_stack: usize,
     tables_in_stack: usize,
+    block_extensions_in_stack: usize,
 
     should_reset_state: bool,
 }
@@ -20,8 +27,10 @@ impl<TStack: Stack<StackEntry>> StackWrapper<TStack> {
         Self {
             stack: TStack::new(),
             top_leaf: None,
+            block_extension_header: None,
             item_likes_in_stack: 0,
             tables_in_stack: 0,
+            block_extensions_in_stack: 0,
             should_reset_state: false,
         }
     }
@@ -40,6 +49,9 @@ impl<TStack: Stack<StackEntry>> StackWrapper<TStack> {
     pub fn tables_in_stack(&self) -> usize {
         self.tables_in_stack
     }
+    pub fn block_extensions_in_stack(&self) -> usize {
+        self.block_extensions_in_stack
+    }
 
     pub fn reset_current_line_for_new_line(&mut self) {
         self.should_reset_state = true;
@@ -70,6 +82,29 @@ impl<TStack: Stack<StackEntry>> StackWrapper<TStack> {
         matches!(self.stack.as_slice().last(), Some(StackEntry::Table(_)))
     }
 
+    pub fn top_is_block_extension(&self) -> bool {
+        if self.top_leaf.is_some() {
+            return false;
+        }
+        matches!(
+            self.stack.as_slice().last(),
+            Some(StackEntry::BlockExtension(_))
+        )
+    }
+
+    pub fn block_extension_header(&self) -> Option<BlockExtensionHeaderState> {
+        self.block_extension_header
+    }
+
+    /// 设置栈顶块级扩展头部的状态。传入 `None` 代表离开头部。
+    ///
+    /// 调用者应保证栈顶是块级扩展。
+    pub fn set_block_extension_header(&mut self, header: Option<BlockExtensionHeaderState>) {
+        debug_assert!(self.top_is_block_extension());
+
+        self.block_extension_header = header;
+    }
+
     /// 向栈中推入一个 item-like entry。
     ///
     /// 调用者应保证 `self.top_leaf` 为 `None`。
@@ -99,17 +134,32 @@ impl<TStack: Stack<StackEntry>> StackWrapper<TStack> {
         Ok(())
     }
 
+    /// 向栈中推入一个块级扩展 entry，推入后处于该扩展的头部。
+    ///
+    /// 调用者应保证 `self.top_leaf` 为 `None`。
+    pub fn push_block_extension(
+        &mut self,
+        stack_entry: StackEntryBlockExtension,
+    ) -> crate::Result<()> {
+        self.try_push(stack_entry.into())?;
+        self.block_extensions_in_stack += 1;
+        self.block_extension_header = Some(BlockExtensionHeaderState::ExpectingItem);
+        Ok(())
+    }
+
     /// 尝试向栈中推入一个 entry。
     ///
     /// 调用者应保证 `self.top_leaf` 为 `None`。
     fn try_push(&mut self, entry: StackEntry) -> crate::Result<()> {
         debug_assert!(self.top_leaf.is_none());
+        debug_assert!(self.block_extension_header.is_none());
 
         self.stack.try_push(entry)
     }
 
     pub fn push_top_leaf(&mut self, entry: TopLeaf) {
         debug_assert!(self.top_leaf.is_none());
+        debug_assert!(self.block_extension_header.is_none());
 
         self.top_leaf = Some(entry);
     }
@@ -130,6 +180,10 @@ impl<TStack: Stack<StackEntry>> StackWrapper<TStack> {
             StackEntry::ItemLike(_) => {}
             StackEntry::ItemLikeContainer(_) => self.item_likes_in_stack -= 1,
             StackEntry::Table(_) => self.tables_in_stack -= 1,
+            StackEntry::BlockExtension(_) => {
+                self.block_extensions_in_stack -= 1;
+                self.block_extension_header = None;
+            }
         }
 
         Some(popped)
@@ -144,6 +198,7 @@ pub enum StackEntry {
     ItemLike(StackEntryItemLike),
     ItemLikeContainer(StackEntryItemLikeContainer),
     Table(StackEntryTable),
+    BlockExtension(StackEntryBlockExtension),
 }
 impl From<StackEntryItemLike> for StackEntry {
     fn from(value: StackEntryItemLike) -> Self {
@@ -160,6 +215,11 @@ impl From<StackEntryTable> for StackEntry {
         Self::Table(value)
     }
 }
+impl From<StackEntryBlockExtension> for StackEntry {
+    fn from(value: StackEntryBlockExtension) -> Self {
+        Self::BlockExtension(value)
+    }
+}
 
 pub struct StackEntryItemLike {
     pub meta: Meta,
@@ -170,15 +230,18 @@ impl StackEntryItemLike {
     /// 返回的事件属于 `Block` 分组。
     pub fn make_enter_event(&self) -> Event {
         match self.r#type {
-            GeneralItemLike::LI => ev!(Block, EnterListItem(self.meta.id.into())),
-            GeneralItemLike::DT => ev!(Block, EnterDescriptionTerm(self.meta.id.into())),
-            GeneralItemLike::DD => ev!(Block, EnterDescriptionDetails(self.meta.id.into())),
+            GeneralItemLike::LI => ev!(Block, EnterListItem(self.meta.make_block_with_id())),
+            GeneralItemLike::DT => ev!(Block, EnterDescriptionTerm(self.meta.make_block_with_id())),
+            GeneralItemLike::DD => ev!(
+                Block,
+                EnterDescriptionDetails(self.meta.make_block_with_id())
+            ),
         }
     }
 
     /// 返回的事件属于 `Block` 分组。
-    pub fn make_exit_event(self, line_end: LineNumber) -> Event {
-        self.meta.make_exit_event(line_end)
+    pub fn make_exit_event(self, line_end: LineNumber, span: SourceSpan) -> Event {
+        self.meta.make_exit_event(line_end, span)
     }
 }
 
@@ -191,52 +254,121 @@ impl StackEntryItemLikeContainer {
     /// 返回的事件属于 `Block` 分组。
     pub fn make_enter_event(&self) -> Event {
         match self.r#type {
-            ItemLikeContainer::BlockQuote => ev!(Block, EnterBlockQuote(self.meta.id.into())),
-            ItemLikeContainer::OL => ev!(Block, EnterOrderedList(self.meta.id.into())),
-            ItemLikeContainer::UL => ev!(Block, EnterUnorderedList(self.meta.id.into())),
-            ItemLikeContainer::DL => ev!(Block, EnterDescriptionList(self.meta.id.into())),
+            ItemLikeContainer::BlockQuote => {
+                ev!(Block, EnterBlockQuote(self.meta.make_block_with_id()))
+            }
+            ItemLikeContainer::OL => ev!(Block, EnterOrderedList(self.meta.make_block_with_id())),
+            ItemLikeContainer::UL => ev!(Block, EnterUnorderedList(self.meta.make_block_with_id())),
+            ItemLikeContainer::DL => {
+                ev!(Block, EnterDescriptionList(self.meta.make_block_with_id()))
+            }
         }
     }
 
     /// 返回的事件属于 `Block` 分组。
-    pub fn make_exit_event(self, line_end: LineNumber) -> Event {
-        self.meta.make_exit_event(line_end)
+    pub fn make_exit_event(self, line_end: LineNumber, span: SourceSpan) -> Event {
+        self.meta.make_exit_event(line_end, span)
     }
 }
 
 pub struct StackEntryTable {
     pub meta: Meta,
+    /// 开启部分（`{|`）在输入中的范围。
+    pub opening: Range<usize>,
 }
 impl StackEntryTable {
     /// 返回的事件属于 `Block` 分组。
     pub fn make_enter_event(&self) -> Event {
-        ev!(Block, EnterTable(self.meta.id.into()))
+        ev!(Block, EnterTable(self.meta.make_block_with_id()))
     }
 
     /// 返回的事件属于 `Block` 分组。
-    pub fn make_exit_event(self, line_end: LineNumber) -> Event {
-        self.meta.make_exit_event(line_end)
+    pub fn make_exit_event(self, line_end: LineNumber, span: SourceSpan) -> Event {
+        self.meta.make_exit_event(line_end, span)
     }
 }
 
+pub struct StackEntryBlockExtension {
+    pub meta: Meta,
+    /// 开启部分（`{{#` 及扩展的名称）在输入中的范围。
+    pub opening: Range<usize>,
+
+    pub name: Range<usize>,
+}
+impl StackEntryBlockExtension {
+    /// 返回的事件属于 `Block` 分组。
+    pub fn make_enter_event(&self) -> Event {
+        ev!(
+            Block,
+            EnterBlockExtension(BlockExtension {
+                id: self.meta.id,
+                name: self.name.clone(),
+                attributes: self.meta.attributes.clone(),
+                span: self.meta.span,
+            })
+        )
+    }
+
+    /// 返回的事件属于 `Block` 分组。
+    pub fn make_exit_event(self, line_end: LineNumber, span: SourceSpan) -> Event {
+        self.meta.make_exit_event(line_end, span)
+    }
+}
+
+/// 块级扩展头部的解析状态。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockExtensionHeaderState {
+    /// 期待 `??`、`||`、`}}` 或换行。遇到其他内容时，视为隐式地开启了第一个子内容。
+    ExpectingItem,
+    /// 刚刚消耗掉了 `??`，期待参数的（可选的）名称与值。
+    ExpectingParameter {
+        /// `??` 在输入中的范围。
+        indicator: SourceSpan,
+    },
+    /// 处于参数的值之中（值被逐字转义截断了）。
+    InParameterValue,
+}
+
 #[derive(Debug)]
 pub struct Meta {
     id: BlockId,
     line_start: LineNumber,
+    attributes: Option<Range<usize>>,
+    /// 开启部分在输入中的范围。
+    span: SourceSpan,
 }
 impl Meta {
-    pub fn new(id: BlockId, line_start: LineNumber) -> Self {
-        Self { id, line_start }
+    pub fn new(
+        id: BlockId,
+        line_start: LineNumber,
+        attributes: Option<Range<usize>>,
+        span: SourceSpan,
+    ) -> Self {
+        Self {
+            id,
+            line_start,
+            attributes,
+            span,
+        }
     }
 
-    /// 返回的事件属于 `Block` 分组。
-    fn make_exit_event(self, line_end: LineNumber) -> Event {
+    fn make_block_with_id(&self) -> BlockWithId {
+        BlockWithId {
+            id: self.id,
+            attributes: self.attributes.clone(),
+            span: self.span,
+        }
+    }
+
+    /// 返回的事件属于 `Block` 分组。`span` 是闭合部分在输入中的范围。
+    fn make_exit_event(self, line_end: LineNumber, span: SourceSpan) -> Event {
         ev!(
             Block,
             ExitBlock(ExitBlock {
                 id: self.id,
                 start_line: self.line_start,
                 end_line: line_end,
+                span,
             })
         )
     }
@@ -294,12 +426,12 @@ pub struct TopLeafParagraph {
 impl TopLeafParagraph {
     /// 返回的事件属于 `Block` 分组。
     pub fn make_enter_event(&self) -> Event {
-        ev!(Block, EnterParagraph(self.meta.id.into()))
+        ev!(Block, EnterParagraph(self.meta.make_block_with_id()))
     }
 
     /// 返回的事件属于 `Block` 分组。
-    pub fn make_exit_event(self, line_end: LineNumber) -> Event {
-        self.meta.make_exit_event(line_end)
+    pub fn make_exit_event(self, line_end: LineNumber, span: SourceSpan) -> Event {
+        self.meta.make_exit_event(line_end, span)
     }
 }
 
@@ -315,19 +447,19 @@ impl TopLeafHeading {
     /// 返回的事件属于 `Block` 分组。
     pub fn make_enter_event(&self) -> Event {
         match self.level {
-            1 => ev!(Block, EnterHeading1(self.meta.id.into())),
-            2 => ev!(Block, EnterHeading2(self.meta.id.into())),
-            3 => ev!(Block, EnterHeading3(self.meta.id.into())),
-            4 => ev!(Block, EnterHeading4(self.meta.id.into())),
-            5 => ev!(Block, EnterHeading5(self.meta.id.into())),
-            6 => ev!(Block, EnterHeading6(self.meta.id.into())),
+            1 => ev!(Block, EnterHeading1(self.meta.make_block_with_id())),
+            2 => ev!(Block, EnterHeading2(self.meta.make_block_with_id())),
+            3 => ev!(Block, EnterHeading3(self.meta.make_block_with_id())),
+            4 => ev!(Block, EnterHeading4(self.meta.make_block_with_id())),
+            5 => ev!(Block, EnterHeading5(self.meta.make_block_with_id())),
+            6 => ev!(Block, EnterHeading6(self.meta.make_block_with_id())),
             _ => unreachable!(),
         }
     }
 
     /// 返回的事件属于 `Block` 分组。
-    pub fn make_exit_event(self, line_end: LineNumber) -> Event {
-        self.meta.make_exit_event(line_end)
+    pub fn make_exit_event(self, line_end: LineNumber, span: SourceSpan) -> Event {
+        self.meta.make_exit_event(line_end, span)
     }
 }
 
@@ -354,11 +486,11 @@ pub enum TopLeafCodeBlockStateInCode {
 impl TopLeafCodeBlock {
     /// 返回的事件属于 `Block` 分组。
     pub fn make_enter_event(&self) -> Event {
-        ev!(Block, EnterCodeBlock(self.meta.id.into()))
+        ev!(Block, EnterCodeBlock(self.meta.make_block_with_id()))
     }
 
     /// 返回的事件属于 `Block` 分组。
-    pub fn make_exit_event(self, line_end: LineNumber) -> Event {
-        self.meta.make_exit_event(line_end)
+    pub fn make_exit_event(self, line_end: LineNumber, span: SourceSpan) -> Event {
+        self.meta.make_exit_event(line_end, span)
     }
 }
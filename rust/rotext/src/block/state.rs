@@ -45,6 +45,9 @@ pub enum ExitingUntil {
     TopIsTable {
         should_also_exit_table: bool,
     },
+    TopIsBlockExtension {
+        should_also_exit_block_extension: bool,
+    },
     TopIsAwareOfDoublePipes,
     StackIsEmpty,
 }
@@ -77,3 +77,33 @@ fn it_works_with_array_stack() {
     );
     assert_parse_error_with_stack(&ctx, "> > >", Error::OutOfStackSpace)
 }
+
+#[test]
+fn it_parses_table_cell_spans() {
+    let table: &[(&str, (usize, usize))] = &[
+        ("{|\n|| a\n|}", (1, 1)),
+        ("{|\n{! colspan=\"2\"} !! a\n|}", (2, 1)),
+        ("{|\n{! .x rowspan='3'}{! colspan=\"2\"}|| a\n|}", (2, 3)),
+        ("{|\n{! colspan=\"0\" rowspan=\"x\"}|| a\n|}", (1, 1)),
+        (
+            "{|\n{! colspan=\"99999999999999999999\" rowspan=\"70000\"}|| a\n|}",
+            (1000, 65534),
+        ),
+    ];
+
+    for (input, expected) in table {
+        let cell = crate::parse(input.as_bytes())
+            .find_map(|ev| match ev.unwrap() {
+                crate::Event::IndicateTableHeaderCell(cell)
+                | crate::Event::IndicateTableDataCell(cell) => Some(cell),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(
+            *expected,
+            cell.spans(input.as_bytes()),
+            "input: {:?}",
+            input
+        );
+    }
+}
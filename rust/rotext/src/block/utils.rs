@@ -8,6 +8,12 @@ impl BlockIdGenerator {
         Self(0)
     }
 
+    /// 创建之后生成的 ID 紧接在 `last` 之后的生成器。
+    #[cfg(feature = "incremental")]
+    pub fn new_after(last: usize) -> Self {
+        Self(last)
+    }
+
     #[cfg(feature = "block-id")]
     pub fn pop(&mut self) -> BlockId {
         self.0 += 1;
@@ -1,5 +1,7 @@
 use rotext_internal_macros::make_markup_guard;
 
+use crate::utils::internal::string::count_continuous_character;
+
 make_markup_guard! {
     markup_guard_macro_name = m,
     is_markup_function_name = is_markup,
@@ -12,3 +14,45 @@ pub fn is_valid_character_in_name(char: u8) -> bool {
         m!('{') | m!('}') | m!('[') | m!(']') | m!('<') | m!('>') | m!('|')
     )
 }
+
+/// 返回 `name` 是否是有效的 Rotext 变量标识符，即是否符合：
+///
+/// ```regexp
+/// /^([@_.]|[\p{ID_Start}_]\p{ID_Continue}*\.)[\p{ID_Start}_]\p{ID_Continue}*(\.[\p{ID_Start}_]\p{ID_Continue}*)*$/
+/// ```
+///
+/// NOTE: 实际判断时用的是 `XID_Start` 与 `XID_Continue`，二者与
+/// `ID_Start`、`ID_Continue` 只在极少数字符上有差别。
+pub fn is_valid_variable_identifier(name: &[u8]) -> bool {
+    let Ok(name) = std::str::from_utf8(name) else {
+        return false;
+    };
+
+    let is_valid_segment = |segment: &str| {
+        let mut chars = segment.chars();
+        chars
+            .next()
+            .is_some_and(|char| char == '_' || unicode_ident::is_xid_start(char))
+            && chars.all(unicode_ident::is_xid_continue)
+    };
+    let are_valid_segments = |segments: &str| segments.split('.').all(is_valid_segment);
+
+    // 以单个 `@`、`_` 或 `.` 开头的情况。
+    if let Some(rest) = name.strip_prefix(['@', '_', '.']) {
+        if are_valid_segments(rest) {
+            return true;
+        }
+    }
+    // 以 `<segment>.` 开头的情况。
+    name.contains('.') && are_valid_segments(name)
+}
+
+/// 返回未被强制闭合的逐字转义在输入中结束的位置（即闭合部分之后的位置）。
+/// `content_end` 是其内容结束的位置。
+pub fn find_verbatim_escaping_end(input: &[u8], mut content_end: usize) -> usize {
+    if input.get(content_end) == Some(&b' ') {
+        content_end += 1;
+    }
+
+    content_end + count_continuous_character(input, m!('`'), content_end) + ">".len()
+}
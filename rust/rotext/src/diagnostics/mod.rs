@@ -0,0 +1,123 @@
+//! 解析时收集的诊断信息。
+//!
+//! Rotext 不会拒绝任何输入，书写上的失误只会让渲染结果悄悄地退化（如未闭合的逐
+//! 字转义会一直延续到文档末尾）。诊断信息记录这些情况，供编辑器等工具提示。
+
+#[cfg(test)]
+mod tests;
+
+use std::ops::Range;
+
+/// 诊断信息。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub code: DiagnosticCode,
+    pub severity: Severity,
+    /// 诊断信息所针对的内容在输入中的范围。
+    pub range: Range<usize>,
+    pub message: &'static str,
+}
+
+impl Diagnostic {
+    pub fn new(code: DiagnosticCode, range: Range<usize>) -> Self {
+        Self {
+            code,
+            severity: code.severity(),
+            range,
+            message: code.message(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// 很可能是书写上的失误。
+    Warning,
+    /// 可能是书写上的失误，也可能是有意为之。
+    Information,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticCode {
+    /// 逐字转义（`<` … `>`）没有闭合，其内容延续到了文档末尾。范围是其内容。
+    UnclosedVerbatimEscaping,
+    /// 行内代码（`[` … `]`）没有闭合，在所在的块级槽位结束时被强制闭合。范围
+    /// 是其开启部分。
+    UnclosedCodeSpan,
+    /// 看起来像是 Wiki 链接的内容（`[[` 之后有地址），由于没有找到 `]]` 或
+    /// `|`，被当作了文本。范围从 `[[` 到地址的结尾。
+    UnrecognizedWikiLink,
+    /// 表格（`{|` … `|}`）没有闭合，被强制闭合。范围是其开启部分。
+    UnclosedTable,
+    /// 块级扩展（`{{#` … `}}`）没有闭合，被强制闭合。范围是其开启部分（含扩
+    /// 展的名称）。
+    UnclosedBlockExtension,
+}
+
+impl DiagnosticCode {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::UnclosedVerbatimEscaping => "unclosed-verbatim-escaping",
+            Self::UnclosedCodeSpan => "unclosed-code-span",
+            Self::UnrecognizedWikiLink => "unrecognized-wiki-link",
+            Self::UnclosedTable => "unclosed-table",
+            Self::UnclosedBlockExtension => "unclosed-block-extension",
+        }
+    }
+
+    pub fn severity(self) -> Severity {
+        match self {
+            Self::UnrecognizedWikiLink => Severity::Information,
+            Self::UnclosedVerbatimEscaping
+            | Self::UnclosedCodeSpan
+            | Self::UnclosedTable
+            | Self::UnclosedBlockExtension => Severity::Warning,
+        }
+    }
+
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::UnclosedVerbatimEscaping => "逐字转义没有闭合，其内容延续到了文档末尾。",
+            Self::UnclosedCodeSpan => "行内代码没有闭合。",
+            Self::UnrecognizedWikiLink => "没有找到 “]]” 或 “|”，内容被当作了文本。",
+            Self::UnclosedTable => "表格没有以 “|}” 闭合。",
+            Self::UnclosedBlockExtension => "块级扩展没有以 “}}” 闭合。",
+        }
+    }
+}
+
+/// 解析器内部用于收集诊断信息的容器。默认不收集。
+#[derive(Default)]
+pub(crate) struct DiagnosticCollector {
+    diagnostics: Option<Vec<Diagnostic>>,
+}
+
+impl DiagnosticCollector {
+    pub fn enable(&mut self) {
+        self.diagnostics.get_or_insert_with(Vec::new);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.diagnostics.is_some()
+    }
+
+    pub fn report(&mut self, code: DiagnosticCode, range: Range<usize>) {
+        if let Some(diagnostics) = &mut self.diagnostics {
+            diagnostics.push(Diagnostic::new(code, range));
+        }
+    }
+
+    pub fn extend(&mut self, other: Vec<Diagnostic>) {
+        if let Some(diagnostics) = &mut self.diagnostics {
+            diagnostics.extend(other);
+        }
+    }
+
+    /// 取走目前为止收集到的诊断信息。
+    pub fn take(&mut self) -> Vec<Diagnostic> {
+        self.diagnostics
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,72 @@
+use indoc::indoc;
+
+use super::{DiagnosticCode, Severity};
+
+/// 将诊断信息转换为 “代码 诊断范围的内容” 构成的各项，便于比较。
+fn summarize(input: &str) -> Vec<String> {
+    let (_, diagnostics) = crate::parse_with_diagnostics(input.as_bytes()).unwrap();
+    diagnostics
+        .iter()
+        .map(|diagnostic| {
+            format!(
+                "{} {}",
+                diagnostic.code.name(),
+                &input[diagnostic.range.clone()]
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn it_works() {
+    let table: &[(&str, &[&str])] = &[
+        ("", &[]),
+        ("foo", &[]),
+        ("<`a`> [`b`] [[c]] [[d|e]]", &[]),
+        ("{|\n| a\n|}", &[]),
+        ("{{#Note\na\n}}", &[]),
+        ("a <` b\nc", &["unclosed-verbatim-escaping b\nc"]),
+        ("a [`b", &["unclosed-code-span [`"]),
+        ("a [``b`]\n\nc", &["unclosed-code-span [``"]),
+        ("a [[b", &["unrecognized-wiki-link [[b"]),
+        ("a [[ b c\nd]]", &["unrecognized-wiki-link [[ b c"]),
+        ("a [[<`b`> c", &["unrecognized-wiki-link [[<`b"]),
+        ("{|\n| a", &["unclosed-table {|"]),
+        ("> {|\n> | a\nb", &["unclosed-table {|"]),
+        ("{{#Note\na", &["unclosed-block-extension {{#Note"]),
+        (
+            indoc! {"
+            {{#Note
+            [`a
+            <`b"},
+            &[
+                "unclosed-block-extension {{#Note",
+                "unclosed-code-span [`",
+                "unclosed-verbatim-escaping b",
+            ],
+        ),
+    ];
+
+    for (input, expected) in table {
+        assert_eq!(expected, &summarize(input), "input: {:?}", input);
+    }
+}
+
+#[test]
+fn it_does_not_collect_diagnostics_by_default() {
+    let mut block_parser = crate::block::Parser::<
+        crate::utils::stack::VecStack<crate::block::StackEntry>,
+    >::new(b"{|\n<`a");
+    while block_parser.next().is_some() {}
+    assert!(block_parser.take_diagnostics().is_empty());
+}
+
+#[test]
+fn it_derives_severity_and_message_from_code() {
+    let (_, diagnostics) = crate::parse_with_diagnostics(b"[[a").unwrap();
+    assert_eq!(1, diagnostics.len());
+    let diagnostic = &diagnostics[0];
+    assert_eq!(DiagnosticCode::UnrecognizedWikiLink, diagnostic.code);
+    assert_eq!(Severity::Information, diagnostic.severity);
+    assert_eq!(diagnostic.code.message(), diagnostic.message);
+}
@@ -0,0 +1,258 @@
+//! 骰子表达式（Dicexp）的本地求值器。
+//!
+//! 支持的语法是完整骰子表达式语言的一个子集，只处理整数：
+//! - 整数字面量，如 `42`；
+//! - 掷骰，如 `d100`、`3d6`；
+//! - 在闭区间内随机取整数，如 `3~6`；
+//! - 四则运算 `+`、`-`、`*`、`//`（向下取整的整数除法）与 `%`，以及括号；
+//! - 对赋值骰子表达式（`[@<name>=<code>]`）所赋值的变量的引用，如 `@_balls`。
+//!
+//! 随机数由调用者提供的 [RngCore] 生成，因此只要使用相同种子的可复现随机数生
+//! 成器，求值的结果就总是相同。
+
+mod parser;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use rand_core::RngCore;
+
+use parser::{BinaryOperator, Expr};
+
+/// 单次掷骰时骰子数量的上限。
+pub const MAX_DICE_COUNT: i64 = 1000;
+
+/// 表达式嵌套层数的上限。括号、一元运算符以及每个二元运算符与掷骰各算一层，因
+/// 此 `1+2+3` 的层数是 3。
+pub const MAX_NESTING_DEPTH: usize = 128;
+
+/// 骰子表达式的求值器。
+///
+/// 求值器会记住赋值骰子表达式所赋值的变量，因此应按照骰子表达式在文档中出现的
+/// 顺序依次求值。
+pub struct Evaluator<R: RngCore> {
+    rng: R,
+    variables: HashMap<String, i64>,
+}
+
+/// 求值的结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evaluation {
+    pub value: i64,
+    /// 求值过程中依次进行的各次掷骰。
+    pub rolls: Vec<Roll>,
+}
+
+/// 一次掷骰。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Roll {
+    pub kind: RollKind,
+    /// 每个骰子的点数。对于 [RollKind::Range]，只有一项。
+    pub results: Vec<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RollKind {
+    /// `<count>d<sides>`。
+    Dice { count: i64, sides: i64 },
+    /// `<low>~<high>`。
+    Range { low: i64, high: i64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Parse(ParseError),
+    Runtime(RuntimeError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 出错处在代码中的字节索引。
+    pub position: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnexpectedCharacter,
+    UnexpectedEnd,
+    IntegerTooLarge,
+    InvalidVariableIdentifier,
+    /// 表达式嵌套的层数超过了 [MAX_NESTING_DEPTH]。
+    TooDeeplyNested,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuntimeError {
+    UndefinedVariable(String),
+    /// 骰子数量不在 `1..=MAX_DICE_COUNT` 之中。
+    InvalidDiceCount(i64),
+    /// 骰子面数不为正数。
+    InvalidDiceSides(i64),
+    /// 区间的下界大于上界。
+    InvalidRange {
+        low: i64,
+        high: i64,
+    },
+    DivisionByZero,
+    Overflow,
+}
+
+impl Error {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Error::Parse(_) => "Parse",
+            Error::Runtime(_) => "Runtime",
+        }
+    }
+}
+
+impl<R: RngCore> Evaluator<R> {
+    pub fn new(rng: R) -> Self {
+        Self {
+            rng,
+            variables: HashMap::new(),
+        }
+    }
+
+    /// 返回名称为 `name`（不含用于引用变量的 `@`）的变量的值。
+    pub fn variable(&self, name: &str) -> Option<i64> {
+        self.variables.get(name).copied()
+    }
+
+    /// 设置名称为 `name`（不含用于引用变量的 `@`）的变量的值。
+    pub fn set_variable(&mut self, name: impl Into<String>, value: i64) {
+        self.variables.insert(name.into(), value);
+    }
+
+    /// 对骰子表达式的代码 `code` 求值。若 `assigning_to` 存在，求值成功后会将结
+    /// 果赋给该变量。
+    pub fn evaluate(
+        &mut self,
+        code: &str,
+        assigning_to: Option<&str>,
+    ) -> Result<Evaluation, Error> {
+        let expr = parser::parse(code).map_err(Error::Parse)?;
+
+        let mut rolls = vec![];
+        let value = self
+            .evaluate_expr(&expr, &mut rolls)
+            .map_err(Error::Runtime)?;
+        if let Some(name) = assigning_to {
+            self.set_variable(name, value);
+        }
+
+        Ok(Evaluation { value, rolls })
+    }
+
+    fn evaluate_expr(&mut self, expr: &Expr, rolls: &mut Vec<Roll>) -> Result<i64, RuntimeError> {
+        match expr {
+            Expr::Integer(value) => Ok(*value),
+            Expr::Variable(name) => self
+                .variable(name)
+                .ok_or_else(|| RuntimeError::UndefinedVariable(name.clone())),
+            Expr::Negation(operand) => self
+                .evaluate_expr(operand, rolls)?
+                .checked_neg()
+                .ok_or(RuntimeError::Overflow),
+            Expr::Binary(BinaryOperator::Range, low, high) => {
+                let low = self.evaluate_expr(low, rolls)?;
+                let high = self.evaluate_expr(high, rolls)?;
+                if low > high {
+                    return Err(RuntimeError::InvalidRange { low, high });
+                }
+
+                let value = self.roll_in_range(low, high);
+                rolls.push(Roll {
+                    kind: RollKind::Range { low, high },
+                    results: vec![value],
+                });
+                Ok(value)
+            }
+            Expr::Binary(operator, left, right) => {
+                let left = self.evaluate_expr(left, rolls)?;
+                let right = self.evaluate_expr(right, rolls)?;
+                evaluate_arithmetic(*operator, left, right)
+            }
+            Expr::Dice { count, sides } => {
+                let count = match count {
+                    Some(count) => self.evaluate_expr(count, rolls)?,
+                    None => 1,
+                };
+                let sides = self.evaluate_expr(sides, rolls)?;
+                if !(1..=MAX_DICE_COUNT).contains(&count) {
+                    return Err(RuntimeError::InvalidDiceCount(count));
+                }
+                if sides < 1 {
+                    return Err(RuntimeError::InvalidDiceSides(sides));
+                }
+
+                let results: Vec<i64> = (0..count).map(|_| self.roll_in_range(1, sides)).collect();
+                // 骰子数量与面数都有限，但总和仍可能溢出。
+                let sum = results
+                    .iter()
+                    .try_fold(0i64, |acc, result| acc.checked_add(*result))
+                    .ok_or(RuntimeError::Overflow)?;
+                rolls.push(Roll {
+                    kind: RollKind::Dice { count, sides },
+                    results,
+                });
+                Ok(sum)
+            }
+        }
+    }
+
+    /// 在 `low..=high` 中均匀地随机取一个整数。调用者应保证 `low` <= `high`。
+    fn roll_in_range(&mut self, low: i64, high: i64) -> i64 {
+        // 区间的宽度。为 0 时代表宽度为 2^64，即整个 `i64` 的范围。
+        let width = (high as u64).wrapping_sub(low as u64).wrapping_add(1);
+        if width == 0 {
+            return self.rng.next_u64() as i64;
+        }
+
+        // 拒绝采样，以免取模带来的偏差。
+        let threshold = width.wrapping_neg() % width;
+        loop {
+            let value = self.rng.next_u64();
+            if value >= threshold {
+                return low.wrapping_add((value % width) as i64);
+            }
+        }
+    }
+}
+
+fn evaluate_arithmetic(
+    operator: BinaryOperator,
+    left: i64,
+    right: i64,
+) -> Result<i64, RuntimeError> {
+    let result = match operator {
+        BinaryOperator::Add => left.checked_add(right),
+        BinaryOperator::Subtract => left.checked_sub(right),
+        BinaryOperator::Multiply => left.checked_mul(right),
+        BinaryOperator::FloorDivide | BinaryOperator::Remainder if right == 0 => {
+            return Err(RuntimeError::DivisionByZero)
+        }
+        BinaryOperator::FloorDivide => left.checked_div(right).map(|quotient| {
+            // 整数除法默认向零取整，被除数与除数异号且除不尽时需要再减一。
+            if left % right != 0 && (left < 0) != (right < 0) {
+                quotient - 1
+            } else {
+                quotient
+            }
+        }),
+        BinaryOperator::Remainder => left.checked_rem(right).map(|remainder| {
+            // 默认的余数与被除数同号，需要修正为与除数同号。
+            if remainder != 0 && (remainder < 0) != (right < 0) {
+                remainder + right
+            } else {
+                remainder
+            }
+        }),
+        BinaryOperator::Range => unreachable!(),
+    };
+
+    result.ok_or(RuntimeError::Overflow)
+}
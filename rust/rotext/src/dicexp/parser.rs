@@ -0,0 +1,281 @@
+use crate::common::is_valid_variable_identifier;
+
+use super::{ParseError, ParseErrorKind, MAX_NESTING_DEPTH};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Expr {
+    Integer(i64),
+    /// 不含开头用于引用变量的 `@`。
+    Variable(String),
+    Negation(Box<Expr>),
+    Binary(BinaryOperator, Box<Expr>, Box<Expr>),
+    /// `[count]d<sides>`。省略 `count` 时视为 1。
+    Dice {
+        count: Option<Box<Expr>>,
+        sides: Box<Expr>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    /// `//`，向下取整的整数除法。
+    FloorDivide,
+    /// `%`，结果的符号与除数相同。
+    Remainder,
+    /// `~`，在闭区间内随机取一个整数。
+    Range,
+}
+
+/// 将骰子表达式的代码解析为表达式树。
+///
+/// 语法（优先级由低到高）：
+///
+/// ```text
+/// expr     = additive ;
+/// additive = multiplicative , { ( "+" | "-" ) , multiplicative } ;
+/// multiplicative = unary , { ( "*" | "//" | "%" ) , unary } ;
+/// unary    = ( "+" | "-" ) , unary | range ;
+/// range    = dice , [ "~" , dice ] ;
+/// dice     = "d" , dice | primary , { "d" , dice } ;
+/// primary  = integer | "@" , identifier | "(" , expr , ")" ;
+/// ```
+///
+/// 表达式树的深度（包括括号的层数）超过 [MAX_NESTING_DEPTH] 时报错，以免解析与求值
+/// 时的递归耗尽栈空间。
+pub fn parse(code: &str) -> Result<Expr, ParseError> {
+    let mut parser = Parser {
+        code: code.as_bytes(),
+        position: 0,
+        depth: 0,
+    };
+
+    let (expr, _) = parser.parse_additive()?;
+    parser.skip_whitespaces();
+    if parser.position < parser.code.len() {
+        return Err(parser.make_error(ParseErrorKind::UnexpectedCharacter));
+    }
+
+    Ok(expr)
+}
+
+struct Parser<'a> {
+    code: &'a [u8],
+    position: usize,
+    /// 正在解析的表达式外层的表达式与括号的层数。
+    depth: usize,
+}
+
+/// 解析出的表达式，及其深度（不计外层）。
+type Parsed = Result<(Expr, usize), ParseError>;
+
+impl Parser<'_> {
+    fn make_error(&self, kind: ParseErrorKind) -> ParseError {
+        let kind = if self.position < self.code.len() {
+            kind
+        } else {
+            ParseErrorKind::UnexpectedEnd
+        };
+        ParseError {
+            position: self.position,
+            kind,
+        }
+    }
+
+    /// 检查深度为 `depth` 的表达式在加上外层后是否过深。
+    fn check_depth(&self, depth: usize) -> Result<(), ParseError> {
+        if self.depth + depth > MAX_NESTING_DEPTH {
+            // 不使用 `make_error`，以免在代码末尾时被报告为 `UnexpectedEnd`。
+            Err(ParseError {
+                position: self.position,
+                kind: ParseErrorKind::TooDeeplyNested,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 解析位于外层之下一层的子表达式。
+    fn parse_nested(&mut self, parse: fn(&mut Self) -> Parsed) -> Parsed {
+        self.depth += 1;
+        let result = self.check_depth(1).and_then(|_| parse(self));
+        self.depth -= 1;
+        result
+    }
+
+    /// 由运算符连接起的深度分别为 `left_depth` 与 `right_depth` 的两个子表达式构
+    /// 造表达式。
+    fn make_binary(&self, expr: Expr, left_depth: usize, right_depth: usize) -> Parsed {
+        let depth = left_depth.max(right_depth) + 1;
+        self.check_depth(depth)?;
+        Ok((expr, depth))
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while self
+            .code
+            .get(self.position)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.position += 1;
+        }
+    }
+
+    /// 跳过空白后，若接下来是 `token`，消耗之并返回 `true`。
+    fn eat(&mut self, token: &[u8]) -> bool {
+        self.skip_whitespaces();
+        if self.code[self.position..].starts_with(token) {
+            self.position += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_additive(&mut self) -> Parsed {
+        let (mut left, mut left_depth) = self.parse_multiplicative()?;
+        loop {
+            let operator = if self.eat(b"+") {
+                BinaryOperator::Add
+            } else if self.eat(b"-") {
+                BinaryOperator::Subtract
+            } else {
+                break Ok((left, left_depth));
+            };
+            let (right, right_depth) = self.parse_nested(Self::parse_multiplicative)?;
+            let expr = Expr::Binary(operator, Box::new(left), Box::new(right));
+            (left, left_depth) = self.make_binary(expr, left_depth, right_depth)?;
+        }
+    }
+
+    fn parse_multiplicative(&mut self) -> Parsed {
+        let (mut left, mut left_depth) = self.parse_unary()?;
+        loop {
+            let operator = if self.eat(b"*") {
+                BinaryOperator::Multiply
+            } else if self.eat(b"//") {
+                BinaryOperator::FloorDivide
+            } else if self.eat(b"%") {
+                BinaryOperator::Remainder
+            } else {
+                break Ok((left, left_depth));
+            };
+            let (right, right_depth) = self.parse_nested(Self::parse_unary)?;
+            let expr = Expr::Binary(operator, Box::new(left), Box::new(right));
+            (left, left_depth) = self.make_binary(expr, left_depth, right_depth)?;
+        }
+    }
+
+    fn parse_unary(&mut self) -> Parsed {
+        if self.eat(b"-") {
+            let (operand, depth) = self.parse_nested(Self::parse_unary)?;
+            Ok((Expr::Negation(Box::new(operand)), depth + 1))
+        } else if self.eat(b"+") {
+            // 不产生新的表达式，但仍需计入层数以限制递归的深度。
+            let (operand, depth) = self.parse_nested(Self::parse_unary)?;
+            Ok((operand, depth + 1))
+        } else {
+            self.parse_range()
+        }
+    }
+
+    fn parse_range(&mut self) -> Parsed {
+        let (low, low_depth) = self.parse_dice()?;
+        if !self.eat(b"~") {
+            return Ok((low, low_depth));
+        }
+        let (high, high_depth) = self.parse_nested(Self::parse_dice)?;
+
+        let expr = Expr::Binary(BinaryOperator::Range, Box::new(low), Box::new(high));
+        self.make_binary(expr, low_depth, high_depth)
+    }
+
+    fn parse_dice(&mut self) -> Parsed {
+        let (mut expr, mut depth) = if self.eat(b"d") {
+            let (sides, sides_depth) = self.parse_nested(Self::parse_dice)?;
+            let expr = Expr::Dice {
+                count: None,
+                sides: Box::new(sides),
+            };
+            (expr, sides_depth + 1)
+        } else {
+            self.parse_primary()?
+        };
+        while self.eat(b"d") {
+            let (sides, sides_depth) = self.parse_nested(Self::parse_dice)?;
+            let dice = Expr::Dice {
+                count: Some(Box::new(expr)),
+                sides: Box::new(sides),
+            };
+            (expr, depth) = self.make_binary(dice, depth, sides_depth)?;
+        }
+
+        Ok((expr, depth))
+    }
+
+    fn parse_primary(&mut self) -> Parsed {
+        self.skip_whitespaces();
+        match self.code.get(self.position) {
+            Some(b'0'..=b'9') => Ok((self.parse_integer()?, 1)),
+            Some(b'@') => {
+                self.position += 1;
+                Ok((self.parse_variable()?, 1))
+            }
+            Some(b'(') => {
+                self.position += 1;
+                let (expr, depth) = self.parse_nested(Self::parse_additive)?;
+                if !self.eat(b")") {
+                    return Err(self.make_error(ParseErrorKind::UnexpectedCharacter));
+                }
+                Ok((expr, depth + 1))
+            }
+            _ => Err(self.make_error(ParseErrorKind::UnexpectedCharacter)),
+        }
+    }
+
+    fn parse_integer(&mut self) -> Result<Expr, ParseError> {
+        let start = self.position;
+        let mut value: Option<i64> = Some(0);
+        while let Some(digit @ b'0'..=b'9') = self.code.get(self.position) {
+            value = value
+                .and_then(|value| value.checked_mul(10))
+                .and_then(|value| value.checked_add((digit - b'0') as i64));
+            self.position += 1;
+        }
+
+        value.map(Expr::Integer).ok_or(ParseError {
+            position: start,
+            kind: ParseErrorKind::IntegerTooLarge,
+        })
+    }
+
+    /// 调用者应保证位于 `@` 之后。
+    fn parse_variable(&mut self) -> Result<Expr, ParseError> {
+        let start = self.position;
+        // 标识符中的 ASCII 字符只可能是字母、数字以及 `_`、`.`、`@`。
+        while let Some(char) = self.code.get(self.position) {
+            if char.is_ascii()
+                && !char.is_ascii_alphanumeric()
+                && !matches!(char, b'_' | b'.' | b'@')
+            {
+                break;
+            }
+            self.position += 1;
+        }
+
+        let name = &self.code[start..self.position];
+        if !is_valid_variable_identifier(name) {
+            return Err(ParseError {
+                position: start,
+                kind: ParseErrorKind::InvalidVariableIdentifier,
+            });
+        }
+
+        // SAFETY: `is_valid_variable_identifier` 已经确认了 `name` 是有效的
+        // UTF-8。
+        let name = unsafe { std::str::from_utf8_unchecked(name) };
+        Ok(Expr::Variable(name.to_string()))
+    }
+}
@@ -0,0 +1,269 @@
+use rand_core::{RngCore, SeedableRng};
+use rand_xoshiro::Xoshiro256PlusPlus;
+
+use super::{
+    Error, Evaluation, Evaluator, ParseError, ParseErrorKind, Roll, RollKind, RuntimeError,
+    MAX_NESTING_DEPTH,
+};
+
+/// 依次循环产出给定数值的随机数生成器。
+struct SequenceRng {
+    values: Vec<u64>,
+    index: usize,
+}
+impl SequenceRng {
+    fn new(values: Vec<u64>) -> Self {
+        Self { values, index: 0 }
+    }
+}
+impl RngCore for SequenceRng {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let value = self.values[self.index % self.values.len()];
+        self.index += 1;
+        value
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+fn evaluate(code: &str, rng_values: Vec<u64>) -> Result<Evaluation, Error> {
+    Evaluator::new(SequenceRng::new(rng_values)).evaluate(code, None)
+}
+
+fn value_of(code: &str) -> Result<i64, Error> {
+    evaluate(code, vec![0]).map(|evaluation| evaluation.value)
+}
+
+#[test]
+fn it_evaluates_arithmetic() {
+    let table: &[(&str, i64)] = &[
+        ("42", 42),
+        ("1+2*3", 7),
+        ("(1+2)*3", 9),
+        (" 1 +\n2 ", 3),
+        ("-3", -3),
+        ("--3", 3),
+        ("+3", 3),
+        ("2-3-4", -5),
+        ("7//2", 3),
+        ("-7//2", -4),
+        ("7//-2", -4),
+        ("-7//-2", 3),
+        ("7%3", 1),
+        ("-7%3", 2),
+        ("7%-3", -2),
+        ("6%-3", 0),
+    ];
+    for (code, expected) in table {
+        assert_eq!(Ok(*expected), value_of(code), "code: {:?}", code);
+    }
+}
+
+#[test]
+fn it_rolls_dice() {
+    // 对于 d100，随机数 `141` 对应的点数是 `1 + 141 % 100`，即 42。
+    assert_eq!(
+        Ok(Evaluation {
+            value: 42,
+            rolls: vec![Roll {
+                kind: RollKind::Dice {
+                    count: 1,
+                    sides: 100
+                },
+                results: vec![42],
+            }],
+        }),
+        evaluate("d100", vec![141]),
+    );
+
+    assert_eq!(
+        Ok(Evaluation {
+            value: 60,
+            rolls: vec![Roll {
+                kind: RollKind::Dice { count: 3, sides: 6 },
+                results: vec![5, 6, 1],
+            }],
+        }),
+        evaluate("3d6*5", vec![10, 11, 12]),
+    );
+
+    assert_eq!(
+        Ok(Evaluation {
+            value: 4,
+            rolls: vec![Roll {
+                kind: RollKind::Range { low: 3, high: 6 },
+                results: vec![4],
+            }],
+        }),
+        evaluate("3~6", vec![5]),
+    );
+
+    // 掷骰的先后顺序与求值顺序一致。
+    assert_eq!(
+        Ok(Evaluation {
+            value: 3,
+            rolls: vec![
+                Roll {
+                    kind: RollKind::Dice { count: 1, sides: 2 },
+                    results: vec![2],
+                },
+                Roll {
+                    kind: RollKind::Dice { count: 2, sides: 1 },
+                    results: vec![1, 1],
+                },
+                Roll {
+                    kind: RollKind::Range { low: -1, high: 0 },
+                    results: vec![-1],
+                },
+            ],
+        }),
+        evaluate("d2 + 2d1 + (-1)~0", vec![1, 0, 0, 0]),
+    );
+}
+
+#[test]
+fn it_rejects_biased_random_numbers() {
+    // 对于 d6，小于 `2^64 % 6`（即 4）的随机数会被舍弃。
+    assert_eq!(Ok(4), evaluate("d6", vec![0, 9]).map(|e| e.value));
+    assert_eq!(Ok(4), evaluate("d6", vec![3, 9]).map(|e| e.value));
+    assert_eq!(Ok(5), evaluate("d6", vec![4, 9]).map(|e| e.value));
+}
+
+#[test]
+fn it_assigns_and_references_variables() {
+    let mut evaluator = Evaluator::new(SequenceRng::new(vec![141, 29]));
+
+    let balls = evaluator.evaluate("d100", Some("_balls")).unwrap();
+    assert_eq!(42, balls.value);
+    assert_eq!(Some(42), evaluator.variable("_balls"));
+
+    // 对于 d42，随机数 `29` 对应的点数是 30。
+    let red_balls = evaluator.evaluate("d(@_balls)", Some("_redBalls")).unwrap();
+    assert_eq!(30, red_balls.value);
+
+    let blue_balls = evaluator.evaluate("@_balls-@_redBalls", None).unwrap();
+    assert_eq!(12, blue_balls.value);
+    assert!(blue_balls.rolls.is_empty());
+
+    evaluator.set_variable("foo.bar", 3);
+    assert_eq!(
+        Ok(6),
+        evaluator.evaluate("@foo.bar*2", None).map(|e| e.value)
+    );
+}
+
+#[test]
+fn it_reports_parse_errors() {
+    let table: &[(&str, usize, ParseErrorKind)] = &[
+        ("", 0, ParseErrorKind::UnexpectedEnd),
+        ("1+", 2, ParseErrorKind::UnexpectedEnd),
+        ("(1", 2, ParseErrorKind::UnexpectedEnd),
+        ("1 2", 2, ParseErrorKind::UnexpectedCharacter),
+        ("1+*2", 2, ParseErrorKind::UnexpectedCharacter),
+        ("d", 1, ParseErrorKind::UnexpectedEnd),
+        ("3~-1", 2, ParseErrorKind::UnexpectedCharacter),
+        ("99999999999999999999", 0, ParseErrorKind::IntegerTooLarge),
+        ("@balls", 1, ParseErrorKind::InvalidVariableIdentifier),
+        ("1+@", 3, ParseErrorKind::InvalidVariableIdentifier),
+    ];
+    for (code, position, kind) in table {
+        assert_eq!(
+            Err(Error::Parse(ParseError {
+                position: *position,
+                kind: *kind
+            })),
+            value_of(code),
+            "code: {:?}",
+            code
+        );
+    }
+}
+
+#[test]
+fn it_rejects_too_deeply_nested_expressions() {
+    let flat_sum = |terms: usize| format!("1{}", "+1".repeat(terms - 1));
+    let limit = MAX_NESTING_DEPTH;
+    assert_eq!(Ok(limit as i64), value_of(&flat_sum(limit)));
+
+    let codes = [
+        flat_sum(limit + 1),
+        flat_sum(200_000),
+        format!("{}1{}", "(".repeat(20_000), ")".repeat(20_000)),
+        format!("{}1", "-".repeat(50_000)),
+        format!("{}1", "+".repeat(50_000)),
+        format!("{}6", "d".repeat(50_000)),
+        format!("1{}", "d1".repeat(50_000)),
+        format!("1{}", "~(1".repeat(20_000)),
+    ];
+    for code in codes {
+        assert!(
+            matches!(
+                value_of(&code),
+                Err(Error::Parse(ParseError {
+                    kind: ParseErrorKind::TooDeeplyNested,
+                    ..
+                }))
+            ),
+            "code: {:?}…",
+            &code[..10]
+        );
+    }
+}
+
+#[test]
+fn it_reports_runtime_errors() {
+    let table: &[(&str, RuntimeError)] = &[
+        ("@_x", RuntimeError::UndefinedVariable("_x".to_string())),
+        ("0d6", RuntimeError::InvalidDiceCount(0)),
+        ("1001d6", RuntimeError::InvalidDiceCount(1001)),
+        ("d0", RuntimeError::InvalidDiceSides(0)),
+        ("d(-1)", RuntimeError::InvalidDiceSides(-1)),
+        ("6~3", RuntimeError::InvalidRange { low: 6, high: 3 }),
+        ("1//0", RuntimeError::DivisionByZero),
+        ("1%0", RuntimeError::DivisionByZero),
+        ("9223372036854775807+1", RuntimeError::Overflow),
+        ("-9223372036854775807-1-1", RuntimeError::Overflow),
+        ("(-9223372036854775807-1)//-1", RuntimeError::Overflow),
+    ];
+    for (code, error) in table {
+        assert_eq!(
+            Err(Error::Runtime(error.clone())),
+            value_of(code),
+            "code: {:?}",
+            code
+        );
+    }
+}
+
+#[test]
+fn it_is_deterministic_with_the_same_seed() {
+    let evaluate_with_seed = |seed: u64| {
+        let mut evaluator = Evaluator::new(Xoshiro256PlusPlus::seed_from_u64(seed));
+        evaluator.evaluate("10d6 + 3~6", Some("_x")).unwrap()
+    };
+
+    let evaluation = evaluate_with_seed(42);
+    assert_eq!(evaluation, evaluate_with_seed(42));
+
+    let [dice, range] = &evaluation.rolls[..] else {
+        panic!("unexpected rolls: {:?}", evaluation.rolls)
+    };
+    assert_eq!(10, dice.results.len());
+    assert!(dice.results.iter().all(|result| (1..=6).contains(result)));
+    assert!((3..=6).contains(&range.results[0]));
+    assert_eq!(
+        dice.results.iter().sum::<i64>() + range.results[0],
+        evaluation.value
+    );
+}
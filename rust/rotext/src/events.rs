@@ -1,6 +1,7 @@
 use std::ops::Range;
 
-use crate::types::{BlockId, LineNumber};
+use crate::attributes;
+use crate::types::{BlockId, LineNumber, SourceSpan};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -13,6 +14,7 @@ pub enum EventType {
     NewLine = 201,
     VerbatimEscaping = 202,
     Text = 203,
+    IndicateExtensionParameter = 204,
 
     // 在块级阶段产出。
     ThematicBreak = 8,
@@ -32,20 +34,24 @@ pub enum EventType {
     EnterDescriptionDetails = 17,
     EnterCodeBlock = 21,
     EnterTable = 31,
+    EnterBlockExtension = 41,
     IndicateCodeBlockCode = 22,
     IndicateTableCaption = 35,
     IndicateTableRow = 32,
     IndicateTableHeaderCell = 33,
     IndicateTableDataCell = 34,
+    IndicateBlockExtensionSlot = 42,
     ExitBlock = 99,
 
     // 在行内阶段产出。
     RefLink = 101,
-    Dicexp = 102,
+    EnterDicexp = 102,
     EnterCodeSpan = 111,
     EnterStrong = 112,
     EnterStrikethrough = 113,
     EnterWikiLink = 121,
+    EnterInlineExtension = 131,
+    IndicateInlineExtensionSlot = 132,
     ExitInline = 199,
 }
 
@@ -147,51 +153,71 @@ pub enum Event {
     /// 进入表格。
     #[groups(Block | Blend)]
     EnterTable(BlockWithId) = EventType::EnterTable as u8,
+    /// 进入块级扩展。
+    #[groups(Block | Blend)]
+    EnterBlockExtension(BlockExtension) = EventType::EnterBlockExtension as u8,
 
     /// 指示到达代码块的代码部分。
     #[groups(Block | Blend)]
-    IndicateCodeBlockCode = EventType::IndicateCodeBlockCode as u8,
+    IndicateCodeBlockCode(Indication) = EventType::IndicateCodeBlockCode as u8,
     /// 指示到达表格标题。
     #[groups(Block | Blend)]
-    IndicateTableCaption = EventType::IndicateTableCaption as u8,
+    IndicateTableCaption(Indication) = EventType::IndicateTableCaption as u8,
     /// 指示到达（新）表格行。
     #[groups(Block | Blend)]
-    IndicateTableRow = EventType::IndicateTableRow as u8,
+    IndicateTableRow(Indication) = EventType::IndicateTableRow as u8,
     /// 指示到达（新）表格头部单元格。
     #[groups(Block | Blend)]
-    IndicateTableHeaderCell = EventType::IndicateTableHeaderCell as u8,
+    IndicateTableHeaderCell(TableCell) = EventType::IndicateTableHeaderCell as u8,
     /// 指示到达（新）表格数据单元格。
     #[groups(Block | Blend)]
-    IndicateTableDataCell = EventType::IndicateTableDataCell as u8,
+    IndicateTableDataCell(TableCell) = EventType::IndicateTableDataCell as u8,
+    /// 指示到达（新）块级扩展的子内容槽位。
+    #[groups(Block | Blend)]
+    IndicateBlockExtensionSlot(Indication) = EventType::IndicateBlockExtensionSlot as u8,
+    /// 指示到达（新）扩展的逐字参数。参数的值为其后直到下个指示或退出事件之前的
+    /// `Text` 与 `VerbatimEscaping` 事件。
+    ///
+    /// 块级扩展与行内扩展共用本事件。
+    #[groups(Block | Inline | Blend)]
+    IndicateExtensionParameter(ExtensionParameter) = EventType::IndicateExtensionParameter as u8,
 
     /// 退出一层块级的 “进入…”。
     #[groups(Block | Blend)]
     ExitBlock(ExitBlock) = EventType::ExitBlock as u8,
 
-    /// 引用链接。
+    /// 引用链接。内容不含开头的 `>>`，可以通过 [RefLinkTarget::parse] 解析。
     #[groups(Inline | Blend)]
     RefLink(Range<usize>) = EventType::RefLink as u8,
-    /// Dicexp。
+    /// 进入 Dicexp。之后直到对应的 `ExitInline` 之间只有 `Text` 与 `NewLine` 事
+    /// 件，它们构成骰子表达式的代码。内容是被赋值的变量的标识符（若有）。
     #[groups(Inline | Blend)]
-    Dicexp(Range<usize>) = EventType::Dicexp as u8,
+    EnterDicexp(Dicexp) = EventType::EnterDicexp as u8,
 
     /// 进入行内代码。
     #[groups(Inline | Blend)]
-    EnterCodeSpan = EventType::EnterCodeSpan as u8,
+    EnterCodeSpan(InlineElement) = EventType::EnterCodeSpan as u8,
     /// 进入加粗强调。
     #[groups(Inline | Blend)]
-    EnterStrong = EventType::EnterStrong as u8,
+    EnterStrong(InlineElement) = EventType::EnterStrong as u8,
     /// 进入删除线。
     #[groups(Inline | Blend)]
-    EnterStrikethrough = EventType::EnterStrikethrough as u8,
+    EnterStrikethrough(InlineElement) = EventType::EnterStrikethrough as u8,
 
     // 进入Wiki链接。
     #[groups(Inline | Blend)]
-    EnterWikiLink(Range<usize>) = EventType::EnterWikiLink as u8,
+    EnterWikiLink(WikiLink) = EventType::EnterWikiLink as u8,
+    /// 进入行内扩展。
+    #[groups(Inline | Blend)]
+    EnterInlineExtension(InlineExtension) = EventType::EnterInlineExtension as u8,
+
+    /// 指示到达（新）行内扩展的子内容槽位。
+    #[groups(Inline | Blend)]
+    IndicateInlineExtensionSlot(Indication) = EventType::IndicateInlineExtensionSlot as u8,
 
     /// 退出一层行内的 “进入…”。
     #[groups(Inline | Blend)]
-    ExitInline = EventType::ExitInline as u8,
+    ExitInline(ExitInline) = EventType::ExitInline as u8,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -209,17 +235,198 @@ pub struct NewLine {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BlockWithId {
     pub id: BlockId,
+    /// 附着于块级元素的块级附加信息（`{! … }`）在输入中的范围。存在多组时，范
+    /// 围从第一组的开头延续到最后一组的结尾。
+    pub attributes: Option<Range<usize>>,
+    /// 开启部分（如 `== `、`# `、`{|`）在输入中的范围，不含附加信息。段落没有
+    /// 开启部分，范围是其开头处的空范围；列表与描述列表的范围是其第一项的标记开
+    /// 头处的空范围。
+    pub span: SourceSpan,
 }
-impl From<BlockId> for BlockWithId {
-    fn from(value: BlockId) -> Self {
-        Self { id: value }
-    }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockExtension {
+    pub id: BlockId,
+    pub name: Range<usize>,
+    /// 见 [BlockWithId::attributes]。
+    pub attributes: Option<Range<usize>>,
+    /// 开启部分（`{{#` 及扩展的名称）在输入中的范围。
+    pub span: SourceSpan,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionParameter {
+    /// 参数的名称。对于没有名称的参数，为 `None`。
+    pub name: Option<Range<usize>>,
+    /// 指示标记（块级扩展为 `??`，行内扩展为 `?`）在输入中的范围。
+    pub span: SourceSpan,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ThematicBreak {
     pub id: BlockId,
     pub line: LineNumber,
+    /// 见 [BlockWithId::attributes]。
+    pub attributes: Option<Range<usize>>,
+    /// 标记（`---`）在输入中的范围。
+    pub span: SourceSpan,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dicexp {
+    /// 对于赋值骰子表达式（`[@<name>=<code>]`），为被赋值的变量的标识符。
+    pub assigning_to: Option<Range<usize>>,
+    /// 开启部分（`[=` 或 `[@<name>=`）在输入中的范围。
+    pub span: SourceSpan,
+}
+
+/// 引用链接（[Event::RefLink]）的目标。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefLinkTarget<'a> {
+    /// `#<floor>`，当前串中的楼层。
+    Floor(u64),
+    /// `<prefix>.<thread_id>` 或 `<prefix>.<thread_id>#<floor>`。
+    Thread {
+        prefix: &'a str,
+        thread_id: &'a str,
+        floor: Option<u64>,
+    },
+    /// `<prefix>.<post_number>`。
+    Post { prefix: &'a str, post_number: u64 },
+}
+
+impl<'a> RefLinkTarget<'a> {
+    /// 解析引用链接的内容（即 [Event::RefLink] 的范围所对应的输入，不含开头的
+    /// `>>`）。内容无效，或者其中的数字超出 `u64` 的范围时，返回 `None`。
+    pub fn parse(address: &'a [u8]) -> Option<Self> {
+        if let Some(floor) = address.strip_prefix(b"#") {
+            return Some(Self::Floor(parse_ref_link_number(floor)?));
+        }
+
+        let dot_index = address.iter().position(|char| *char == b'.')?;
+        let prefix = parse_ref_link_alphabetic_part(&address[..dot_index])?;
+        let rest = &address[dot_index + 1..];
+        if rest.first()?.is_ascii_digit() {
+            return Some(Self::Post {
+                prefix,
+                post_number: parse_ref_link_number(rest)?,
+            });
+        }
+
+        let (thread_id, floor) = match rest.iter().position(|char| *char == b'#') {
+            Some(sharp_index) => (
+                &rest[..sharp_index],
+                Some(parse_ref_link_number(&rest[sharp_index + 1..])?),
+            ),
+            None => (rest, None),
+        };
+        Some(Self::Thread {
+            prefix,
+            thread_id: parse_ref_link_alphabetic_part(thread_id)?,
+            floor,
+        })
+    }
+}
+
+fn parse_ref_link_alphabetic_part(part: &[u8]) -> Option<&str> {
+    if part.is_empty() || !part.iter().all(u8::is_ascii_alphabetic) {
+        return None;
+    }
+    // SAFETY: `part` 中只有 ASCII 字母。
+    Some(unsafe { std::str::from_utf8_unchecked(part) })
+}
+
+fn parse_ref_link_number(digits: &[u8]) -> Option<u64> {
+    if digits.is_empty() {
+        return None;
+    }
+    digits.iter().try_fold(0u64, |acc, digit| {
+        if !digit.is_ascii_digit() {
+            return None;
+        }
+        acc.checked_mul(10)?.checked_add((digit - b'0') as u64)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineElement {
+    /// 附着于行内元素的行内附加信息（`[! … ]`）在输入中的范围。存在多组时，范
+    /// 围从第一组的开头延续到最后一组的结尾。
+    pub attributes: Option<Range<usize>>,
+    /// 开启部分（如 `['`、`` [` ``）在输入中的范围，不含附加信息。
+    pub span: SourceSpan,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WikiLink {
+    pub address: Range<usize>,
+    /// 见 [InlineElement::attributes]。
+    pub attributes: Option<Range<usize>>,
+    /// 开启部分（`[[`）在输入中的范围。
+    pub span: SourceSpan,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineExtension {
+    pub name: Range<usize>,
+    /// 见 [InlineElement::attributes]。
+    pub attributes: Option<Range<usize>>,
+    /// 开启部分（`[{#` 及扩展的名称）在输入中的范围。
+    pub span: SourceSpan,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableCell {
+    /// 见 [BlockWithId::attributes]。
+    pub attributes: Option<Range<usize>>,
+    /// 指示标记（`!!` 或 `||`）在输入中的范围。
+    pub span: SourceSpan,
+}
+
+/// HTML 中 `colspan` 的上限。
+const MAX_COLSPAN: usize = 1000;
+/// HTML 中 `rowspan` 的上限。
+const MAX_ROWSPAN: usize = 65534;
+
+impl TableCell {
+    /// 从附着于单元格的附加信息中取得跨列（`colspan`）与跨行（`rowspan`）的数
+    /// 量，即 `(colspan, rowspan)`。未指定或无效的值视为 1，超出 HTML 上限的值
+    /// 视为上限。
+    ///
+    /// 这里不考虑表格实际的大小，渲染时还会按照表格的网格进一步限制。
+    pub fn spans(&self, input: &[u8]) -> (usize, usize) {
+        let Some(attributes) = &self.attributes else {
+            return (1, 1);
+        };
+        let parse = |key: &[u8], max: usize| {
+            attributes::find_value(input, attributes.clone(), key)
+                .and_then(|value| parse_positive_integer(&input[value]))
+                .map_or(1, |value| value.min(max))
+        };
+
+        (
+            parse(b"colspan", MAX_COLSPAN),
+            parse(b"rowspan", MAX_ROWSPAN),
+        )
+    }
+}
+
+fn parse_positive_integer(value: &[u8]) -> Option<usize> {
+    if value.is_empty() || !value.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let value = value.iter().try_fold(0usize, |acc, digit| {
+        acc.checked_mul(10)?.checked_add((digit - b'0') as usize)
+    });
+    // 溢出的值也必然超出上限。
+    Some(value.unwrap_or(usize::MAX)).filter(|value| *value > 0)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Indication {
+    /// 指示标记（如 `|-`、`|+`、`||`）在输入中的范围。没有指示标记时（如代码块
+    /// 的代码部分、隐式开启的块级扩展的第一个子内容），为空范围。
+    pub span: SourceSpan,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -227,6 +434,16 @@ pub struct ExitBlock {
     pub id: BlockId,
     pub start_line: LineNumber,
     pub end_line: LineNumber,
+    /// 闭合部分（如 `|}`、`}}`、代码块的闭合栅栏、标题结尾的 `==`）在输入中的
+    /// 范围。没有闭合部分时，为块级元素结尾处的空范围。
+    pub span: SourceSpan,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExitInline {
+    /// 闭合部分（如 `']`、`]]`、`}]`）在输入中的范围。被隐式闭合时，为行内元素
+    /// 结尾处的空范围。
+    pub span: SourceSpan,
 }
 
 impl Event {
@@ -242,43 +459,135 @@ impl Event {
             | Event::VerbatimEscaping(VerbatimEscaping { content, .. })
             | Event::Text(content)
             | Event::RefLink(content)
-            | Event::Dicexp(content)
-            | Event::EnterWikiLink(content) => unsafe {
-                std::str::from_utf8_unchecked(&input[content.clone()])
-            },
+            | Event::EnterDicexp(Dicexp {
+                assigning_to: Some(content),
+                ..
+            })
+            | Event::EnterWikiLink(WikiLink {
+                address: content, ..
+            })
+            | Event::EnterInlineExtension(InlineExtension { name: content, .. })
+            | Event::EnterBlockExtension(BlockExtension { name: content, .. })
+            | Event::IndicateExtensionParameter(ExtensionParameter {
+                name: Some(content),
+                ..
+            })
+            // 对于没有其他内容的块级元素，内容是附着于其上的块级附加信息。
+            | Event::ThematicBreak(ThematicBreak {
+                attributes: Some(content),
+                ..
+            })
+            | Event::IndicateTableHeaderCell(TableCell {
+                attributes: Some(content),
+                ..
+            })
+            | Event::IndicateTableDataCell(TableCell {
+                attributes: Some(content),
+                ..
+            })
+            // 同上，对于没有其他内容的行内元素，内容是附着于其上的行内附加信息。
+            | Event::EnterCodeSpan(InlineElement {
+                attributes: Some(content),
+                ..
+            })
+            | Event::EnterStrong(InlineElement {
+                attributes: Some(content),
+                ..
+            })
+            | Event::EnterStrikethrough(InlineElement {
+                attributes: Some(content),
+                ..
+            }) => unsafe { std::str::from_utf8_unchecked(&input[content.clone()]) },
+            Event::EnterParagraph(data)
+            | Event::EnterHeading1(data)
+            | Event::EnterHeading2(data)
+            | Event::EnterHeading3(data)
+            | Event::EnterHeading4(data)
+            | Event::EnterHeading5(data)
+            | Event::EnterHeading6(data)
+            | Event::EnterBlockQuote(data)
+            | Event::EnterOrderedList(data)
+            | Event::EnterUnorderedList(data)
+            | Event::EnterListItem(data)
+            | Event::EnterDescriptionList(data)
+            | Event::EnterDescriptionTerm(data)
+            | Event::EnterDescriptionDetails(data)
+            | Event::EnterCodeBlock(data)
+            | Event::EnterTable(data) => {
+                let content = data.attributes.as_ref()?;
+                unsafe { std::str::from_utf8_unchecked(&input[content.clone()]) }
+            }
             Event::NewLine(_)
             | Event::ThematicBreak(_)
-            | Event::EnterParagraph(_)
-            | Event::EnterHeading1(_)
-            | Event::EnterHeading2(_)
-            | Event::EnterHeading3(_)
-            | Event::EnterHeading4(_)
-            | Event::EnterHeading5(_)
-            | Event::EnterHeading6(_)
-            | Event::EnterBlockQuote(_)
-            | Event::EnterOrderedList(_)
-            | Event::EnterUnorderedList(_)
-            | Event::EnterListItem(_)
-            | Event::EnterDescriptionList(_)
-            | Event::EnterDescriptionTerm(_)
-            | Event::EnterDescriptionDetails(_)
-            | Event::EnterCodeBlock(_)
-            | Event::EnterTable(_)
-            | Event::IndicateCodeBlockCode
-            | Event::IndicateTableCaption
-            | Event::IndicateTableRow
-            | Event::IndicateTableHeaderCell
-            | Event::IndicateTableDataCell
+            | Event::IndicateCodeBlockCode(_)
+            | Event::IndicateTableCaption(_)
+            | Event::IndicateTableRow(_)
+            | Event::IndicateTableHeaderCell(_)
+            | Event::IndicateTableDataCell(_)
+            | Event::IndicateBlockExtensionSlot(_)
+            | Event::IndicateInlineExtensionSlot(_)
+            | Event::IndicateExtensionParameter(ExtensionParameter { name: None, .. })
             | Event::ExitBlock(_)
-            | Event::EnterCodeSpan
-            | Event::EnterStrong
-            | Event::EnterStrikethrough
-            | Event::ExitInline => return None,
+            | Event::EnterDicexp(_)
+            | Event::EnterCodeSpan(_)
+            | Event::EnterStrong(_)
+            | Event::EnterStrikethrough(_)
+            | Event::ExitInline(_) => return None,
         };
 
         Some(result)
     }
 
+    /// 返回结构性事件（进入、指示与退出事件，以及分割线）对应的标记在输入中的范
+    /// 围（见 [SourceSpan]）。对于其他事件，返回 `None`，它们的范围即其内容。
+    #[cfg(feature = "source-span")]
+    pub fn span(&self) -> Option<SourceSpan> {
+        let span = match self {
+            Event::ThematicBreak(ThematicBreak { span, .. })
+            | Event::EnterParagraph(BlockWithId { span, .. })
+            | Event::EnterHeading1(BlockWithId { span, .. })
+            | Event::EnterHeading2(BlockWithId { span, .. })
+            | Event::EnterHeading3(BlockWithId { span, .. })
+            | Event::EnterHeading4(BlockWithId { span, .. })
+            | Event::EnterHeading5(BlockWithId { span, .. })
+            | Event::EnterHeading6(BlockWithId { span, .. })
+            | Event::EnterBlockQuote(BlockWithId { span, .. })
+            | Event::EnterOrderedList(BlockWithId { span, .. })
+            | Event::EnterUnorderedList(BlockWithId { span, .. })
+            | Event::EnterListItem(BlockWithId { span, .. })
+            | Event::EnterDescriptionList(BlockWithId { span, .. })
+            | Event::EnterDescriptionTerm(BlockWithId { span, .. })
+            | Event::EnterDescriptionDetails(BlockWithId { span, .. })
+            | Event::EnterCodeBlock(BlockWithId { span, .. })
+            | Event::EnterTable(BlockWithId { span, .. })
+            | Event::EnterBlockExtension(BlockExtension { span, .. })
+            | Event::IndicateCodeBlockCode(Indication { span })
+            | Event::IndicateTableCaption(Indication { span })
+            | Event::IndicateTableRow(Indication { span })
+            | Event::IndicateTableHeaderCell(TableCell { span, .. })
+            | Event::IndicateTableDataCell(TableCell { span, .. })
+            | Event::IndicateBlockExtensionSlot(Indication { span })
+            | Event::IndicateExtensionParameter(ExtensionParameter { span, .. })
+            | Event::ExitBlock(ExitBlock { span, .. })
+            | Event::EnterDicexp(Dicexp { span, .. })
+            | Event::EnterCodeSpan(InlineElement { span, .. })
+            | Event::EnterStrong(InlineElement { span, .. })
+            | Event::EnterStrikethrough(InlineElement { span, .. })
+            | Event::EnterWikiLink(WikiLink { span, .. })
+            | Event::EnterInlineExtension(InlineExtension { span, .. })
+            | Event::IndicateInlineExtensionSlot(Indication { span })
+            | Event::ExitInline(ExitInline { span }) => span,
+            Event::__Unparsed(_)
+            | Event::Raw(_)
+            | Event::VerbatimEscaping(_)
+            | Event::NewLine(_)
+            | Event::Text(_)
+            | Event::RefLink(_) => return None,
+        };
+
+        Some(*span)
+    }
+
     #[cfg(test)]
     pub fn assertion_flags(&self) -> Option<std::collections::HashSet<&'static str>> {
         let mut flags = std::collections::HashSet::new();
@@ -0,0 +1,583 @@
+//! 增量解析。
+//!
+//! 编辑器中的每次按键通常只改动文档的一小部分。[ParseResult::reparse] 只重新解
+//! 析受编辑影响的顶层块级元素，其余顶层块级元素的事件（包括其中的 [BlockId]）会
+//! 被复用，只是其中的范围与行号会随编辑平移。
+//!
+//! 重新解析从某个顶层块级元素所在行的行首开始，直到在编辑的内容之后遇到一个在编
+//! 辑前后位于相同位置（平移后）的顶层块级元素为止。只有在行首与块级元素（或其附
+//! 加信息）的开头之间只有空格时，才会从该顶层块级元素所在的行首开始或停止重新解
+//! 析：此时解析器处于与从头开始解析时相同的状态。
+//!
+//! 重新解析的部分会分配新的块级元素 ID，因此结果中的 ID 与完整地重新解析所得的
+//! ID 未必相同，但 ID 之间的对应关系相同。
+//!
+//! [BlockId]: crate::types::BlockId
+
+#[cfg(test)]
+mod tests;
+
+use std::ops::Range;
+
+use crate::{
+    blend::BlockEventStreamInlineSegmentMapper,
+    block::{self, StartingPoint},
+    events::{
+        BlockExtension, BlockWithId, Dicexp, ExitBlock, ExitInline, ExtensionParameter, Indication,
+        InlineElement, InlineExtension, NewLine, TableCell, ThematicBreak, VerbatimEscaping,
+        WikiLink,
+    },
+    inline,
+    types::{LineNumber, SourceSpan},
+    utils::stack::VecStack,
+    Event,
+};
+
+/// 对输入的一次编辑：将（编辑前的）输入中位于 `range` 的内容替换为
+/// `replacement`。
+#[derive(Debug, Clone)]
+pub struct TextEdit<'a> {
+    pub range: Range<usize>,
+    pub replacement: &'a [u8],
+}
+
+impl TextEdit<'_> {
+    /// 返回对 `input` 应用本编辑后的结果。
+    pub fn apply(&self, input: &[u8]) -> Vec<u8> {
+        let mut output = Vec::with_capacity(input.len() + self.replacement.len());
+        output.extend_from_slice(&input[..self.range.start]);
+        output.extend_from_slice(self.replacement);
+        output.extend_from_slice(&input[self.range.end..]);
+        output
+    }
+}
+
+/// 能够增量更新的解析结果。
+#[derive(Debug, Clone)]
+pub struct ParseResult {
+    /// 属于 `Blend` 分组的事件。
+    events: Vec<Event>,
+    /// 按照在输入中出现的顺序排列。
+    blocks: Vec<TopLevelBlock>,
+    input_len: usize,
+    /// 已经分配出去的最大的块级元素 ID。
+    last_block_id: usize,
+}
+
+#[derive(Debug, Clone)]
+struct TopLevelBlock {
+    /// 块级元素（或其附加信息）的开头。
+    start: usize,
+    /// 若可以从本块级元素所在的行首开始解析，为该行的行首及其行号。
+    resumable_at: Option<(usize, usize)>,
+    /// 本块级元素的第一个事件在 [ParseResult::events] 中的索引。
+    first_event: usize,
+}
+
+/// 完整地解析 `input`，得到之后能够增量更新的解析结果。
+pub fn parse(input: &[u8]) -> crate::Result<ParseResult> {
+    let mut result = ParseResult {
+        events: vec![],
+        blocks: vec![],
+        input_len: input.len(),
+        last_block_id: 0,
+    };
+    let point = StartingPoint {
+        cursor: 0,
+        line: 1,
+        last_block_id: 0,
+    };
+    result.last_block_id = collect(input, point, &mut result.events, &mut result.blocks, |_| {
+        false
+    })?
+    .last_block_id;
+
+    Ok(result)
+}
+
+impl ParseResult {
+    /// 属于 `Blend` 分组的事件，与 [crate::parse] 产出的事件相对应。
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn into_events(self) -> Vec<Event> {
+        self.events
+    }
+
+    /// 在对输入进行了编辑 `edit` 之后，得到编辑后的输入 `new_input` 的解析结
+    /// 果。
+    pub fn reparse(self, new_input: &[u8], edit: &TextEdit) -> crate::Result<ParseResult> {
+        debug_assert_eq!(
+            self.input_len - edit.range.len() + edit.replacement.len(),
+            new_input.len()
+        );
+        let offset_delta = edit.replacement.len() as isize - edit.range.len() as isize;
+        let new_edit_end = edit.range.start + edit.replacement.len();
+
+        // 编辑之前的内容没有变化，因此可以在编辑后的输入中寻找行首。
+        let edit_line_start = find_line_start(new_input, edit.range.start);
+        // 以开头位于编辑之前的最后一个顶层块级元素的前一个为界。这是因为编辑可能
+        // 让开头位于编辑之前的最后一个顶层块级元素与其前一个顶层块级元素合并。
+        let blocks_before_edit = self
+            .blocks
+            .partition_point(|block| block.start <= edit.range.start);
+        let first_block_to_reparse = self.blocks[..blocks_before_edit.saturating_sub(1)]
+            .iter()
+            .rposition(|block| {
+                block
+                    .resumable_at
+                    .is_some_and(|(line_start, _)| line_start < edit_line_start)
+            });
+        let (point, events_to_keep) = match first_block_to_reparse {
+            Some(index) => {
+                let block = &self.blocks[index];
+                let (cursor, line) = block.resumable_at.unwrap();
+                (
+                    StartingPoint {
+                        cursor,
+                        line,
+                        last_block_id: self.last_block_id,
+                    },
+                    block.first_event,
+                )
+            }
+            None => (
+                StartingPoint {
+                    cursor: 0,
+                    line: 1,
+                    last_block_id: self.last_block_id,
+                },
+                0,
+            ),
+        };
+        let first_block_to_reparse = first_block_to_reparse.unwrap_or(0);
+
+        let mut events = self.events;
+        let mut old_events_after = events.split_off(events_to_keep);
+        let mut blocks = self.blocks[..first_block_to_reparse].to_vec();
+        let old_blocks = &self.blocks[first_block_to_reparse..];
+
+        let collected = collect(new_input, point, &mut events, &mut blocks, |block| {
+            let Some((line_start, _)) = block.resumable_at else {
+                return false;
+            };
+            line_start >= new_edit_end
+                && find_resumable_block(old_blocks, line_start, offset_delta).is_some()
+        })?;
+        let last_block_id = self.last_block_id.max(collected.last_block_id);
+
+        if let Some((line_start, line)) = collected.stopped_at {
+            let index = find_resumable_block(old_blocks, line_start, offset_delta).unwrap();
+            let (_, old_line) = old_blocks[index].resumable_at.unwrap();
+            let shift = Shift {
+                offset: offset_delta,
+                lines: line as isize - old_line as isize,
+            };
+
+            let first_old_event = old_blocks[index].first_event - events_to_keep;
+            let event_index_delta = events.len() as isize - old_blocks[index].first_event as isize;
+            events.extend(old_events_after.drain(first_old_event..).map(|mut ev| {
+                shift.event(&mut ev);
+                ev
+            }));
+            blocks.extend(old_blocks[index..].iter().map(|block| {
+                TopLevelBlock {
+                    start: shift.offset(block.start),
+                    resumable_at: block
+                        .resumable_at
+                        .map(|(line_start, line)| (shift.offset(line_start), shift.line(line))),
+                    first_event: block
+                        .first_event
+                        .checked_add_signed(event_index_delta)
+                        .unwrap(),
+                }
+            }));
+        }
+
+        Ok(ParseResult {
+            events,
+            blocks,
+            input_len: new_input.len(),
+            last_block_id,
+        })
+    }
+}
+
+/// 在 `old_blocks` 中寻找平移 `offset_delta` 后可以从 `line_start` 开始解析的顶
+/// 层块级元素，返回其索引。
+fn find_resumable_block(
+    old_blocks: &[TopLevelBlock],
+    line_start: usize,
+    offset_delta: isize,
+) -> Option<usize> {
+    let old_line_start = line_start.checked_add_signed(-offset_delta)?;
+    // 可以从行首开始解析的顶层块级元素与其行首之间只有空格，因此只可能是第一个
+    // 开头不在该行首之前的顶层块级元素。
+    let index = old_blocks.partition_point(|block| block.start < old_line_start);
+    let (line_start, _) = old_blocks.get(index)?.resumable_at?;
+    (line_start == old_line_start).then_some(index)
+}
+
+struct Collected {
+    last_block_id: usize,
+    /// 若因 `should_stop` 返回 `true` 而停止，为停止时那个顶层块级元素所在的行
+    /// 首及其行号。
+    stopped_at: Option<(usize, usize)>,
+}
+
+/// 从 `point` 开始解析 `input`，将事件与顶层块级元素分别追加至 `events` 与
+/// `blocks`。在遇到新的顶层块级元素时，若 `should_stop` 对其返回 `true`，则停止
+/// 解析（该顶层块级元素不会被追加）。
+fn collect(
+    input: &[u8],
+    point: StartingPoint,
+    events: &mut Vec<Event>,
+    blocks: &mut Vec<TopLevelBlock>,
+    mut should_stop: impl FnMut(&TopLevelBlock) -> bool,
+) -> crate::Result<Collected> {
+    let block_parser = block::Parser::<VecStack<_>>::new_at(input, point);
+    let parser = BlockEventStreamInlineSegmentMapper::<_, VecStack<inline::StackEntry>>::new(
+        input,
+        block_parser,
+    );
+
+    let mut line_counter = LineCounter {
+        offset: point.cursor,
+        line: point.line,
+    };
+    #[cfg_attr(not(feature = "block-id"), allow(unused_mut))]
+    let mut last_block_id = point.last_block_id;
+    let mut depth = 0;
+    for ev in parser {
+        let ev = ev?;
+
+        if let Some(block_start) = block_start(&ev) {
+            #[cfg(feature = "block-id")]
+            {
+                last_block_id = last_block_id.max(block_id(&ev));
+            }
+
+            if depth == 0 {
+                let line_start = find_line_start(input, block_start);
+                let is_resumable = input[line_start..block_start].iter().all(|c| *c == b' ');
+                let block = TopLevelBlock {
+                    start: block_start,
+                    resumable_at: is_resumable
+                        .then(|| (line_start, line_counter.advance_to(input, line_start))),
+                    first_event: events.len(),
+                };
+                if should_stop(&block) {
+                    return Ok(Collected {
+                        last_block_id,
+                        stopped_at: block.resumable_at,
+                    });
+                }
+                blocks.push(block);
+            }
+            if !matches!(ev, Event::ThematicBreak(_)) {
+                depth += 1;
+            }
+        } else if matches!(ev, Event::ExitBlock(_)) {
+            depth -= 1;
+        }
+
+        events.push(ev);
+    }
+
+    Ok(Collected {
+        last_block_id,
+        stopped_at: None,
+    })
+}
+
+/// 若 `ev` 是进入块级元素的事件或分割线，返回块级元素（或其附加信息）的开头。
+fn block_start(ev: &Event) -> Option<usize> {
+    let (attributes, span) = match ev {
+        Event::ThematicBreak(ThematicBreak {
+            attributes, span, ..
+        })
+        | Event::EnterParagraph(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterHeading1(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterHeading2(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterHeading3(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterHeading4(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterHeading5(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterHeading6(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterBlockQuote(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterOrderedList(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterUnorderedList(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterListItem(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterDescriptionList(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterDescriptionTerm(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterDescriptionDetails(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterCodeBlock(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterTable(BlockWithId {
+            attributes, span, ..
+        })
+        | Event::EnterBlockExtension(BlockExtension {
+            attributes, span, ..
+        }) => (attributes, span),
+        _ => return None,
+    };
+
+    let start = span.range().start;
+    Some(attributes.as_ref().map_or(start, |a| a.start.min(start)))
+}
+
+#[cfg(feature = "block-id")]
+fn block_id(ev: &Event) -> usize {
+    match ev {
+        Event::ThematicBreak(ThematicBreak { id, .. })
+        | Event::EnterBlockExtension(BlockExtension { id, .. }) => id.value(),
+        Event::EnterParagraph(BlockWithId { id, .. })
+        | Event::EnterHeading1(BlockWithId { id, .. })
+        | Event::EnterHeading2(BlockWithId { id, .. })
+        | Event::EnterHeading3(BlockWithId { id, .. })
+        | Event::EnterHeading4(BlockWithId { id, .. })
+        | Event::EnterHeading5(BlockWithId { id, .. })
+        | Event::EnterHeading6(BlockWithId { id, .. })
+        | Event::EnterBlockQuote(BlockWithId { id, .. })
+        | Event::EnterOrderedList(BlockWithId { id, .. })
+        | Event::EnterUnorderedList(BlockWithId { id, .. })
+        | Event::EnterListItem(BlockWithId { id, .. })
+        | Event::EnterDescriptionList(BlockWithId { id, .. })
+        | Event::EnterDescriptionTerm(BlockWithId { id, .. })
+        | Event::EnterDescriptionDetails(BlockWithId { id, .. })
+        | Event::EnterCodeBlock(BlockWithId { id, .. })
+        | Event::EnterTable(BlockWithId { id, .. }) => id.value(),
+        _ => unreachable!(),
+    }
+}
+
+/// 返回 `offset` 所在行的行首。与解析器一致，`\n`、`\r\n` 与单独的 `\r` 都视为
+/// 换行。
+fn find_line_start(input: &[u8], offset: usize) -> usize {
+    input[..offset]
+        .iter()
+        .rposition(|c| matches!(c, b'\r' | b'\n'))
+        .map_or(0, |i| i + 1)
+}
+
+/// 随着位置前进计算行号。
+struct LineCounter {
+    offset: usize,
+    line: usize,
+}
+
+impl LineCounter {
+    /// 返回 `offset` 所在的行号。`offset` 不能在之前传入的位置之前。
+    fn advance_to(&mut self, input: &[u8], offset: usize) -> usize {
+        for i in self.offset..offset {
+            match input[i] {
+                b'\n' => self.line += 1,
+                b'\r' if input.get(i + 1) != Some(&b'\n') => self.line += 1,
+                _ => {}
+            }
+        }
+        self.offset = offset;
+        self.line
+    }
+}
+
+/// 复用的事件中的范围与行号需要平移的量。
+struct Shift {
+    offset: isize,
+    lines: isize,
+}
+
+impl Shift {
+    fn offset(&self, offset: usize) -> usize {
+        offset.checked_add_signed(self.offset).unwrap()
+    }
+
+    fn line(&self, line: usize) -> usize {
+        line.checked_add_signed(self.lines).unwrap()
+    }
+
+    fn range(&self, range: &mut Range<usize>) {
+        *range = self.offset(range.start)..self.offset(range.end);
+    }
+
+    fn optional_range(&self, range: &mut Option<Range<usize>>) {
+        if let Some(range) = range {
+            self.range(range);
+        }
+    }
+
+    fn span(&self, span: &mut SourceSpan) {
+        let mut range = span.range();
+        self.range(&mut range);
+        *span = SourceSpan::new(range);
+    }
+
+    fn line_number(&self, line: &mut LineNumber) {
+        #[cfg(feature = "line-number")]
+        {
+            *line = LineNumber::new(self.line(line.value()));
+        }
+        #[cfg(not(feature = "line-number"))]
+        {
+            let _ = line;
+        }
+    }
+
+    /// 平移属于 `Blend` 分组的事件 `ev`。
+    fn event(&self, ev: &mut Event) {
+        match ev {
+            Event::__Unparsed(range)
+            | Event::Raw(range)
+            | Event::Text(range)
+            | Event::RefLink(range) => self.range(range),
+            Event::VerbatimEscaping(VerbatimEscaping {
+                content,
+                line_after,
+                ..
+            }) => {
+                self.range(content);
+                self.line_number(line_after);
+            }
+            Event::NewLine(NewLine { line_after }) => self.line_number(line_after),
+            Event::ThematicBreak(ThematicBreak {
+                line,
+                attributes,
+                span,
+                ..
+            }) => {
+                self.line_number(line);
+                self.optional_range(attributes);
+                self.span(span);
+            }
+            Event::EnterParagraph(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterHeading1(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterHeading2(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterHeading3(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterHeading4(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterHeading5(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterHeading6(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterBlockQuote(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterOrderedList(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterUnorderedList(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterListItem(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterDescriptionList(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterDescriptionTerm(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterDescriptionDetails(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterCodeBlock(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::EnterTable(BlockWithId {
+                attributes, span, ..
+            })
+            | Event::IndicateTableHeaderCell(TableCell { attributes, span })
+            | Event::IndicateTableDataCell(TableCell { attributes, span })
+            | Event::EnterCodeSpan(InlineElement { attributes, span })
+            | Event::EnterStrong(InlineElement { attributes, span })
+            | Event::EnterStrikethrough(InlineElement { attributes, span }) => {
+                self.optional_range(attributes);
+                self.span(span);
+            }
+            Event::EnterBlockExtension(BlockExtension {
+                name,
+                attributes,
+                span,
+                ..
+            })
+            | Event::EnterWikiLink(WikiLink {
+                address: name,
+                attributes,
+                span,
+            })
+            | Event::EnterInlineExtension(InlineExtension {
+                name,
+                attributes,
+                span,
+            }) => {
+                self.range(name);
+                self.optional_range(attributes);
+                self.span(span);
+            }
+            Event::IndicateExtensionParameter(ExtensionParameter { name, span })
+            | Event::EnterDicexp(Dicexp {
+                assigning_to: name,
+                span,
+            }) => {
+                self.optional_range(name);
+                self.span(span);
+            }
+            Event::IndicateCodeBlockCode(Indication { span })
+            | Event::IndicateTableCaption(Indication { span })
+            | Event::IndicateTableRow(Indication { span })
+            | Event::IndicateBlockExtensionSlot(Indication { span })
+            | Event::IndicateInlineExtensionSlot(Indication { span })
+            | Event::ExitInline(ExitInline { span }) => self.span(span),
+            Event::ExitBlock(ExitBlock {
+                start_line,
+                end_line,
+                span,
+                ..
+            }) => {
+                self.line_number(start_line);
+                self.line_number(end_line);
+                self.span(span);
+            }
+        }
+    }
+}
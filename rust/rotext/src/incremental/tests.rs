@@ -0,0 +1,207 @@
+use rand_xoshiro::{
+    rand_core::{RngCore, SeedableRng},
+    Xoshiro256PlusPlus,
+};
+
+use super::{parse, TextEdit};
+use crate::Event;
+
+/// 用于组成文档的行。
+const LINES: &[&str] = &[
+    "",
+    "",
+    "a",
+    "b c",
+    "  d",
+    "中文😀",
+    "= h =",
+    "== h",
+    "---",
+    "> q",
+    "> > q",
+    "* i",
+    "** i",
+    "# o",
+    "; t",
+    ": d",
+    "```",
+    "```js",
+    "{|",
+    "|+ c",
+    "|-",
+    "!! h || d",
+    "|}",
+    "{{#Note",
+    "??x=1",
+    "||",
+    "}}",
+    "<`v`>",
+    "<` v",
+    "`>",
+    "<% c",
+    "%>",
+    "[!x=1]",
+    "['s'] [~t~]",
+    "[[w|l]]",
+    "[{#E?p=1|s}]",
+    "[=1] [`c`]",
+    "{! .x}",
+    "{! .x} = h =",
+    "[=d6",
+    "+1]",
+];
+
+/// 用于替换的内容。
+const REPLACEMENTS: &[&str] = &[
+    "", "x", " ", "\n", "\n\n", "\r\n", "\r", "= ", "> ", "* ", "---", "```", "{|", "|}", "||",
+    "{{#", "}}", "<`", "`>", "<%", "%>", "['", "']", "[[", "]]", "[!x]", "中",
+];
+
+fn random_index(rng: &mut impl RngCore, len: usize) -> usize {
+    rng.next_u32() as usize % len
+}
+
+fn random_document(rng: &mut impl RngCore) -> String {
+    let line_count = 1 + random_index(rng, 24);
+    let mut document = String::new();
+    for i in 0..line_count {
+        if i > 0 {
+            document.push_str(if random_index(rng, 8) == 0 {
+                "\r\n"
+            } else {
+                "\n"
+            });
+        }
+        document.push_str(LINES[random_index(rng, LINES.len())]);
+    }
+    document
+}
+
+/// 返回位于字符边界上的随机位置。
+fn random_boundary(rng: &mut impl RngCore, input: &str) -> usize {
+    let mut offset = random_index(rng, input.len() + 1);
+    while !input.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+fn random_edit<'a>(rng: &mut impl RngCore, input: &str) -> TextEdit<'a> {
+    let start = random_boundary(rng, input);
+    let mut end = (start + random_index(rng, 8)).min(input.len());
+    while !input.is_char_boundary(end) {
+        end += 1;
+    }
+    TextEdit {
+        range: start..end,
+        replacement: REPLACEMENTS[random_index(rng, REPLACEMENTS.len())].as_bytes(),
+    }
+}
+
+/// 将块级元素的 ID 按照其首次出现的顺序重新编号。
+fn normalize_block_ids(events: &mut [Event]) {
+    #[cfg(feature = "block-id")]
+    {
+        use crate::{
+            events::{BlockExtension, BlockWithId, ExitBlock, ThematicBreak},
+            types::BlockId,
+        };
+
+        let mut ids: Vec<BlockId> = vec![];
+        for ev in events {
+            let id = match ev {
+                Event::ThematicBreak(ThematicBreak { id, .. })
+                | Event::EnterBlockExtension(BlockExtension { id, .. })
+                | Event::ExitBlock(ExitBlock { id, .. })
+                | Event::EnterParagraph(BlockWithId { id, .. })
+                | Event::EnterHeading1(BlockWithId { id, .. })
+                | Event::EnterHeading2(BlockWithId { id, .. })
+                | Event::EnterHeading3(BlockWithId { id, .. })
+                | Event::EnterHeading4(BlockWithId { id, .. })
+                | Event::EnterHeading5(BlockWithId { id, .. })
+                | Event::EnterHeading6(BlockWithId { id, .. })
+                | Event::EnterBlockQuote(BlockWithId { id, .. })
+                | Event::EnterOrderedList(BlockWithId { id, .. })
+                | Event::EnterUnorderedList(BlockWithId { id, .. })
+                | Event::EnterListItem(BlockWithId { id, .. })
+                | Event::EnterDescriptionList(BlockWithId { id, .. })
+                | Event::EnterDescriptionTerm(BlockWithId { id, .. })
+                | Event::EnterDescriptionDetails(BlockWithId { id, .. })
+                | Event::EnterCodeBlock(BlockWithId { id, .. })
+                | Event::EnterTable(BlockWithId { id, .. }) => id,
+                _ => continue,
+            };
+            let index = match ids.iter().position(|x| x == id) {
+                Some(index) => index,
+                None => {
+                    ids.push(*id);
+                    ids.len() - 1
+                }
+            };
+            *id = BlockId::new(index + 1);
+        }
+    }
+    #[cfg(not(feature = "block-id"))]
+    {
+        let _ = events;
+    }
+}
+
+#[test]
+fn it_is_equivalent_to_full_reparsing_on_random_edits() {
+    let mut rng = Xoshiro256PlusPlus::seed_from_u64(42);
+
+    for _ in 0..300 {
+        let mut input = random_document(&mut rng);
+        let mut result = parse(input.as_bytes()).unwrap();
+
+        for _ in 0..20 {
+            let edit = random_edit(&mut rng, &input);
+            let new_input = String::from_utf8(edit.apply(input.as_bytes())).unwrap();
+            let description = format!(
+                "input: {:?}, edit: {:?} => {:?}",
+                input,
+                edit.range,
+                std::str::from_utf8(edit.replacement).unwrap()
+            );
+
+            result = result.reparse(new_input.as_bytes(), &edit).unwrap();
+            input = new_input;
+
+            let mut actual = result.events().to_vec();
+            normalize_block_ids(&mut actual);
+            let mut expected = crate::parse(input.as_bytes())
+                .collect::<crate::Result<Vec<_>>>()
+                .unwrap();
+            normalize_block_ids(&mut expected);
+            assert_eq!(expected, actual, "{}", description);
+        }
+    }
+}
+
+#[cfg(feature = "block-id")]
+#[test]
+fn it_reuses_block_ids_of_untouched_blocks() {
+    let input = "a\n\nb\n\nc\n\nd";
+    let ids = |events: &[Event]| -> Vec<usize> {
+        events
+            .iter()
+            .filter_map(|ev| match ev {
+                Event::EnterParagraph(data) => Some(data.id.value()),
+                _ => None,
+            })
+            .collect()
+    };
+
+    let result = parse(input.as_bytes()).unwrap();
+    assert_eq!(vec![1, 2, 3, 4], ids(result.events()));
+
+    let edit = TextEdit {
+        range: 6..7,
+        replacement: b"C",
+    };
+    let new_input = edit.apply(input.as_bytes());
+    let result = result.reparse(&new_input, &edit).unwrap();
+    // 只有被编辑的 `c` 及其之前的 `b` 被重新解析。
+    assert_eq!(vec![1, 5, 6, 4], ids(result.events()));
+}
@@ -10,18 +10,20 @@ pub use stack_wrapper::StackEntry;
 use std::ops::Range;
 
 use crate::{
-    events::{ev, NewLine},
+    attributes,
+    diagnostics::{Diagnostic, DiagnosticCode},
+    events::{ev, ExitInline, Indication, InlineElement, InlineExtension, NewLine, WikiLink},
     utils::internal::peekable::Peekable,
     Event,
 };
 use parser_inner::{ParserInner, ToSkipInputEvents};
-use stack_wrapper::{TopLeaf, TopLeafCodeSpan};
+use stack_wrapper::{TopLeaf, TopLeafCodeSpan, TopLeafDicexp, TopLeafInlineExtensionParameter};
 use types::{Cursor, YieldContext};
 
 use crate::{
-    common::{is_valid_character_in_name, m},
-    events::VerbatimEscaping,
-    types::{Tym, TYM_UNIT},
+    common::{is_valid_character_in_name, is_valid_variable_identifier, m},
+    events::{ExtensionParameter, VerbatimEscaping},
+    types::{SourceSpan, Tym, TYM_UNIT},
     utils::{
         internal::string::{
             count_continuous_character, count_continuous_character_with_maximum, is_whitespace,
@@ -53,23 +55,49 @@ impl<'a, TInlineStack: Stack<StackEntry>> Parser<'a, TInlineStack> {
         }
     }
 
+    /// 开始收集诊断信息（见 [crate::diagnostics]）。
+    pub fn enable_diagnostics(&mut self) {
+        self.inner.diagnostics.enable();
+    }
+
+    /// 取走目前为止收集到的诊断信息。
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        self.inner.diagnostics.take()
+    }
+
     /// `event_stream` 的迭代对象是属于 `InlineInput` 分组的事件。返回的事件属于
     /// `Inline` 分组。
     pub fn next(
         &mut self,
         event_stream: &mut Peekable<2, impl Iterator<Item = Event>>,
+    ) -> Option<crate::Result<Event>> {
+        let result = self.next_without_recording(event_stream);
+        #[cfg(feature = "source-span")]
+        if let Some(Ok(ev)) = &result {
+            self.inner.record_yielded(self.full_input, ev);
+        }
+        result
+    }
+
+    fn next_without_recording(
+        &mut self,
+        event_stream: &mut Peekable<2, impl Iterator<Item = Event>>,
     ) -> Option<crate::Result<Event>> {
         loop {
             if let Some(ev) = self.inner.pop_to_be_yielded() {
                 break Some(Ok(ev));
             }
 
-            if let Some(end_entry) = &self.inner.to_exit_until_popped_entry_from_stack {
+            if let Some((end_entry, closing)) = &self.inner.to_exit_until_popped_entry_from_stack {
                 let entry = self.inner.stack.pop_entry().unwrap();
-                if &entry == end_entry {
+                let span = if &entry == end_entry {
+                    let closing = *closing;
                     self.inner.to_exit_until_popped_entry_from_stack = None;
-                }
-                break Some(Ok(ev!(Inline, ExitInline)));
+                    closing
+                } else {
+                    self.inner.span_after_last_yielded()
+                };
+                break Some(Ok(ev!(Inline, ExitInline(ExitInline { span }))));
             }
 
             let result = match &mut self.state {
@@ -123,7 +151,17 @@ impl<'a, TInlineStack: Stack<StackEntry>> Parser<'a, TInlineStack> {
                             continue;
                         }
                         Event::VerbatimEscaping(verbatim_escaping) => {
-                            ev!(Inline, VerbatimEscaping(verbatim_escaping))
+                            let ev = ev!(Inline, VerbatimEscaping(verbatim_escaping));
+                            match leaf::dicexp::exit_if_top_leaf_before_verbatim_escaping(
+                                &mut self.inner,
+                            ) {
+                                Some(exit_ev) => {
+                                    let tym = self.inner.r#yield(ev);
+                                    self.inner.enforce_to_yield_mark(tym.into());
+                                    exit_ev
+                                }
+                                None => ev,
+                            }
                         }
                         Event::NewLine(new_line) => ev!(Inline, NewLine(new_line)),
                     };
@@ -155,10 +193,20 @@ impl<'a, TInlineStack: Stack<StackEntry>> Parser<'a, TInlineStack> {
     ) -> crate::Result<Tym<4>> {
         match inner.stack.pop_top_leaf() {
             None => Self::parse_normal(input, cursor, inner, event_stream),
+            Some(TopLeaf::Dicexp(top_leaf)) => {
+                leaf::dicexp::parse_content_and_process(input, cursor, inner, top_leaf)
+                    .map(|tym| tym.into())
+            }
             Some(TopLeaf::CodeSpan(top_leaf)) => {
                 leaf::code_span::parse_content_and_process(input, cursor, inner, top_leaf)
                     .map(|tym| tym.into())
             }
+            Some(TopLeaf::InlineExtensionParameter(top_leaf)) => {
+                leaf::inline_extension::parse_parameter_value_and_process(
+                    input, cursor, inner, top_leaf,
+                )
+                .map(|tym| tym.into())
+            }
         }
     }
 
@@ -210,62 +258,102 @@ impl<'a, TInlineStack: Stack<StackEntry>> Parser<'a, TInlineStack> {
                         None => continue,
                     }
                 }
-                m!('[') => match input.get(cursor.value() + 1) {
-                    None => {
-                        cursor.move_forward(1);
-                        break (cursor.value(), None);
-                    }
-                    Some(m!('=')) => {
-                        break leaf::dicexp::process(input, cursor);
-                    }
-                    Some(m!('`')) => {
-                        break leaf::code_span::process(input, cursor, inner);
-                    }
-                    Some(m!('\'')) => {
-                        let text_end = cursor.value();
-
-                        cursor.move_forward("['".len());
-                        inner.stack.push_entry(StackEntry::Strong)?;
-                        let to_yield_after_text = ev!(Inline, EnterStrong);
+                m!('[') => {
+                    let attributes = special::parse_potential_attributes(input, cursor.value());
+                    let element_start = attributes.as_ref().map_or(cursor.value(), |a| a.end);
 
-                        break (text_end, Some(to_yield_after_text));
-                    }
-                    Some(m!('~')) => {
-                        let text_end = cursor.value();
+                    match input.get(element_start + 1) {
+                        None => {
+                            cursor.move_forward(1);
+                            break (cursor.value(), None);
+                        }
+                        Some(m!('=')) => {
+                            break leaf::dicexp::process(input, cursor, inner);
+                        }
+                        Some(m!('@')) => {
+                            match leaf::dicexp::process_potential_assignment(input, cursor, inner) {
+                                Some(result) => {
+                                    break result;
+                                }
+                                None => continue,
+                            }
+                        }
+                        Some(m!('`')) => {
+                            break leaf::code_span::process(input, cursor, inner, attributes);
+                        }
+                        Some(m!('\'')) => {
+                            let text_end = cursor.value();
 
-                        cursor.move_forward("[~".len());
-                        inner.stack.push_entry(StackEntry::Strikethrough)?;
-                        let to_yield_after_text = ev!(Inline, EnterStrikethrough);
+                            cursor.set_value(element_start + "['".len());
+                            inner.stack.push_entry(StackEntry::Strong)?;
+                            let span = SourceSpan::new_universal(element_start..cursor.value());
+                            let to_yield_after_text =
+                                ev!(Inline, EnterStrong(InlineElement { attributes, span }));
 
-                        break (text_end, Some(to_yield_after_text));
-                    }
-                    Some(m!('[')) => {
-                        match leaf::wiki_link::process_and_yield_potential(
-                            input,
-                            text_start,
-                            cursor,
-                            inner,
-                            event_stream,
-                        )? {
-                            Some(tym) => {
-                                return Ok(tym);
+                            break (text_end, Some(to_yield_after_text));
+                        }
+                        Some(m!('~')) => {
+                            let text_end = cursor.value();
+
+                            cursor.set_value(element_start + "[~".len());
+                            inner.stack.push_entry(StackEntry::Strikethrough)?;
+                            let span = SourceSpan::new_universal(element_start..cursor.value());
+                            let to_yield_after_text = ev!(
+                                Inline,
+                                EnterStrikethrough(InlineElement { attributes, span })
+                            );
+
+                            break (text_end, Some(to_yield_after_text));
+                        }
+                        Some(m!('[')) => {
+                            match leaf::wiki_link::process_and_yield_potential(
+                                input,
+                                text_start,
+                                cursor,
+                                inner,
+                                event_stream,
+                                attributes,
+                            )? {
+                                Some(tym) => {
+                                    return Ok(tym);
+                                }
+                                None => continue,
                             }
-                            None => continue,
+                        }
+                        Some(m!('{')) => {
+                            let result = leaf::inline_extension::process_and_yield_potential(
+                                input, text_start, cursor, inner, attributes,
+                            )?;
+                            match result {
+                                Some(tym) => {
+                                    return Ok(tym.into());
+                                }
+                                None => continue,
+                            }
+                        }
+                        Some(_) => {
+                            cursor.move_forward(1);
+                            continue;
                         }
                     }
-                    Some(_) => {
-                        cursor.move_forward(1);
-                        continue;
-                    }
-                },
+                }
+                m!('|') if end_condition.on_inline_extension_separator => {
+                    break leaf::inline_extension::process_separator(input, text_start, cursor);
+                }
                 &char => {
                     if let Some(entry_to_be_popped_until) =
                         end_condition.test(char, input.get(cursor.value() + 1).copied())
                     {
-                        let text_end = cursor.value();
+                        let text_end = if entry_to_be_popped_until == StackEntry::InlineExtension {
+                            // 行内扩展的闭合部分包含其之前的空白。
+                            leaf::inline_extension::trim_end(input, text_start, cursor.value())
+                        } else {
+                            cursor.value()
+                        };
+                        let closing = SourceSpan::new_universal(cursor.value()..cursor.value() + 2);
                         cursor.move_forward(2);
                         inner.to_exit_until_popped_entry_from_stack =
-                            Some(entry_to_be_popped_until);
+                            Some((entry_to_be_popped_until, closing));
                         break (text_end, None);
                     }
 
@@ -290,15 +378,28 @@ impl<'a, TInlineStack: Stack<StackEntry>> Parser<'a, TInlineStack> {
     ) -> (Tym<1>, Option<State<'a>>) {
         if let Some(top_leaf) = inner.stack.pop_top_leaf() {
             let tym = match top_leaf {
+                stack_wrapper::TopLeaf::Dicexp(top_leaf) => {
+                    let span = inner.span_after_last_yielded();
+                    inner.r#yield(top_leaf.make_exit_event(span))
+                }
                 stack_wrapper::TopLeaf::CodeSpan(top_leaf) => {
-                    inner.r#yield(top_leaf.make_exit_event())
+                    inner
+                        .diagnostics
+                        .report(DiagnosticCode::UnclosedCodeSpan, top_leaf.opening.clone());
+                    let span = inner.span_after_last_yielded();
+                    inner.r#yield(top_leaf.make_exit_event(span))
                 }
+                stack_wrapper::TopLeaf::InlineExtensionParameter(_) => TYM_UNIT.into(),
             };
             (tym, None)
         } else if let Some(entry) = inner.stack.pop_entry() {
             let tym = match entry {
-                StackEntry::Strong | StackEntry::Strikethrough | StackEntry::WikiLink => {
-                    inner.r#yield(ev!(Inline, ExitInline))
+                StackEntry::Strong
+                | StackEntry::Strikethrough
+                | StackEntry::WikiLink
+                | StackEntry::InlineExtension => {
+                    let span = inner.span_after_last_yielded();
+                    inner.r#yield(ev!(Inline, ExitInline(ExitInline { span })))
                 }
             };
 
@@ -353,6 +454,31 @@ mod special {
         (text_end, None)
     }
 
+    /// 尝试解析位于 `start` 的行内附加信息。附加信息可以有多组，各组之间不能有
+    /// 其他内容。
+    ///
+    /// 只有在紧随其后的是能附着附加信息的行内元素的开启部分时，才返回附加信息的
+    /// 范围。（对于Wiki链接与行内扩展，这里只检查其开启部分的开头，是否真的构成
+    /// 相应的元素由调用者进一步判断。）
+    pub fn parse_potential_attributes(input: &[u8], start: usize) -> Option<Range<usize>> {
+        if input.get(start + 1) != Some(&m!('!')) {
+            return None;
+        }
+
+        let mut end = start;
+        while let Some(group_end) = attributes::parse_group(input, end, |_| {}) {
+            end = group_end;
+        }
+        if end == start {
+            return None;
+        }
+
+        match input.get(end..end + 2)? {
+            b"['" | b"[~" | b"[`" | b"[[" | b"[{" => Some(start..end),
+            _ => None,
+        }
+    }
+
     /// 返回的事件属于 `Inline` 分组。
     pub fn process_potential_numeric_character_reference(
         input: &[u8],
@@ -529,52 +655,147 @@ mod leaf {
 
         /// 返回的事件属于 `Inline` 分组。
         ///
-        /// TODO: 支持多行。
-        ///
-        /// NOTE: 为什么 `dicexp` 不能像 `code_span` 那样相对简单地支持多行：
-        /// - `code_span` 基于原生的 HTML 元素 `<code/>`，内容可以直接作为其子节
-        ///   点，因此设计成了用两个事件（`EnterCodeSpan`、`Exit`）来包围内容。
-        ///   
-        /// - `dicexp` 基于自定义元素，需要以属性（attribute）传递内容，因此设计
-        ///   为了只用一个事件（`Dicexp`）。
-        ///
-        /// 后者的事件只能传递一整块范围的内容，而多行内容的范围并不连续，因此无
-        /// 法传递。未来可能会让 `dicexp` 的事件也以 Enter/Exit 的形式表示，在那
-        /// 之前 dicexp 无法支持多行。
-        pub fn process(input: &[u8], cursor: &mut Cursor) -> (usize, Option<Event>) {
+        /// 与 `code_span` 一样，骰子表达式的代码以 `Text` 事件（跨行时还有
+        /// `NewLine` 事件）表示，并由 `EnterDicexp` 与 `ExitInline` 包围。
+        pub fn process<TInlineStack: Stack<StackEntry>>(
+            _input: &[u8],
+            cursor: &mut Cursor,
+            inner: &mut ParserInner<TInlineStack>,
+        ) -> (usize, Option<Event>) {
             let text_end = cursor.value();
 
             cursor.move_forward("[=".len());
-            let content = leaf::dicexp::advance_until_ends(input, cursor);
-            let to_yield_after_text = ev!(Inline, Dicexp(content));
+            let top_leaf = TopLeafDicexp::new();
+            let span = SourceSpan::new_universal(text_end..cursor.value());
+            let ev = top_leaf.make_enter_event(None, span);
+            inner.stack.push_top_leaf(top_leaf.into());
 
-            (text_end, Some(to_yield_after_text))
+            (text_end, Some(ev))
         }
 
-        /// 推进游标，直到到了数量匹配的 “]” 之前，或者 `input` 到头时。如果是前者，结束时
-        /// `ctx.cursor()` 对应于 “]” 的索引，也即还没消耗掉那个 “]”。
-        pub fn advance_until_ends(input: &[u8], cursor: &mut Cursor) -> Range<usize> {
-            let start = cursor.value();
+        /// 尝试解析位于 cursor 的赋值骰子表达式（`[@<name>=<code>]`）。
+        ///
+        /// 返回的事件属于 `Inline` 分组。若返回 `None`，cursor 位于 `[` 之后。
+        pub fn process_potential_assignment<TInlineStack: Stack<StackEntry>>(
+            input: &[u8],
+            cursor: &mut Cursor,
+            inner: &mut ParserInner<TInlineStack>,
+        ) -> Option<(usize, Option<Event>)> {
+            let text_end = cursor.value();
+            let name_start = text_end + "[@".len();
+
+            // 标识符中的 ASCII 字符只可能是字母、数字以及 `_`、`.`、`@`，遇到其他
+            // ASCII 字符便可以停止寻找 `=`。
+            let name_end = input[name_start..]
+                .iter()
+                .position(|char| {
+                    char.is_ascii()
+                        && !char.is_ascii_alphanumeric()
+                        && !matches!(char, m!('_') | m!('.') | m!('@'))
+                })
+                .map(|len| name_start + len)
+                .filter(|end| input[*end] == m!('='))
+                .filter(|end| is_valid_variable_identifier(&input[name_start..*end]));
+            let Some(name_end) = name_end else {
+                cursor.move_forward(1);
+                return None;
+            };
+
+            cursor.set_value(name_end + "=".len());
+            let top_leaf = TopLeafDicexp::new();
+            let span = SourceSpan::new_universal(text_end..cursor.value());
+            let ev = top_leaf.make_enter_event(Some(name_start..name_end), span);
+            inner.stack.push_top_leaf(top_leaf.into());
+
+            Some((text_end, Some(ev)))
+        }
+
+        pub fn parse_content_and_process<TInlineStack: Stack<StackEntry>>(
+            input: &[u8],
+            cursor: &mut Cursor,
+            inner: &mut ParserInner<TInlineStack>,
+            mut top_leaf: TopLeafDicexp,
+        ) -> crate::Result<Tym<2>> {
+            let (code, has_ended) = advance_until_ends(input, cursor, &mut top_leaf);
+
+            let tym_a = yield_text_if_not_empty(code.start, code.end, inner);
+            let tym_b = if has_ended {
+                let span = SourceSpan::new_universal((cursor.value() - "]".len())..cursor.value());
+                inner.r#yield(top_leaf.make_exit_event(span))
+            } else {
+                debug_assert_eq!(cursor.value(), input.len());
+                inner.stack.push_top_leaf(top_leaf.into());
+                TYM_UNIT.into()
+            };
+
+            Ok(tym_a.add(tym_b))
+        }
 
-            let mut depth = 1;
+        /// 逐字转义是骰子表达式的终止界限。若栈顶是骰子表达式，将其弹出并返回
+        /// 退出事件。
+        ///
+        /// 返回的事件属于 `Inline` 分组。
+        pub fn exit_if_top_leaf_before_verbatim_escaping<TInlineStack: Stack<StackEntry>>(
+            inner: &mut ParserInner<TInlineStack>,
+        ) -> Option<Event> {
+            let Some(TopLeaf::Dicexp(_)) = inner.stack.top_leaf() else {
+                return None;
+            };
+            let Some(TopLeaf::Dicexp(top_leaf)) = inner.stack.pop_top_leaf() else {
+                unreachable!()
+            };
+
+            let span = inner.span_after_last_yielded();
+            Some(top_leaf.make_exit_event(span))
+        }
+
+        /// 按照 “考虑双引号的中括号匹配语法” 推进游标，直到到了数量匹配的 “]” 之前，
+        /// 或者 `input` 到头时。如果是前者，结束时 `ctx.cursor()` 对应于 “]” 之后的
+        /// 索引，也即已经消耗掉了那个 “]”。返回的是这一段骰子表达式的代码的范围，
+        /// 以及是否遇到了那个 “]”。
+        ///
+        /// 考虑双引号的中括号匹配语法：
+        /// - `"` 是字符串的开启或闭合部分；
+        /// - 在字符串中，可以用 `\` 转义 `"` 与 `\`；
+        /// - 在字符串外，`[` 与 `]` 配对。
+        ///
+        /// 匹配的状态记录在 `top_leaf` 中，以便跨行时延续。
+        ///
+        /// NOTE: `input` 会在逐字转义之前到头，因此骰子表达式也自然终止于逐字转义
+        /// 之前。
+        pub fn advance_until_ends(
+            input: &[u8],
+            cursor: &mut Cursor,
+            top_leaf: &mut TopLeafDicexp,
+        ) -> (Range<usize>, bool) {
+            let start = cursor.value();
 
             while let Some(char) = input.get(cursor.value()) {
-                match char {
-                    m!('[') => depth += 1,
-                    m!(']') => {
-                        depth -= 1;
-                        if depth == 0 {
+                match (top_leaf.is_in_string, char) {
+                    (true, m!('\\')) => {
+                        // 被转义的字符不会是 “]”，直接跳过即可。
+                        if cursor.value() + 1 < input.len() {
+                            cursor.move_forward(1);
+                        }
+                    }
+                    (true, m!('"')) => top_leaf.is_in_string = false,
+                    (true, _) => {}
+                    (false, m!('"')) => top_leaf.is_in_string = true,
+                    (false, m!('[')) => top_leaf.depth += 1,
+                    (false, m!(']')) => {
+                        top_leaf.depth -= 1;
+                        if top_leaf.depth == 0 {
                             let content = start..cursor.value();
                             cursor.move_forward(1);
-                            return content;
+                            return (content, true);
                         }
                     }
-                    _ => {}
+                    (false, _) => {}
                 }
                 cursor.move_forward(1)
             }
 
-            start..cursor.value()
+            (start..cursor.value(), false)
         }
     }
 
@@ -586,19 +807,24 @@ mod leaf {
             input: &[u8],
             cursor: &mut Cursor,
             inner: &mut ParserInner<TInlineStack>,
+            attributes: Option<Range<usize>>,
         ) -> (usize, Option<Event>) {
             let text_end = cursor.value();
+            if let Some(attributes) = &attributes {
+                cursor.set_value(attributes.end);
+            }
 
             let backticks =
                 "`".len() + count_continuous_character(input, m!('`'), cursor.value() + "[`".len());
+            let opening = cursor.value()..(cursor.value() + "[".len() + backticks);
             cursor.move_forward("[".len() + backticks);
 
             if input.get(cursor.value()) == Some(&b' ') {
                 cursor.move_forward(1);
             }
 
-            let top_leaf = TopLeafCodeSpan { backticks };
-            let ev = top_leaf.make_enter_event();
+            let top_leaf = TopLeafCodeSpan { backticks, opening };
+            let ev = top_leaf.make_enter_event(attributes);
             inner.stack.push_top_leaf(top_leaf.into());
 
             (text_end, Some(ev))
@@ -648,8 +874,10 @@ mod leaf {
 
                 let tym_a = yield_text_if_not_empty(start, content_end, inner);
 
-                cursor.move_forward(top_leaf.backticks + "]".len());
-                let tym_b = inner.r#yield(top_leaf.make_exit_event());
+                let closing = cursor.value()..(cursor.value() + top_leaf.backticks + "]".len());
+                cursor.set_value(closing.end);
+                let span = SourceSpan::new_universal(closing);
+                let tym_b = inner.r#yield(top_leaf.make_exit_event(span));
 
                 return Ok(tym_a.add(tym_b).into());
             }
@@ -671,55 +899,79 @@ mod leaf {
             cursor: &mut Cursor,
             inner: &mut ParserInner<TInlineStack>,
             event_stream: &mut Peekable<2, impl Iterator<Item = Event>>,
+            attributes: Option<Range<usize>>,
         ) -> crate::Result<Option<Tym<4>>> {
             let maybe_text_end = cursor.value();
+            if let Some(attributes) = &attributes {
+                cursor.set_value(attributes.end);
+            }
+            let opening_start = cursor.value();
             cursor.move_forward("[[".len());
 
-            let (address, address_ev, indicator) = if let (Some(slot_content), after_slot) =
-                parse_first_slot_for_non_verbatim(input, cursor)
-            {
-                let AfterSlot::Indicator {
-                    indicator,
-                    index_after_indicator,
-                } = after_slot
-                else {
-                    // 有内容（标题）但没找到指示标记时，不视为Wiki链接。
-                    // 如：`[[f<`oo`>]]`、`[[f\noo]]` 都不被视为Wiki链接。
-                    return Ok(None);
-                };
+            let (address, address_ev, indicator, index_after_indicator) =
+                if let (Some(slot_content), after_slot) =
+                    parse_first_slot_for_non_verbatim(input, cursor)
+                {
+                    let AfterSlot::Indicator {
+                        indicator,
+                        index_after_indicator,
+                    } = after_slot
+                    else {
+                        // 有内容（标题）但没找到指示标记时，不视为Wiki链接。
+                        // 如：`[[f<`oo`>]]`、`[[f\noo]]` 都不被视为Wiki链接。
+                        inner.diagnostics.report(
+                            DiagnosticCode::UnrecognizedWikiLink,
+                            opening_start..slot_content.end,
+                        );
+                        return Ok(None);
+                    };
 
-                cursor.set_value(index_after_indicator);
-                let address = slot_content.clone();
-                let address_ev = ev!(Inline, Text(slot_content));
+                    cursor.set_value(index_after_indicator);
+                    let address = slot_content.clone();
+                    let address_ev = ev!(Inline, Text(slot_content));
 
-                (address, address_ev, indicator)
-            } else {
-                let Some((
-                    slot,
-                    AfterSlot::Indicator {
+                    (address, address_ev, indicator, index_after_indicator)
+                } else {
+                    let Some((slot, after_slot)) =
+                        parse_first_slot_for_verbatim(input, event_stream)
+                    else {
+                        return Ok(None);
+                    };
+                    let AfterSlot::Indicator {
                         indicator,
                         index_after_indicator,
-                    },
-                )) = parse_first_slot_for_verbatim(input, event_stream)
-                else {
-                    return Ok(None);
-                };
+                    } = after_slot
+                    else {
+                        inner.diagnostics.report(
+                            DiagnosticCode::UnrecognizedWikiLink,
+                            opening_start..slot.content.end,
+                        );
+                        return Ok(None);
+                    };
 
-                // 跳过当前正在处理的事件（即以 “[[” 结尾的事件）以及作为第一个槽位的逐字转译的事件。
-                // 由于完成跳过后会设置游标，这里不用再用 `cursor.set_value` 来设置游标。
-                inner.to_skip_input = ToSkipInputEvents {
-                    count: 2,
-                    cursor_value: Some(index_after_indicator),
-                };
+                    // 跳过当前正在处理的事件（即以 “[[” 结尾的事件）以及作为第一个槽位的逐字转译的事件。
+                    // 由于完成跳过后会设置游标，这里不用再用 `cursor.set_value` 来设置游标。
+                    inner.to_skip_input = ToSkipInputEvents {
+                        count: 2,
+                        cursor_value: Some(index_after_indicator),
+                    };
 
-                let address = slot.content.clone();
-                let address_ev = ev!(Inline, VerbatimEscaping(slot));
+                    let address = slot.content.clone();
+                    let address_ev = ev!(Inline, VerbatimEscaping(slot));
 
-                (address, address_ev, indicator)
-            };
+                    (address, address_ev, indicator, index_after_indicator)
+                };
 
-            let tym_a = process_first_slot(text_start, maybe_text_end, inner, address)?;
-            let tym_b = process_indicator(inner, address_ev, indicator)?;
+            let opening = opening_start..(opening_start + "[[".len());
+            let tym_a = process_first_slot(
+                text_start,
+                maybe_text_end,
+                inner,
+                address,
+                attributes,
+                opening,
+            )?;
+            let tym_b = process_indicator(inner, address_ev, indicator, index_after_indicator)?;
             Ok(Some(tym_a.add(tym_b)))
         }
 
@@ -728,23 +980,37 @@ mod leaf {
             text_end: usize,
             inner: &mut ParserInner<TInlineStack>,
             address: Range<usize>,
+            attributes: Option<Range<usize>>,
+            opening: Range<usize>,
         ) -> crate::Result<Tym<2>> {
             let tym_a = yield_text_if_not_empty(text_start, text_end, inner);
-            let tym_b = inner.r#yield(ev!(Inline, EnterWikiLink(address.clone())));
+            let tym_b = inner.r#yield(ev!(
+                Inline,
+                EnterWikiLink(WikiLink {
+                    address,
+                    attributes,
+                    span: SourceSpan::new_universal(opening),
+                })
+            ));
             Ok(tym_a.add(tym_b))
         }
 
         /// `address_ev` 是属于 Inline 分组的事件，其具体应该是
-        /// [Event::Text] 或  [Event::VerbatimEscaping]。
+        /// [Event::Text] 或  [Event::VerbatimEscaping]。`index_after_indicator`
+        /// 是指示标记之后的索引。
         fn process_indicator<TInlineStack: Stack<StackEntry>>(
             inner: &mut ParserInner<TInlineStack>,
             address_ev: Event,
             indicator: Indicator,
+            index_after_indicator: usize,
         ) -> crate::Result<Tym<2>> {
             let tym = match indicator {
                 Indicator::Closing => {
+                    let span = SourceSpan::new_universal(
+                        (index_after_indicator - "]]".len())..index_after_indicator,
+                    );
                     let tym_c1 = inner.r#yield(address_ev);
-                    let tym_c2 = inner.r#yield(ev!(Inline, ExitInline));
+                    let tym_c2 = inner.r#yield(ev!(Inline, ExitInline(ExitInline { span })));
                     tym_c1.add(tym_c2)
                 }
                 Indicator::Separator => {
@@ -899,4 +1165,212 @@ mod leaf {
             }
         }
     }
+
+    pub mod inline_extension {
+        use super::*;
+
+        #[derive(Debug)]
+        enum Indicator {
+            /// `?`。
+            Parameter,
+            /// `|`。
+            Separator,
+            /// `}]`。
+            Closing,
+        }
+        impl Indicator {
+            fn len(&self) -> usize {
+                match self {
+                    Indicator::Parameter | Indicator::Separator => 1,
+                    Indicator::Closing => 2,
+                }
+            }
+        }
+
+        /// 若 `[{` 之后能解析出行内扩展的开启部分，产出相应的事件并返回 `Some`；否
+        /// 则返回 `None`，此时 cursor 位于 `[{` 之后。
+        pub fn process_and_yield_potential<TInlineStack: Stack<StackEntry>>(
+            input: &[u8],
+            text_start: usize,
+            cursor: &mut Cursor,
+            inner: &mut ParserInner<TInlineStack>,
+            attributes: Option<Range<usize>>,
+        ) -> crate::Result<Option<Tym<3>>> {
+            let maybe_text_end = cursor.value();
+            if let Some(attributes) = &attributes {
+                cursor.set_value(attributes.end);
+            }
+            cursor.move_forward("[{".len());
+
+            let mut name_start = skip_whitespaces(input, cursor.value());
+            if input.get(name_start) != Some(&m!('#')) {
+                return Ok(None);
+            }
+            name_start += "#".len();
+
+            // 扩展名称是单行的逐字内容，因此指示标记必须与 `[{` 位于同一段输入之中。
+            let Some((name_end, indicator, index_after_indicator)) =
+                find_indicator(input, name_start)
+            else {
+                return Ok(None);
+            };
+            let name_start = skip_whitespaces(input, name_start);
+            let name_end = trim_end(input, name_start, name_end);
+            if name_start == name_end {
+                return Ok(None);
+            }
+            let opening = attributes.as_ref().map_or(maybe_text_end, |a| a.end)..name_end;
+            cursor.set_value(index_after_indicator);
+
+            let tym_a = yield_text_if_not_empty(text_start, maybe_text_end, inner);
+            let tym_b = inner.r#yield(ev!(
+                Inline,
+                EnterInlineExtension(InlineExtension {
+                    name: name_start..name_end,
+                    attributes,
+                    span: SourceSpan::new_universal(opening),
+                })
+            ));
+            inner.stack.push_entry(StackEntry::InlineExtension)?;
+            let tym_c = process_indicator(input, cursor, inner, indicator)?;
+
+            Ok(Some(tym_a.add(tym_b).add(tym_c)))
+        }
+
+        /// 调用者应保证栈顶是 [StackEntry::InlineExtension]，且 cursor 位于指示标
+        /// 记之后。
+        fn process_indicator<TInlineStack: Stack<StackEntry>>(
+            input: &[u8],
+            cursor: &mut Cursor,
+            inner: &mut ParserInner<TInlineStack>,
+            indicator: Indicator,
+        ) -> crate::Result<Tym<1>> {
+            let span =
+                SourceSpan::new_universal((cursor.value() - indicator.len())..cursor.value());
+            let tym = match indicator {
+                Indicator::Parameter => {
+                    cursor.set_value(skip_whitespaces(input, cursor.value()));
+                    let name = parse_parameter_name(input, cursor);
+                    inner
+                        .stack
+                        .push_top_leaf(TopLeafInlineExtensionParameter.into());
+                    inner.r#yield(ev!(
+                        Inline,
+                        IndicateExtensionParameter(ExtensionParameter { name, span })
+                    ))
+                }
+                Indicator::Separator => {
+                    cursor.set_value(skip_whitespaces(input, cursor.value()));
+                    inner.r#yield(ev!(
+                        Inline,
+                        IndicateInlineExtensionSlot(Indication { span })
+                    ))
+                }
+                Indicator::Closing => {
+                    let entry = inner.stack.pop_entry();
+                    debug_assert!(entry == Some(StackEntry::InlineExtension));
+                    inner.r#yield(ev!(Inline, ExitInline(ExitInline { span })))
+                }
+            };
+            Ok(tym)
+        }
+
+        /// 若存在参数名称，返回参数名称，并将 cursor 移至参数值的开头。
+        fn parse_parameter_name(input: &[u8], cursor: &mut Cursor) -> Option<Range<usize>> {
+            let start = cursor.value();
+            let end = find_indicator(input, start).map_or(input.len(), |(end, ..)| end);
+            let equal_sign = input[start..end]
+                .iter()
+                .position(|char| *char == m!('='))
+                .map(|i| start + i)?;
+
+            let name_end = trim_end(input, start, equal_sign);
+            if name_end == start {
+                return None;
+            }
+
+            cursor.set_value(skip_whitespaces(input, equal_sign + "=".len()));
+
+            Some(start..name_end)
+        }
+
+        pub fn parse_parameter_value_and_process<TInlineStack: Stack<StackEntry>>(
+            input: &[u8],
+            cursor: &mut Cursor,
+            inner: &mut ParserInner<TInlineStack>,
+            top_leaf: TopLeafInlineExtensionParameter,
+        ) -> crate::Result<Tym<2>> {
+            let start = cursor.value();
+            let Some((end, indicator, index_after_indicator)) = find_indicator(input, start) else {
+                cursor.set_value(input.len());
+                inner.stack.push_top_leaf(top_leaf.into());
+                let tym = yield_text_if_not_empty(start, input.len(), inner);
+                return Ok(tym.into());
+            };
+
+            let tym_a = yield_text_if_not_empty(start, trim_end(input, start, end), inner);
+            cursor.set_value(index_after_indicator);
+            let tym_b = process_indicator(input, cursor, inner, indicator)?;
+
+            Ok(tym_a.add(tym_b))
+        }
+
+        /// 返回的事件属于 `Inline` 分组。
+        ///
+        /// 调用者应保证 cursor 位于直属于行内扩展的 `|` 之上。
+        pub fn process_separator(
+            input: &[u8],
+            text_start: usize,
+            cursor: &mut Cursor,
+        ) -> (usize, Option<Event>) {
+            let text_end = trim_end(input, text_start, cursor.value());
+            let span = SourceSpan::new_universal(cursor.value()..(cursor.value() + "|".len()));
+            cursor.set_value(skip_whitespaces(input, cursor.value() + "|".len()));
+
+            (
+                text_end,
+                Some(ev!(
+                    Inline,
+                    IndicateInlineExtensionSlot(Indication { span })
+                )),
+            )
+        }
+
+        /// 从 `start` 开始寻找第一处指示标记（`?`、`|` 或 `}]`）。若找到，返回指
+        /// 示标记之前的索引、指示标记本身以及指示标记之后的索引。
+        fn find_indicator(input: &[u8], start: usize) -> Option<(usize, Indicator, usize)> {
+            for i in start..input.len() {
+                // SAFETY: `start` <= `i` < `input.len()`.
+                let char = unsafe { input.get_unchecked(i) };
+                match char {
+                    m!('?') => return Some((i, Indicator::Parameter, i + 1)),
+                    m!('|') => return Some((i, Indicator::Separator, i + 1)),
+                    m!('}') if input.get(i + 1) == Some(&m!(']')) => {
+                        return Some((i, Indicator::Closing, i + 2))
+                    }
+                    _ => {}
+                }
+            }
+            None
+        }
+
+        fn skip_whitespaces(input: &[u8], mut i: usize) -> usize {
+            while let Some(&char) = input.get(i) {
+                if is_whitespace!(char) {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            i
+        }
+
+        /// 返回剪除 `start..end` 结尾空白后的结束索引。
+        pub fn trim_end(input: &[u8], start: usize, mut end: usize) -> usize {
+            while end > start && is_whitespace!(input[end - 1]) {
+                end -= 1;
+            }
+            end
+        }
+    }
 }
@@ -1,7 +1,10 @@
 #[cfg(debug_assertions)]
 use crate::events::is_event_of;
+#[cfg(feature = "source-span")]
+use crate::{common::find_verbatim_escaping_end, events::ev};
 use crate::{
-    types::Tym,
+    diagnostics::DiagnosticCollector,
+    types::{SourceSpan, Tym},
     utils::{internal::array_queue::ArrayQueue, stack::Stack},
     Event,
 };
@@ -22,7 +25,14 @@ pub struct ParserInner<TStack: Stack<StackEntry>> {
     /// XXX: 要确保 `cursor` 到达 `input.len()`，以让 `state` 变为 [State::Idle]。
     pub to_skip_input: ToSkipInputEvents,
 
-    pub to_exit_until_popped_entry_from_stack: Option<StackEntry>,
+    /// 第二个值是被弹出的那个条目的闭合部分在输入中的范围。
+    pub to_exit_until_popped_entry_from_stack: Option<(StackEntry, SourceSpan)>,
+
+    pub diagnostics: DiagnosticCollector,
+
+    /// 最近产出的（换行以外的）事件在输入中的结尾。
+    #[cfg(feature = "source-span")]
+    last_yielded_end: usize,
 }
 
 impl<TStack: Stack<StackEntry>> ParserInner<TStack> {
@@ -32,6 +42,9 @@ impl<TStack: Stack<StackEntry>> ParserInner<TStack> {
             to_yield: ArrayQueue::new(),
             to_skip_input: ToSkipInputEvents::default(),
             to_exit_until_popped_entry_from_stack: None,
+            diagnostics: DiagnosticCollector::default(),
+            #[cfg(feature = "source-span")]
+            last_yielded_end: 0,
         }
     }
 
@@ -41,6 +54,43 @@ impl<TStack: Stack<StackEntry>> ParserInner<TStack> {
     pub fn pop_to_be_yielded(&mut self) -> Option<Event> {
         self.to_yield.pop_front()
     }
+
+    /// 记录产出的事件在输入中的结尾。`ev` 是属于 `Inline` 分组的事件。
+    #[cfg(feature = "source-span")]
+    pub fn record_yielded(&mut self, full_input: &[u8], ev: &Event) {
+        match ev {
+            ev!(Inline, Raw(content))
+            | ev!(Inline, Text(content))
+            | ev!(Inline, RefLink(content)) => self.last_yielded_end = content.end,
+            ev!(Inline, VerbatimEscaping(verbatim_escaping)) => {
+                self.last_yielded_end = if verbatim_escaping.is_closed_forcedly {
+                    verbatim_escaping.content.end
+                } else {
+                    find_verbatim_escaping_end(full_input, verbatim_escaping.content.end)
+                };
+            }
+            ev!(Inline, NewLine(_)) => {}
+            ev => {
+                if let Some(span) = ev.span() {
+                    self.last_yielded_end = span.range().end;
+                }
+            }
+        }
+    }
+
+    /// 返回最近产出的事件的结尾处的空范围，用于被隐式闭合的行内元素的退出事件。
+    ///
+    /// 调用者应保证调用时 `to_yield` 中没有尚未产出的事件。
+    pub fn span_after_last_yielded(&self) -> SourceSpan {
+        #[cfg(feature = "source-span")]
+        {
+            SourceSpan::new(self.last_yielded_end..self.last_yielded_end)
+        }
+        #[cfg(not(feature = "source-span"))]
+        {
+            SourceSpan::new()
+        }
+    }
 }
 impl<TStack: Stack<StackEntry>> YieldContext for ParserInner<TStack> {
     /// `ev` 是属于 `Inline` 分组的事件。
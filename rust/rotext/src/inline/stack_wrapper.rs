@@ -1,4 +1,12 @@
-use crate::{common::m, events::ev, utils::stack::Stack, Event};
+use std::ops::Range;
+
+use crate::{
+    common::m,
+    events::{ev, Dicexp, ExitInline, InlineElement},
+    types::SourceSpan,
+    utils::stack::Stack,
+    Event,
+};
 
 pub struct StackWrapper<TStack: Stack<StackEntry>> {
     stack: TStack,
@@ -32,6 +40,9 @@ impl<TStack: Stack<StackEntry>> StackWrapper<TStack> {
             StackEntry::WikiLink => {
                 self.stack_entry_counts.wiki_link += 1;
             }
+            StackEntry::InlineExtension => {
+                self.stack_entry_counts.inline_extension += 1;
+            }
         }
 
         self.stack.try_push(entry)
@@ -57,6 +68,9 @@ impl<TStack: Stack<StackEntry>> StackWrapper<TStack> {
             StackEntry::WikiLink => {
                 self.stack_entry_counts.wiki_link -= 1;
             }
+            StackEntry::InlineExtension => {
+                self.stack_entry_counts.inline_extension -= 1;
+            }
         }
 
         Some(entry)
@@ -66,6 +80,10 @@ impl<TStack: Stack<StackEntry>> StackWrapper<TStack> {
         self.top_leaf.take()
     }
 
+    pub fn top_leaf(&self) -> Option<&TopLeaf> {
+        self.top_leaf.as_ref()
+    }
+
     pub fn make_end_condition(&self) -> EndCondition {
         debug_assert!(self.top_leaf.is_none());
 
@@ -73,6 +91,11 @@ impl<TStack: Stack<StackEntry>> StackWrapper<TStack> {
             on_strong_closing: self.stack_entry_counts.strong > 0,
             on_strikethrough_closing: self.stack_entry_counts.strikethrough > 0,
             on_wiki_link_closing: self.stack_entry_counts.wiki_link > 0,
+            on_inline_extension_closing: self.stack_entry_counts.inline_extension > 0,
+            on_inline_extension_separator: matches!(
+                self.stack.as_slice().last(),
+                Some(StackEntry::InlineExtension)
+            ),
         }
     }
 }
@@ -82,6 +105,7 @@ pub enum StackEntry {
     Strong,
     Strikethrough,
     WikiLink,
+    InlineExtension,
 }
 
 #[derive(Default)]
@@ -89,34 +113,85 @@ struct StackEntryCounts {
     strong: usize,
     strikethrough: usize,
     wiki_link: usize,
+    inline_extension: usize,
 }
 
 pub enum TopLeaf {
+    Dicexp(TopLeafDicexp),
     CodeSpan(TopLeafCodeSpan),
+    InlineExtensionParameter(TopLeafInlineExtensionParameter),
+}
+impl From<TopLeafDicexp> for TopLeaf {
+    fn from(value: TopLeafDicexp) -> Self {
+        Self::Dicexp(value)
+    }
 }
 impl From<TopLeafCodeSpan> for TopLeaf {
     fn from(value: TopLeafCodeSpan) -> Self {
         Self::CodeSpan(value)
     }
 }
+impl From<TopLeafInlineExtensionParameter> for TopLeaf {
+    fn from(value: TopLeafInlineExtensionParameter) -> Self {
+        Self::InlineExtensionParameter(value)
+    }
+}
+
+/// 骰子表达式的代码。记录着 “考虑双引号的中括号匹配语法” 的状态，以便代码跨行。
+#[derive(Debug, PartialEq, Eq)]
+pub struct TopLeafDicexp {
+    /// 尚未配对的 `[` 的数量，包含骰子表达式开启部分中的 `[`。
+    pub depth: usize,
+    pub is_in_string: bool,
+}
+impl TopLeafDicexp {
+    pub fn new() -> Self {
+        Self {
+            depth: 1,
+            is_in_string: false,
+        }
+    }
+
+    pub fn make_enter_event(&self, assigning_to: Option<Range<usize>>, span: SourceSpan) -> Event {
+        ev!(Inline, EnterDicexp(Dicexp { assigning_to, span }))
+    }
+
+    pub fn make_exit_event(&self, span: SourceSpan) -> Event {
+        ev!(Inline, ExitInline(ExitInline { span }))
+    }
+}
 
 pub struct TopLeafCodeSpan {
     pub backticks: usize,
+    /// 开启部分（`[` 与之后的 “`”）在输入中的范围。
+    pub opening: Range<usize>,
 }
 impl TopLeafCodeSpan {
-    pub fn make_enter_event(&self) -> Event {
-        ev!(Inline, EnterCodeSpan)
+    pub fn make_enter_event(&self, attributes: Option<Range<usize>>) -> Event {
+        ev!(
+            Inline,
+            EnterCodeSpan(InlineElement {
+                attributes,
+                span: SourceSpan::new_universal(self.opening.clone()),
+            })
+        )
     }
 
-    pub fn make_exit_event(&self) -> Event {
-        ev!(Inline, ExitInline)
+    pub fn make_exit_event(&self, span: SourceSpan) -> Event {
+        ev!(Inline, ExitInline(ExitInline { span }))
     }
 }
 
+/// 行内扩展的逐字参数的值。此时栈顶必然是 [StackEntry::InlineExtension]。
+pub struct TopLeafInlineExtensionParameter;
+
 pub struct EndCondition {
     pub on_strong_closing: bool,
     pub on_strikethrough_closing: bool,
     pub on_wiki_link_closing: bool,
+    pub on_inline_extension_closing: bool,
+    /// 只有直属于行内扩展时，`|` 才是分隔符。
+    pub on_inline_extension_separator: bool,
 }
 
 impl EndCondition {
@@ -128,6 +203,9 @@ impl EndCondition {
             Some(StackEntry::Strikethrough)
         } else if self.on_wiki_link_closing && char == m!(']') && char_next == Some(m!(']')) {
             Some(StackEntry::WikiLink)
+        } else if self.on_inline_extension_closing && char == m!('}') && char_next == Some(m!(']'))
+        {
+            Some(StackEntry::InlineExtension)
         } else {
             None
         }
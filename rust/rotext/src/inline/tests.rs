@@ -64,16 +64,73 @@ mod for_mod_leaf {
         }
     }
 
+    mod for_ref_link_target {
+        use crate::events::RefLinkTarget;
+
+        #[test]
+        fn it_works() {
+            let table: &[(&[u8], Option<RefLinkTarget>)] = &[
+                (b"#123", Some(RefLinkTarget::Floor(123))),
+                (
+                    b"TP.abc",
+                    Some(RefLinkTarget::Thread {
+                        prefix: "TP",
+                        thread_id: "abc",
+                        floor: None,
+                    }),
+                ),
+                (
+                    b"TP.abc#123",
+                    Some(RefLinkTarget::Thread {
+                        prefix: "TP",
+                        thread_id: "abc",
+                        floor: Some(123),
+                    }),
+                ),
+                (
+                    b"TP.456",
+                    Some(RefLinkTarget::Post {
+                        prefix: "TP",
+                        post_number: 456,
+                    }),
+                ),
+                (b"", None),
+                (b"#", None),
+                (b"#99999999999999999999", None),
+                (b"TP", None),
+                (b"TP.", None),
+                (b".abc", None),
+                (b"TP.abc#", None),
+                (b"TP.ab1", None),
+                (b"TP.456#123", None),
+            ];
+            for (address, expected) in table {
+                assert_eq!(
+                    *expected,
+                    RefLinkTarget::parse(address),
+                    "address: {:?}",
+                    std::str::from_utf8(address)
+                );
+            }
+        }
+    }
+
     mod for_mod_dicexp {
         mod for_fn_advance_until_ends {
             use std::ops::Range;
 
-            use crate::inline::{leaf::dicexp::advance_until_ends, types::Cursor};
+            use crate::inline::{
+                leaf::dicexp::advance_until_ends, stack_wrapper::TopLeafDicexp, types::Cursor,
+            };
 
             fn test(input: &[u8], expected: Range<usize>, expected_cursor: Cursor) {
                 let mut cursor = Cursor::new(0);
-                let actual = advance_until_ends(input, &mut cursor);
-                assert_eq!((expected, expected_cursor), (actual, cursor))
+                let expected_has_ended = expected_cursor.value() > expected.end;
+                let actual = advance_until_ends(input, &mut cursor, &mut TopLeafDicexp::new());
+                assert_eq!(
+                    (expected, expected_has_ended, expected_cursor),
+                    (actual.0, actual.1, cursor)
+                )
             }
 
             #[test]
@@ -89,6 +146,36 @@ mod for_mod_leaf {
                 test(b"[]]", 0..2, Cursor::new(3));
                 test(b"[]", 0..2, Cursor::new(2));
                 test(b"...[...]...]", 0..11, Cursor::new(12));
+
+                test(br#""]"]"#, 0..3, Cursor::new(4));
+                test(br#""["]"#, 0..3, Cursor::new(4));
+                test(br#""]"#, 0..2, Cursor::new(2));
+                test(br#""\"]"]"#, 0..5, Cursor::new(6));
+                test(br#""\\"]"#, 0..4, Cursor::new(5));
+                test(br#""\"#, 0..2, Cursor::new(2));
+                test(br#"["]"]]"#, 0..5, Cursor::new(6));
+                test(br#"[""]]"#, 0..4, Cursor::new(5));
+            }
+
+            #[test]
+            fn it_keeps_state_across_lines() {
+                let mut top_leaf = TopLeafDicexp::new();
+
+                let mut cursor = Cursor::new(0);
+                let actual = advance_until_ends(b"[\"", &mut cursor, &mut top_leaf);
+                assert_eq!((0..2, false), actual);
+                assert_eq!(
+                    TopLeafDicexp {
+                        depth: 2,
+                        is_in_string: true,
+                    },
+                    top_leaf
+                );
+
+                let mut cursor = Cursor::new(0);
+                let actual = advance_until_ends(br#"]"]]..."#, &mut cursor, &mut top_leaf);
+                assert_eq!((0..3, true), actual);
+                assert_eq!(Cursor::new(4), cursor);
             }
         }
     }
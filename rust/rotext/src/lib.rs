@@ -3,6 +3,7 @@
 #![feature(proc_macro_hygiene)]
 #![feature(stmt_expr_attributes)]
 
+mod attributes;
 mod blend;
 mod block;
 mod common;
@@ -10,7 +11,15 @@ mod events;
 mod inline;
 mod types;
 
+pub mod diagnostics;
+#[cfg(feature = "dicexp")]
+pub mod dicexp;
+#[cfg(feature = "incremental")]
+pub mod incremental;
+pub mod line_index;
+pub mod outline;
 pub mod rendering;
+pub mod tree;
 pub mod utils;
 
 #[cfg(test)]
@@ -18,7 +27,7 @@ pub(crate) mod test_suites;
 #[cfg(test)]
 pub(crate) mod test_support;
 
-pub use events::Event;
+pub use events::{Event, RefLinkTarget};
 pub use rendering::{HtmlRenderer, NewHtmlRendererOptions};
 pub use types::{Error, Result};
 
@@ -45,3 +54,23 @@ pub fn parse_with_stack<
 
     blend::BlockEventStreamInlineSegmentMapper::new(input, block_parser)
 }
+
+/// 解析全部输入，并同时收集诊断信息（见 [diagnostics]）。诊断信息按其范围的开头
+/// 排序。
+pub fn parse_with_diagnostics(
+    input: &[u8],
+) -> crate::Result<(Vec<Event>, Vec<diagnostics::Diagnostic>)> {
+    let mut block_parser = block::Parser::<VecStack<block::StackEntry>>::new(input);
+    block_parser.enable_diagnostics();
+    let mut mapper =
+        blend::BlockEventStreamInlineSegmentMapper::<_, VecStack<inline::StackEntry>>::new(
+            input,
+            block_parser,
+        );
+    mapper.enable_diagnostics();
+
+    let events = mapper.by_ref().collect::<crate::Result<Vec<_>>>()?;
+    let diagnostics = mapper.take_diagnostics();
+
+    Ok((events, diagnostics))
+}
@@ -0,0 +1,201 @@
+//! 输入中的位置在不同表示之间的转换。
+//!
+//! 解析得到的范围都是 UTF-8 字节偏移，而编辑器（如 CodeMirror）与 JavaScript
+//! 通常使用 UTF-16 码元偏移，或者 “行、列” 对。[LineIndex] 预先记录各行的开头与
+//! 其中的多字节字符，以便在这些表示之间高效地转换。
+
+#[cfg(test)]
+mod tests;
+
+/// 输入中的位置。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    /// 行号，从 1 开始（与 `line-number` 特性记录的行号一致）。
+    pub line: usize,
+    /// 以 UTF-8 字节计的列，从 0 开始。
+    pub utf8_column: usize,
+    /// 以 UTF-16 码元计的列，从 0 开始。
+    pub utf16_column: usize,
+}
+
+/// 输入的行索引。
+///
+/// `\n`、`\r\n` 与单独的 `\r` 都视为换行，这与解析器的处理一致。传入与返回的字
+/// 节偏移都应位于字符的边界上。
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    /// 各行开头的字节偏移。
+    line_starts: Vec<usize>,
+    /// 各行内容（不含换行）结尾的字节偏移。
+    line_ends: Vec<usize>,
+    /// 各行开头的 UTF-16 偏移。
+    line_starts_utf16: Vec<usize>,
+    /// 输入中的多字节字符，按偏移排序。
+    multi_byte_chars: Vec<MultiByteChar>,
+    /// 各行的第一个多字节字符在 `multi_byte_chars` 中的索引。比行数多一项，最后
+    /// 一项是 `multi_byte_chars` 的长度。
+    multi_byte_char_starts: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MultiByteChar {
+    offset: usize,
+    len_utf8: usize,
+}
+impl MultiByteChar {
+    fn len_utf16(&self) -> usize {
+        if self.len_utf8 == 4 {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+impl LineIndex {
+    /// `input` 应为 UTF-8 编码的文本。
+    pub fn new(input: &[u8]) -> Self {
+        let mut index = Self {
+            line_starts: vec![0],
+            line_ends: vec![],
+            line_starts_utf16: vec![0],
+            multi_byte_chars: vec![],
+            multi_byte_char_starts: vec![0],
+        };
+
+        let mut utf16_offset = 0;
+        let mut i = 0;
+        while let Some(&char) = input.get(i) {
+            match char {
+                b'\r' | b'\n' => {
+                    let len = if char == b'\r' && input.get(i + 1) == Some(&b'\n') {
+                        2
+                    } else {
+                        1
+                    };
+                    index.line_ends.push(i);
+                    i += len;
+                    utf16_offset += len;
+                    index.line_starts.push(i);
+                    index.line_starts_utf16.push(utf16_offset);
+                    index
+                        .multi_byte_char_starts
+                        .push(index.multi_byte_chars.len());
+                }
+                0x00..=0x7F => {
+                    i += 1;
+                    utf16_offset += 1;
+                }
+                _ => {
+                    let multi_byte_char = MultiByteChar {
+                        offset: i,
+                        len_utf8: match char {
+                            0xF0.. => 4,
+                            0xE0.. => 3,
+                            _ => 2,
+                        },
+                    };
+                    i += multi_byte_char.len_utf8;
+                    utf16_offset += multi_byte_char.len_utf16();
+                    index.multi_byte_chars.push(multi_byte_char);
+                }
+            }
+        }
+        index.line_ends.push(input.len());
+        index
+            .multi_byte_char_starts
+            .push(index.multi_byte_chars.len());
+
+        index
+    }
+
+    /// 返回行数。空输入也有一行。
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// 返回字节偏移 `offset` 对应的位置。`offset` 超出输入或位于 `\r\n` 之间时
+    /// 返回 `None`。
+    pub fn position(&self, offset: usize) -> Option<Position> {
+        let line_index = self.line_index_of(offset)?;
+        let utf8_column = offset - self.line_starts[line_index];
+        let utf16_column = utf8_column
+            - self
+                .multi_byte_chars_in_line(line_index)
+                .iter()
+                .take_while(|c| c.offset < offset)
+                .map(|c| c.len_utf8 - c.len_utf16())
+                .sum::<usize>();
+
+        Some(Position {
+            line: line_index + 1,
+            utf8_column,
+            utf16_column,
+        })
+    }
+
+    /// 返回字节偏移 `offset` 对应的 UTF-16 偏移。`offset` 超出输入或位于
+    /// `\r\n` 之间时返回 `None`。
+    pub fn utf16_offset(&self, offset: usize) -> Option<usize> {
+        let position = self.position(offset)?;
+        Some(self.line_starts_utf16[position.line - 1] + position.utf16_column)
+    }
+
+    /// 返回第 `line` 行中以 UTF-8 字节计的第 `utf8_column` 列对应的字节偏移。行
+    /// 或列超出范围时返回 `None`。列最多可以到达行内容（不含换行）的结尾。
+    pub fn offset_at_utf8(&self, line: usize, utf8_column: usize) -> Option<usize> {
+        let line_index = line.checked_sub(1)?;
+        let offset = self.line_starts.get(line_index)? + utf8_column;
+        (offset <= self.line_ends[line_index]).then_some(offset)
+    }
+
+    /// 返回第 `line` 行中以 UTF-16 码元计的第 `utf16_column` 列对应的字节偏移。
+    /// 行或列超出范围，或者列位于代理对之间时，返回 `None`。
+    pub fn offset_at_utf16(&self, line: usize, utf16_column: usize) -> Option<usize> {
+        let line_index = line.checked_sub(1)?;
+        let line_start = *self.line_starts.get(line_index)?;
+
+        let (mut utf8_column, mut current_utf16_column) = (0, 0);
+        for c in self.multi_byte_chars_in_line(line_index) {
+            let ascii_run = c.offset - line_start - utf8_column;
+            if utf16_column <= current_utf16_column + ascii_run {
+                break;
+            }
+            utf8_column += ascii_run;
+            current_utf16_column += ascii_run;
+
+            if utf16_column < current_utf16_column + c.len_utf16() {
+                return None;
+            }
+            utf8_column += c.len_utf8;
+            current_utf16_column += c.len_utf16();
+        }
+
+        self.offset_at_utf8(line, utf8_column + (utf16_column - current_utf16_column))
+    }
+
+    /// 返回 UTF-16 偏移 `utf16_offset` 对应的字节偏移。超出输入或位于代理对之
+    /// 间时返回 `None`。
+    pub fn offset_from_utf16_offset(&self, utf16_offset: usize) -> Option<usize> {
+        let line_index = self
+            .line_starts_utf16
+            .partition_point(|&start| start <= utf16_offset)
+            - 1;
+        let utf16_column = utf16_offset - self.line_starts_utf16[line_index];
+        self.offset_at_utf16(line_index + 1, utf16_column)
+    }
+
+    /// 返回字节偏移 `offset` 所在行的索引（从 0 开始）。换行的开头属于其之前的
+    /// 行，与 [Self::offset_at_utf8] 能够到达的范围一致；`\r\n` 之间的偏移不属
+    /// 于任何一行。
+    fn line_index_of(&self, offset: usize) -> Option<usize> {
+        let line_index = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        (offset <= self.line_ends[line_index]).then_some(line_index)
+    }
+
+    fn multi_byte_chars_in_line(&self, line_index: usize) -> &[MultiByteChar] {
+        let start = self.multi_byte_char_starts[line_index];
+        let end = self.multi_byte_char_starts[line_index + 1];
+        &self.multi_byte_chars[start..end]
+    }
+}
@@ -0,0 +1,127 @@
+use super::{LineIndex, Position};
+
+fn pos(line: usize, utf8_column: usize, utf16_column: usize) -> Option<Position> {
+    Some(Position {
+        line,
+        utf8_column,
+        utf16_column,
+    })
+}
+
+/// 输入，以及由 “字节偏移，预期的位置” 构成的各项。
+type PositionCase<'a> = (&'a str, &'a [(usize, Option<Position>)]);
+
+#[test]
+fn it_converts_offsets_to_positions() {
+    let table: &[PositionCase] = &[
+        ("", &[(0, pos(1, 0, 0)), (1, None)]),
+        (
+            "ab\ncd",
+            &[
+                (0, pos(1, 0, 0)),
+                (2, pos(1, 2, 2)),
+                (3, pos(2, 0, 0)),
+                (5, pos(2, 2, 2)),
+                (6, None),
+            ],
+        ),
+        (
+            "a\r\nb\rc",
+            &[
+                (1, pos(1, 1, 1)),
+                (2, None),
+                (3, pos(2, 0, 0)),
+                (4, pos(2, 1, 1)),
+                (5, pos(3, 0, 0)),
+                (6, pos(3, 1, 1)),
+            ],
+        ),
+        (
+            "é中😀x\n😀",
+            &[
+                (2, pos(1, 2, 1)),
+                (5, pos(1, 5, 2)),
+                (9, pos(1, 9, 4)),
+                (10, pos(1, 10, 5)),
+                (11, pos(2, 0, 0)),
+                (15, pos(2, 4, 2)),
+            ],
+        ),
+    ];
+
+    for (input, cases) in table {
+        let index = LineIndex::new(input.as_bytes());
+        for (offset, expected) in cases.iter() {
+            assert_eq!(
+                *expected,
+                index.position(*offset),
+                "input: {:?}, offset: {}",
+                input,
+                offset
+            );
+        }
+    }
+}
+
+#[test]
+fn it_converts_positions_back_to_offsets() {
+    let input = "é中😀x\r\nab\n😀";
+    let index = LineIndex::new(input.as_bytes());
+    assert_eq!(3, index.line_count());
+
+    assert_eq!(Some(9), index.offset_at_utf8(1, 9));
+    assert_eq!(Some(10), index.offset_at_utf8(1, 10));
+    assert_eq!(None, index.offset_at_utf8(1, 11));
+    assert_eq!(Some(14), index.offset_at_utf8(2, 2));
+    assert_eq!(None, index.offset_at_utf8(0, 0));
+    assert_eq!(None, index.offset_at_utf8(4, 0));
+
+    let table: &[((usize, usize), Option<usize>)] = &[
+        ((1, 0), Some(0)),
+        ((1, 1), Some(2)),
+        ((1, 2), Some(5)),
+        ((1, 3), None),
+        ((1, 4), Some(9)),
+        ((1, 5), Some(10)),
+        ((1, 6), None),
+        ((2, 1), Some(13)),
+        ((3, 0), Some(15)),
+        ((3, 1), None),
+        ((3, 2), Some(19)),
+        ((3, 3), None),
+    ];
+    for ((line, utf16_column), expected) in table {
+        assert_eq!(
+            *expected,
+            index.offset_at_utf16(*line, *utf16_column),
+            "line: {}, utf16 column: {}",
+            line,
+            utf16_column
+        );
+    }
+}
+
+#[test]
+fn it_converts_between_utf8_and_utf16_offsets() {
+    let input = "é中😀x\r\nab\n😀";
+    let index = LineIndex::new(input.as_bytes());
+
+    let mut utf16_offset = 0;
+    for (offset, char) in input.char_indices() {
+        if input[..offset].ends_with('\r') && char == '\n' {
+            // `\r\n` 之间的偏移在两个方向上都不被接受。
+            assert_eq!(None, index.utf16_offset(offset));
+            assert_eq!(None, index.offset_from_utf16_offset(utf16_offset));
+        } else {
+            assert_eq!(Some(utf16_offset), index.utf16_offset(offset));
+            assert_eq!(Some(offset), index.offset_from_utf16_offset(utf16_offset));
+        }
+        utf16_offset += char.len_utf16();
+    }
+    assert_eq!(Some(utf16_offset), index.utf16_offset(input.len()));
+    assert_eq!(
+        Some(input.len()),
+        index.offset_from_utf16_offset(utf16_offset)
+    );
+    assert_eq!(None, index.offset_from_utf16_offset(utf16_offset + 1));
+}
@@ -0,0 +1,142 @@
+//! 文档的大纲，即由文档中的标题构成的树。
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    events::ExitBlock,
+    rendering::{ExtensionRegistry, HeadingSlugger, HeadingTitleCollector},
+    types::{BlockId, LineNumber},
+    Event,
+};
+
+/// 对标题级别的调整，用于将文档嵌入到已经使用了较高级别标题的页面（如论坛中
+/// 的帖子）之中。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeadingLevelAdjustment {
+    /// 为标题的级别增加的值。如为 1 时，`=` 对应于 `h2`。
+    pub offset: usize,
+}
+
+impl HeadingLevelAdjustment {
+    /// 返回源级别为 `source_level` 的标题调整后的级别。超过 6 时视为 6。
+    pub fn apply(&self, source_level: usize) -> usize {
+        (source_level + self.offset).min(6)
+    }
+
+    /// 返回作者是否可以使用级别 `source_level`，即调整后的级别是否不必截断至 6。
+    pub fn allows(&self, source_level: usize) -> bool {
+        source_level + self.offset <= 6
+    }
+}
+
+/// 大纲中的一个标题。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    /// 调整后的级别，为 `1..=6`。
+    pub level: usize,
+    /// 作者在文档中使用的级别，即 `=` 的数量。
+    pub source_level: usize,
+    /// 标题的纯文本内容。
+    pub title: String,
+    /// 与 [crate::HtmlRenderer] 使用相同的扩展注册表时为该标题生成的 `id` 相
+    /// 同。
+    pub slug: String,
+    pub id: BlockId,
+    pub start_line: LineNumber,
+    pub end_line: LineNumber,
+    /// 级别更低、位于本标题之后且位于下一个同级或更高级的标题之前的标题。
+    pub children: Vec<Heading>,
+}
+
+/// 从属于 `Blend` 分组的事件中提取文档的大纲。标题的级别按照 `adjustment` 调
+/// 整，嵌套关系由调整后的级别决定。
+///
+/// 标题的纯文本内容与 [crate::HtmlRenderer] 生成 `id` 时所用的相同：由被渲染出
+/// 来的 `Text` 与 `VerbatimEscaping` 事件的内容构成（换行视为空格），不包括骰子
+/// 表达式的代码，以及由注册表中的处理器负责渲染的扩展的参数与不被渲染的子内容。
+/// 因此 `registry` 应与渲染时使用的注册表相同。
+pub fn extract_outline<'e>(
+    input: &[u8],
+    events: impl IntoIterator<Item = &'e Event>,
+    registry: &ExtensionRegistry,
+    adjustment: HeadingLevelAdjustment,
+) -> Vec<Heading> {
+    let mut slugger = HeadingSlugger::new();
+    let mut titles = HeadingTitleCollector::new(input, registry);
+    let mut roots: Vec<Heading> = vec![];
+    // 尚未确定全部子标题的标题，级别由低到高。
+    let mut open_headings: Vec<Heading> = vec![];
+    // 正在处理的标题的级别。
+    let mut current_level: Option<usize> = None;
+
+    for ev in events {
+        let title = titles.push(ev);
+        match ev {
+            Event::EnterHeading1(_) => current_level = Some(1),
+            Event::EnterHeading2(_) => current_level = Some(2),
+            Event::EnterHeading3(_) => current_level = Some(3),
+            Event::EnterHeading4(_) => current_level = Some(4),
+            Event::EnterHeading5(_) => current_level = Some(5),
+            Event::EnterHeading6(_) => current_level = Some(6),
+            Event::ExitBlock(ExitBlock {
+                id,
+                start_line,
+                end_line,
+                ..
+            }) => {
+                let (Some(source_level), Some(title)) = (current_level.take(), title) else {
+                    continue;
+                };
+                let level = adjustment.apply(source_level);
+                let heading = Heading {
+                    level,
+                    source_level,
+                    slug: slugger.slug(&title),
+                    title: title.trim().to_string(),
+                    id: *id,
+                    start_line: *start_line,
+                    end_line: *end_line,
+                    children: vec![],
+                };
+
+                while open_headings.last().is_some_and(|last| last.level >= level) {
+                    close_last_heading(&mut open_headings, &mut roots);
+                }
+                open_headings.push(heading);
+            }
+            _ => {}
+        }
+    }
+    while !open_headings.is_empty() {
+        close_last_heading(&mut open_headings, &mut roots);
+    }
+
+    roots
+}
+
+/// 返回大纲中使用了 `adjustment` 不允许的级别的标题，按照在文档中出现的顺序排
+/// 列。
+pub fn find_disallowed_headings(
+    headings: &[Heading],
+    adjustment: HeadingLevelAdjustment,
+) -> Vec<&Heading> {
+    let mut result = vec![];
+    let mut stack: Vec<&Heading> = headings.iter().rev().collect();
+    while let Some(heading) = stack.pop() {
+        if !adjustment.allows(heading.source_level) {
+            result.push(heading);
+        }
+        stack.extend(heading.children.iter().rev());
+    }
+
+    result
+}
+
+fn close_last_heading(open_headings: &mut Vec<Heading>, roots: &mut Vec<Heading>) {
+    let heading = open_headings.pop().unwrap();
+    match open_headings.last_mut() {
+        Some(parent) => parent.children.push(heading),
+        None => roots.push(heading),
+    }
+}
@@ -0,0 +1,134 @@
+use indoc::indoc;
+
+use super::{extract_outline, find_disallowed_headings, Heading, HeadingLevelAdjustment};
+
+fn extract(input: &str) -> Vec<Heading> {
+    let events: Vec<_> = crate::parse(input.as_bytes())
+        .collect::<crate::Result<_>>()
+        .unwrap();
+    extract_outline(
+        input.as_bytes(),
+        &events,
+        &Default::default(),
+        Default::default(),
+    )
+}
+
+/// 将大纲转换为 “缩进 级别 标题 #slug” 构成的各行，便于比较。
+fn summarize(headings: &[Heading]) -> Vec<String> {
+    fn walk(headings: &[Heading], depth: usize, lines: &mut Vec<String>) {
+        for heading in headings {
+            lines.push(format!(
+                "{}{} {} #{}",
+                "  ".repeat(depth),
+                heading.level,
+                heading.title,
+                heading.slug
+            ));
+            walk(&heading.children, depth + 1, lines);
+        }
+    }
+
+    let mut lines = vec![];
+    walk(headings, 0, &mut lines);
+    lines
+}
+
+#[test]
+fn it_works() {
+    let table: &[(&str, &[&str])] = &[
+        ("", &[]),
+        ("foo", &[]),
+        ("= a =", &["1 a #a"]),
+        (
+            indoc! {"
+            = A =
+            == B ==
+            === C ===
+            == D ==
+            = E ="},
+            &["1 A #a", "  2 B #b", "    3 C #c", "  2 D #d", "1 E #e"],
+        ),
+        (
+            indoc! {"
+            == A ==
+            = B =
+            === C ===
+            == D =="},
+            &["2 A #a", "1 B #b", "  3 C #c", "  2 D #d"],
+        ),
+        (
+            indoc! {"
+            = 转义 =
+            = 转义 =
+            > == ['Bold'] <`x`> =="},
+            &["1 转义 #转义", "1 转义 #转义-1", "  2 Bold x #bold-x"],
+        ),
+        ("== a[=d6]b ==", &["2 ab #ab"]),
+        (
+            "== A [{#外部链接?[`http://x`]}] B [{#Span|C}] [{#Foo?d|e}] ==",
+            &["2 A  B C de #a-b-c-de"],
+        ),
+    ];
+
+    for (input, expected) in table {
+        assert_eq!(*expected, summarize(&extract(input)), "input: {:?}", input);
+    }
+}
+
+#[test]
+fn it_adjusts_heading_levels() {
+    let input = indoc! {"
+        = A =
+        ====== B ======
+        ===== C =====
+        = D ="};
+    let events: Vec<_> = crate::parse(input.as_bytes())
+        .collect::<crate::Result<_>>()
+        .unwrap();
+    let adjustment = HeadingLevelAdjustment { offset: 1 };
+
+    let outline = extract_outline(input.as_bytes(), &events, &Default::default(), adjustment);
+    assert_eq!(
+        vec!["2 A #a", "  6 B #b", "  6 C #c", "2 D #d"],
+        summarize(&outline)
+    );
+
+    let disallowed: Vec<_> = find_disallowed_headings(&outline, adjustment)
+        .into_iter()
+        .map(|heading| (heading.source_level, heading.title.as_str()))
+        .collect();
+    assert_eq!(vec![(6, "B")], disallowed);
+    assert!(find_disallowed_headings(&outline, Default::default()).is_empty());
+}
+
+#[cfg(feature = "line-number")]
+#[test]
+fn it_takes_line_ranges_from_exit_block_events() {
+    let input = indoc! {"
+        = A =
+        == B =="};
+    let events: Vec<_> = crate::parse(input.as_bytes())
+        .collect::<crate::Result<_>>()
+        .unwrap();
+    let exits: Vec<_> = events
+        .iter()
+        .filter_map(|ev| match ev {
+            crate::Event::ExitBlock(exit) => Some((exit.start_line, exit.end_line)),
+            _ => None,
+        })
+        .collect();
+
+    let outline = extract_outline(
+        input.as_bytes(),
+        &events,
+        &Default::default(),
+        Default::default(),
+    );
+    let [a] = &outline[..] else { panic!() };
+    let [b] = &a.children[..] else { panic!() };
+    assert_eq!(
+        vec![(a.start_line, a.end_line), (b.start_line, b.end_line)],
+        exits
+    );
+}
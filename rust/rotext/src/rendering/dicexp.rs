@@ -0,0 +1,89 @@
+/// 预先计算好的骰子表达式的求值结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DicexpResult {
+    /// 求值成功。内容是值的 JSON 表示，如 `42`、`true`、`[1,2]`。
+    Value(String),
+    /// 求值失败。
+    Error {
+        /// 错误的种类，如 `parse`、`runtime`。
+        kind: String,
+        message: String,
+    },
+}
+
+/// 为渲染中遇到的骰子表达式提供预先计算好的求值结果。
+///
+/// 提供的结果会以 JSON 形式写入骰子表达式元素的 `evaluation` 属性，内容为：
+/// `{"result":["value",<值>],"location":"server"}` 或
+/// `{"result":["error","<种类>","<信息>"],"location":"server"}`。
+pub trait DicexpResultProvider {
+    /// 返回文档中第 `index` 个（从 0 开始计数）骰子表达式的求值结果。`code` 是
+    /// 该骰子表达式的代码，各行之间以 `\n` 相连。
+    fn provide(&self, index: usize, code: &[u8]) -> Option<DicexpResult>;
+}
+
+/// 依照骰子表达式在文档中出现的顺序排列的求值结果。
+impl DicexpResultProvider for Vec<DicexpResult> {
+    fn provide(&self, index: usize, _code: &[u8]) -> Option<DicexpResult> {
+        self.get(index).cloned()
+    }
+}
+
+#[cfg(feature = "dicexp")]
+impl From<&Result<crate::dicexp::Evaluation, crate::dicexp::Error>> for DicexpResult {
+    fn from(result: &Result<crate::dicexp::Evaluation, crate::dicexp::Error>) -> Self {
+        match result {
+            Ok(evaluation) => Self::Value(evaluation.value.to_string()),
+            Err(crate::dicexp::Error::Parse(error)) => Self::Error {
+                kind: "parse".to_string(),
+                message: format!("{:?} at {}", error.kind, error.position),
+            },
+            Err(crate::dicexp::Error::Runtime(error)) => Self::Error {
+                kind: "runtime".to_string(),
+                message: format!("{:?}", error),
+            },
+        }
+    }
+}
+
+impl DicexpResult {
+    /// 返回对应于前端的 `DicexpEvaluation` 的 JSON。
+    pub(super) fn to_evaluation_json(&self) -> String {
+        let mut json = String::from(r#"{"result":"#);
+        match self {
+            DicexpResult::Value(value) => {
+                json.push_str(r#"["value","#);
+                json.push_str(value);
+                json.push(']');
+            }
+            DicexpResult::Error { kind, message } => {
+                json.push_str(r#"["error","#);
+                push_json_string(&mut json, kind);
+                json.push(',');
+                push_json_string(&mut json, message);
+                json.push(']');
+            }
+        }
+        json.push_str(r#","location":"server"}"#);
+
+        json
+    }
+}
+
+fn push_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for char in value.chars() {
+        match char {
+            '"' => json.push_str(r#"\""#),
+            '\\' => json.push_str(r"\\"),
+            '\n' => json.push_str(r"\n"),
+            '\r' => json.push_str(r"\r"),
+            '\t' => json.push_str(r"\t"),
+            char if (char as u32) < 0x20 => {
+                json.push_str(&format!(r"\u{:04x}", char as u32));
+            }
+            char => json.push(char),
+        }
+    }
+    json.push('"');
+}
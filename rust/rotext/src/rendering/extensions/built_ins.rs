@@ -0,0 +1,265 @@
+use super::{
+    ExtensionContext, ExtensionHandler, ExtensionKind, ExtensionSpec, HtmlWriter, SlotMode,
+    TagNameMap,
+};
+
+pub(super) const HANDLERS: &[&dyn ExtensionHandler] = &[
+    &Callout::NOTE,
+    &Callout::TIP,
+    &Callout::IMPORTANT,
+    &Callout::WARNING,
+    &Callout::CAUTION,
+    &Collapse,
+    &Div,
+    &Ruby,
+    &ScratchOff,
+    &Span,
+    &ExternalLink,
+];
+
+/// 只有一个子内容、且不接受任何参数的扩展的声明。
+const fn spec_with_single_slot<'s>(kind: ExtensionKind, names: &'s [&'s str]) -> ExtensionSpec<'s> {
+    ExtensionSpec {
+        kind,
+        names,
+        named_parameters: Some(&[]),
+        max_unnamed_parameters: Some(0),
+        max_slots: Some(1),
+        slot_mode: SlotMode::Rendered,
+    }
+}
+
+/// 告示类扩展。
+struct Callout {
+    names: &'static [&'static str],
+    tag_name: for<'a> fn(&TagNameMap<'a>) -> &'a [u8],
+}
+impl Callout {
+    const NOTE: Self = Self {
+        names: &["注", "Note"],
+        tag_name: |map| map.callout_note,
+    };
+    const TIP: Self = Self {
+        names: &["提示", "Tip"],
+        tag_name: |map| map.callout_tip,
+    };
+    const IMPORTANT: Self = Self {
+        names: &["重要信息", "Important"],
+        tag_name: |map| map.callout_important,
+    };
+    const WARNING: Self = Self {
+        names: &["警告", "Warning"],
+        tag_name: |map| map.callout_warning,
+    };
+    const CAUTION: Self = Self {
+        names: &["当心", "Caution"],
+        tag_name: |map| map.callout_caution,
+    };
+}
+impl ExtensionHandler for Callout {
+    fn spec(&self) -> ExtensionSpec<'_> {
+        spec_with_single_slot(ExtensionKind::Block, self.names)
+    }
+
+    fn render_opening(&self, w: &mut HtmlWriter, ctx: &ExtensionContext) {
+        let tag_name = (self.tag_name)(ctx.tag_name_map());
+        w.write_opening_tag_start(tag_name);
+        ctx.write_attributes(w, tag_name);
+        w.write_data_block_id_attribute(ctx.block_id());
+        w.write_opening_tag_end();
+    }
+
+    fn render_closing(&self, w: &mut HtmlWriter, ctx: &ExtensionContext) {
+        w.write_closing_tag((self.tag_name)(ctx.tag_name_map()));
+    }
+}
+
+struct Collapse;
+impl ExtensionHandler for Collapse {
+    fn spec(&self) -> ExtensionSpec<'_> {
+        ExtensionSpec {
+            named_parameters: Some(&["总结", "默认展开"]),
+            ..spec_with_single_slot(ExtensionKind::Block, &["折叠", "Collapse"])
+        }
+    }
+
+    fn render_opening(&self, w: &mut HtmlWriter, ctx: &ExtensionContext) {
+        w.write_opening_tag_start(ctx.tag_name_map().collapse);
+        ctx.write_attributes(w, ctx.tag_name_map().collapse);
+        if ctx
+            .parameter("默认展开")
+            .is_some_and(|value| value.is("是"))
+        {
+            w.write_boolean_attribute(b"open");
+        }
+        w.write_data_block_id_attribute(ctx.block_id());
+        w.write_opening_tag_end();
+
+        if let Some(summary) = ctx.parameter("总结") {
+            w.write_opening_tag_start(b"span");
+            w.write_attribute(b"slot", b"title");
+            w.write_opening_tag_end();
+            for piece in summary.pieces() {
+                w.write_escaped_text(piece);
+            }
+            w.write_closing_tag(b"span");
+        }
+    }
+
+    fn render_slot_opening(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext, _index: usize) {
+        w.write_opening_tag_start(b"div");
+        w.write_attribute(b"slot", b"content");
+        w.write_opening_tag_end();
+    }
+
+    fn render_slot_closing(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext, _index: usize) {
+        w.write_closing_tag(b"div");
+    }
+
+    fn render_closing(&self, w: &mut HtmlWriter, ctx: &ExtensionContext) {
+        w.write_closing_tag(ctx.tag_name_map().collapse);
+    }
+}
+
+struct Div;
+impl ExtensionHandler for Div {
+    fn spec(&self) -> ExtensionSpec<'_> {
+        spec_with_single_slot(ExtensionKind::Block, &["Div"])
+    }
+
+    fn render_opening(&self, w: &mut HtmlWriter, ctx: &ExtensionContext) {
+        w.write_opening_tag_start(b"div");
+        ctx.write_attributes(w, b"div");
+        w.write_data_block_id_attribute(ctx.block_id());
+        w.write_opening_tag_end();
+    }
+
+    fn render_closing(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext) {
+        w.write_closing_tag(b"div");
+    }
+}
+
+/// 第一个子内容是基文本，第二个子内容是注音。
+struct Ruby;
+impl ExtensionHandler for Ruby {
+    fn spec(&self) -> ExtensionSpec<'_> {
+        ExtensionSpec {
+            max_slots: Some(2),
+            ..spec_with_single_slot(ExtensionKind::Inline, &["注音"])
+        }
+    }
+
+    fn render_opening(&self, w: &mut HtmlWriter, ctx: &ExtensionContext) {
+        w.write_opening_tag_start(b"ruby");
+        ctx.write_attributes(w, b"ruby");
+        w.write_opening_tag_end();
+    }
+
+    fn render_slot_opening(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext, index: usize) {
+        if index == 1 {
+            w.write_opening_tag(b"rt");
+        }
+    }
+
+    fn render_slot_closing(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext, index: usize) {
+        if index == 1 {
+            w.write_closing_tag(b"rt");
+        }
+    }
+
+    fn render_closing(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext) {
+        w.write_closing_tag(b"ruby");
+    }
+}
+
+struct ScratchOff;
+impl ExtensionHandler for ScratchOff {
+    fn spec(&self) -> ExtensionSpec<'_> {
+        spec_with_single_slot(ExtensionKind::Inline, &["刮开"])
+    }
+
+    fn render_opening(&self, w: &mut HtmlWriter, ctx: &ExtensionContext) {
+        w.write_opening_tag_start(ctx.tag_name_map().scratch_off);
+        ctx.write_attributes(w, ctx.tag_name_map().scratch_off);
+        w.write_opening_tag_end();
+    }
+
+    fn render_slot_opening(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext, _index: usize) {
+        w.write_opening_tag_start(b"span");
+        w.write_attribute(b"slot", b"content");
+        w.write_opening_tag_end();
+    }
+
+    fn render_slot_closing(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext, _index: usize) {
+        w.write_closing_tag(b"span");
+    }
+
+    fn render_closing(&self, w: &mut HtmlWriter, ctx: &ExtensionContext) {
+        w.write_closing_tag(ctx.tag_name_map().scratch_off);
+    }
+}
+
+struct Span;
+impl ExtensionHandler for Span {
+    fn spec(&self) -> ExtensionSpec<'_> {
+        spec_with_single_slot(ExtensionKind::Inline, &["Span"])
+    }
+
+    fn render_opening(&self, w: &mut HtmlWriter, ctx: &ExtensionContext) {
+        w.write_opening_tag_start(b"span");
+        ctx.write_attributes(w, b"span");
+        w.write_opening_tag_end();
+    }
+
+    fn render_closing(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext) {
+        w.write_closing_tag(b"span");
+    }
+}
+
+/// 外部链接的地址优先取自第一个无名参数，否则取自第一个子内容中的文本（包括行
+/// 内代码中的文本）。子内容本身不会被渲染。
+struct ExternalLink;
+impl ExtensionHandler for ExternalLink {
+    fn spec(&self) -> ExtensionSpec<'_> {
+        ExtensionSpec {
+            max_unnamed_parameters: Some(1),
+            slot_mode: SlotMode::Text,
+            ..spec_with_single_slot(ExtensionKind::Inline, &["外部链接"])
+        }
+    }
+
+    fn render_opening(&self, w: &mut HtmlWriter, ctx: &ExtensionContext) {
+        w.write_opening_tag_start(ctx.tag_name_map().external_link);
+        ctx.write_attributes(w, ctx.tag_name_map().external_link);
+        match ctx.unnamed_parameter(0) {
+            Some(value) => {
+                w.write_attribute(b"address", strip_code_span_markup(&value.to_vec()));
+            }
+            None => w.write_attribute(b"address", ctx.slot_text(0).unwrap_or_default()),
+        }
+        w.write_opening_tag_end();
+    }
+
+    fn render_closing(&self, w: &mut HtmlWriter, ctx: &ExtensionContext) {
+        w.write_closing_tag(ctx.tag_name_map().external_link);
+    }
+}
+
+/// 若 `value` 整体是行内代码的形式（如 “[`foo`]”），返回其中的代码内容。否则原
+/// 样返回 `value`。
+fn strip_code_span_markup(value: &[u8]) -> &[u8] {
+    let Some(inner) = value.strip_prefix(b"[").and_then(|v| v.strip_suffix(b"]")) else {
+        return value;
+    };
+    let backticks = inner.iter().take_while(|char| **char == b'`').count();
+    if backticks == 0 || inner.len() < backticks * 2 {
+        return value;
+    }
+    let (opening, rest) = inner.split_at(backticks);
+    let Some(code) = rest.strip_suffix(opening) else {
+        return value;
+    };
+
+    let code = code.strip_prefix(b" ").unwrap_or(code);
+    code.strip_suffix(b" ").unwrap_or(code)
+}
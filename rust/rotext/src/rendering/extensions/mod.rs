@@ -0,0 +1,269 @@
+mod built_ins;
+
+use std::ops::Range;
+
+use crate::{
+    events::{ExtensionParameter, VerbatimEscaping},
+    Event,
+};
+
+use super::{HtmlWriter, TagNameMap};
+
+/// 扩展的类别。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtensionKind {
+    Block,
+    Inline,
+}
+
+/// 子内容的处理方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotMode {
+    /// 子内容由渲染器照常渲染，处理器只负责包裹子内容的部分。
+    Rendered,
+    /// 子内容不被渲染，其中的文本在渲染前就被收集起来，可以通过
+    /// [ExtensionContext::slot_text] 获取。
+    Text,
+}
+
+/// 对扩展的声明。
+#[derive(Debug, Clone, Copy)]
+pub struct ExtensionSpec<'s> {
+    pub kind: ExtensionKind,
+    /// 扩展的名称。第一项为正式名称，其余各项为别名。匹配时大小写不敏感。
+    pub names: &'s [&'s str],
+    /// 允许的有名称参数。为 `None` 时不做限制。
+    pub named_parameters: Option<&'s [&'s str]>,
+    /// 最多允许的无名称参数的数量。为 `None` 时不做限制。
+    pub max_unnamed_parameters: Option<usize>,
+    /// 最多允许的子内容的数量。为 `None` 时不做限制。
+    pub max_slots: Option<usize>,
+    pub slot_mode: SlotMode,
+}
+
+/// 扩展的处理器。
+///
+/// 不被允许的参数会被忽略，处理器不会见到它们；超出数量的子内容会被丢弃，不会
+/// 被渲染。
+pub trait ExtensionHandler {
+    fn spec(&self) -> ExtensionSpec<'_>;
+
+    /// 在进入扩展时调用。
+    fn render_opening(&self, w: &mut HtmlWriter, ctx: &ExtensionContext);
+
+    /// 在进入第 `index` 个（从 0 开始）子内容时调用。
+    fn render_slot_opening(&self, _w: &mut HtmlWriter, _ctx: &ExtensionContext, _index: usize) {}
+
+    /// 在离开第 `index` 个（从 0 开始）子内容时调用。
+    fn render_slot_closing(&self, _w: &mut HtmlWriter, _ctx: &ExtensionContext, _index: usize) {}
+
+    /// 在退出扩展时调用。
+    fn render_closing(&self, w: &mut HtmlWriter, ctx: &ExtensionContext);
+}
+
+/// 扩展的注册表。
+///
+/// 渲染器按扩展的类别与名称在注册表中查找处理器。找不到处理器的扩展会以通用的
+/// 方式渲染，即渲染为 [TagNameMap::block_extension] 或
+/// [TagNameMap::inline_extension] 对应的元素，其参数与子内容分别置于
+/// `slot="parameter"` 与 `slot="content"` 的子元素中。
+#[derive(Clone)]
+pub struct ExtensionRegistry<'a> {
+    handlers: Vec<&'a dyn ExtensionHandler>,
+}
+
+impl Default for ExtensionRegistry<'_> {
+    fn default() -> Self {
+        Self::with_built_ins()
+    }
+}
+
+impl<'a> ExtensionRegistry<'a> {
+    /// 创建不包含任何处理器的注册表。
+    pub fn new() -> Self {
+        Self { handlers: vec![] }
+    }
+
+    /// 创建包含全部内置扩展的处理器的注册表。
+    pub fn with_built_ins() -> Self {
+        Self {
+            handlers: built_ins::HANDLERS.to_vec(),
+        }
+    }
+
+    /// 注册处理器。后注册的处理器优先，因此可以用来覆盖内置扩展。
+    pub fn register(&mut self, handler: &'a dyn ExtensionHandler) -> &mut Self {
+        self.handlers.push(handler);
+        self
+    }
+
+    pub fn find(&self, kind: ExtensionKind, name: &[u8]) -> Option<&'a dyn ExtensionHandler> {
+        let name = std::str::from_utf8(name).ok()?;
+        self.handlers.iter().rev().copied().find(|handler| {
+            let spec = handler.spec();
+            spec.kind == kind
+                && spec
+                    .names
+                    .iter()
+                    .any(|candidate| eq_ignore_case(candidate, name))
+        })
+    }
+}
+
+fn eq_ignore_case(a: &str, b: &str) -> bool {
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .eq(b.chars().flat_map(char::to_lowercase))
+}
+
+/// 处理器在渲染时可以获取的信息。
+pub struct ExtensionContext<'c> {
+    pub(super) input: &'c [u8],
+    pub(super) tag_name_map: &'c TagNameMap<'c>,
+    pub(super) parameters: &'c ExtensionParameters,
+    pub(super) slot_texts: &'c [Vec<u8>],
+    pub(super) block_id: Option<usize>,
+    pub(super) attributes: Option<Range<usize>>,
+}
+
+impl<'c> ExtensionContext<'c> {
+    pub fn tag_name_map(&self) -> &TagNameMap<'c> {
+        self.tag_name_map
+    }
+
+    /// 返回名称为 `name` 的参数的值。存在同名参数时，最后的那个有效。
+    pub fn parameter(&self, name: &str) -> Option<ParameterValue<'c>> {
+        self.parameters.get(self.input, name)
+    }
+
+    /// 返回第 `index` 个（从 0 开始）没有名称的参数的值。
+    pub fn unnamed_parameter(&self, index: usize) -> Option<ParameterValue<'c>> {
+        self.parameters.get_unnamed(self.input, index)
+    }
+
+    /// 返回第 `index` 个（从 0 开始）子内容中的文本。只在
+    /// [SlotMode::Text] 下可用。
+    pub fn slot_text(&self, index: usize) -> Option<&'c [u8]> {
+        self.slot_texts.get(index).map(Vec::as_slice)
+    }
+
+    /// 块级扩展的块 ID。只在启用了 `block-id` 特性，且渲染器被要求包含块 ID 时
+    /// 存在。
+    pub fn block_id(&self) -> Option<usize> {
+        self.block_id
+    }
+
+    /// 若扩展附着有附加信息，将其中的类，以及元素 `tag_name` 允许的属性写入 `w`。
+    pub fn write_attributes(&self, w: &mut HtmlWriter, tag_name: &[u8]) {
+        if let Some(attributes) = &self.attributes {
+            w.write_attached_attributes(self.input, attributes.clone(), tag_name);
+        }
+    }
+}
+
+/// 扩展的逐字参数。
+pub(super) struct ExtensionParameters {
+    items: Vec<ExtensionParameterItem>,
+}
+struct ExtensionParameterItem {
+    name: Option<Range<usize>>,
+    /// 来自 `Text` 与 `VerbatimEscaping` 事件的内容，依次拼接后即为参数的值。
+    value: Vec<Range<usize>>,
+}
+
+impl ExtensionParameters {
+    /// 从 `input_stream` 中收集紧随 “进入扩展” 事件的全部参数。返回收集到的参
+    /// 数，以及参数之后的第一个事件。
+    ///
+    /// `input_stream` 的迭代对象是属于 `Blend` 分组的事件。
+    pub fn collect(input_stream: &mut impl Iterator<Item = Event>) -> (Self, Event) {
+        let mut items: Vec<ExtensionParameterItem> = vec![];
+
+        loop {
+            let ev = input_stream.next().unwrap();
+            match ev {
+                Event::IndicateExtensionParameter(ExtensionParameter { name, .. }) => {
+                    items.push(ExtensionParameterItem {
+                        name,
+                        value: vec![],
+                    });
+                }
+                Event::Text(content)
+                | Event::VerbatimEscaping(VerbatimEscaping { content, .. })
+                    if !items.is_empty() =>
+                {
+                    items.last_mut().unwrap().value.push(content);
+                }
+                // 行内扩展的参数可以跨越多行，作为属性值时忽略其中的换行。
+                Event::NewLine(_) if !items.is_empty() => {}
+                ev => break (Self { items }, ev),
+            }
+        }
+    }
+
+    /// 移除 `spec` 不允许的参数。
+    pub fn retain_allowed(&mut self, input: &[u8], spec: &ExtensionSpec) {
+        let mut unnamed_count = 0;
+        self.items.retain(|item| match &item.name {
+            Some(name) => spec.named_parameters.map_or(true, |allowed| {
+                allowed
+                    .iter()
+                    .any(|allowed| allowed.as_bytes() == &input[name.clone()])
+            }),
+            None => {
+                unnamed_count += 1;
+                spec.max_unnamed_parameters
+                    .map_or(true, |max| unnamed_count <= max)
+            }
+        });
+    }
+
+    fn get<'a>(&'a self, input: &'a [u8], name: &str) -> Option<ParameterValue<'a>> {
+        self.items
+            .iter()
+            .rev()
+            .find(|item| {
+                item.name
+                    .as_ref()
+                    .is_some_and(|item_name| &input[item_name.clone()] == name.as_bytes())
+            })
+            .map(|item| ParameterValue {
+                input,
+                pieces: &item.value,
+            })
+    }
+
+    fn get_unnamed<'a>(&'a self, input: &'a [u8], index: usize) -> Option<ParameterValue<'a>> {
+        self.items
+            .iter()
+            .filter(|item| item.name.is_none())
+            .nth(index)
+            .map(|item| ParameterValue {
+                input,
+                pieces: &item.value,
+            })
+    }
+}
+
+/// 参数的值。可能由多段内容拼接而成。
+pub struct ParameterValue<'a> {
+    input: &'a [u8],
+    pieces: &'a [Range<usize>],
+}
+impl<'a> ParameterValue<'a> {
+    /// 依次返回组成值的各段内容。
+    pub fn pieces(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        self.pieces.iter().map(|piece| &self.input[piece.clone()])
+    }
+
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.pieces().flatten().copied().collect()
+    }
+
+    pub fn is(&self, expected: &str) -> bool {
+        self.pieces()
+            .flatten()
+            .copied()
+            .eq(expected.as_bytes().iter().copied())
+    }
+}
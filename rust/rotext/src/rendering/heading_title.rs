@@ -0,0 +1,163 @@
+use crate::{
+    events::{InlineExtension, VerbatimEscaping},
+    Event,
+};
+
+use super::{ExtensionKind, ExtensionRegistry, SlotMode};
+
+/// 收集标题的纯文本内容，以用于生成标题的 slug。
+///
+/// [super::HtmlRenderer] 与 [crate::outline::extract_outline] 共用此收集器，以
+/// 保证二者为同一标题生成的 slug 相同。标题的纯文本内容由被渲染出来的 `Text` 与
+/// `VerbatimEscaping` 事件的内容构成（换行视为空格），不包括骰子表达式的代码，
+/// 以及由注册表中的处理器负责渲染的扩展的参数与不被渲染的子内容。
+pub(crate) struct HeadingTitleCollector<'r, 'a> {
+    input: &'r [u8],
+    registry: &'r ExtensionRegistry<'a>,
+    /// 正在收集的标题的纯文本内容。
+    title: Option<String>,
+    /// 标题中尚未退出的行内元素。
+    stack: Vec<Frame>,
+}
+
+struct Frame {
+    /// 由处理器负责渲染的扩展的子内容的处理方式、最多允许的子内容的数量，以及
+    /// 已经进入过的子内容的数量。对于其他行内元素为 `None`。
+    handled: Option<(SlotMode, Option<usize>, usize)>,
+    /// 元素目前的内容是否计入标题。
+    is_counted: bool,
+}
+
+impl<'r, 'a> HeadingTitleCollector<'r, 'a> {
+    /// `registry` 应与渲染时使用的注册表相同。
+    pub fn new(input: &'r [u8], registry: &'r ExtensionRegistry<'a>) -> Self {
+        Self {
+            input,
+            registry,
+            title: None,
+            stack: vec![],
+        }
+    }
+
+    /// 处理属于 `Blend` 分组的事件 `ev`。若 `ev` 是标题的结束，返回该标题的纯文
+    /// 本内容。
+    pub fn push(&mut self, ev: &Event) -> Option<String> {
+        match ev {
+            Event::EnterHeading1(_)
+            | Event::EnterHeading2(_)
+            | Event::EnterHeading3(_)
+            | Event::EnterHeading4(_)
+            | Event::EnterHeading5(_)
+            | Event::EnterHeading6(_) => {
+                self.title = Some(String::new());
+                self.stack.clear();
+                return None;
+            }
+            _ if self.title.is_none() => return None,
+            // 标题之中只有行内元素，因此这是标题的结束。
+            Event::ExitBlock(_) => return self.title.take(),
+            _ => {}
+        }
+
+        let is_counted = self.stack.last().map_or(true, |frame| frame.is_counted);
+        match ev {
+            Event::EnterDicexp(_) => self.stack.push(Frame {
+                handled: None,
+                is_counted: false,
+            }),
+            Event::EnterInlineExtension(InlineExtension { name, .. }) => {
+                let handler = self
+                    .registry
+                    .find(ExtensionKind::Inline, &self.input[name.clone()]);
+                self.stack.push(match handler {
+                    Some(handler) => {
+                        let spec = handler.spec();
+                        Frame {
+                            handled: Some((spec.slot_mode, spec.max_slots, 0)),
+                            is_counted: false,
+                        }
+                    }
+                    None => Frame {
+                        handled: None,
+                        is_counted,
+                    },
+                });
+            }
+            Event::EnterCodeSpan(_)
+            | Event::EnterStrong(_)
+            | Event::EnterStrikethrough(_)
+            | Event::EnterWikiLink(_) => self.stack.push(Frame {
+                handled: None,
+                is_counted,
+            }),
+            Event::IndicateInlineExtensionSlot(_) => {
+                let parent_is_counted = self
+                    .stack
+                    .iter()
+                    .rev()
+                    .nth(1)
+                    .map_or(true, |frame| frame.is_counted);
+                if let Some(Frame {
+                    handled: Some((slot_mode, max_slots, entered_slots)),
+                    is_counted,
+                }) = self.stack.last_mut()
+                {
+                    *is_counted = parent_is_counted
+                        && *slot_mode == SlotMode::Rendered
+                        && max_slots.map_or(true, |max| *entered_slots < max);
+                    *entered_slots += 1;
+                }
+            }
+            Event::ExitInline(_) => {
+                self.stack.pop();
+            }
+            Event::Text(content) | Event::VerbatimEscaping(VerbatimEscaping { content, .. })
+                if is_counted =>
+            {
+                // SAFETY: 输入是有效的 UTF-8，文本在字符边界处截断。
+                self.title.as_mut().unwrap().push_str(unsafe {
+                    std::str::from_utf8_unchecked(&self.input[content.clone()])
+                });
+            }
+            Event::NewLine(_) if is_counted => self.title.as_mut().unwrap().push(' '),
+            _ => {}
+        }
+
+        None
+    }
+}
+
+/// 在迭代属于 `Blend` 分组的事件的同时，收集其中标题的纯文本内容。
+pub(super) struct HeadingTitleCollectingIter<'r, 'a, I> {
+    inner: I,
+    collector: HeadingTitleCollector<'r, 'a>,
+    /// 最近结束的标题的纯文本内容。
+    finished_title: Option<String>,
+}
+
+impl<'r, 'a, I: Iterator<Item = Event>> HeadingTitleCollectingIter<'r, 'a, I> {
+    pub fn new(inner: I, collector: HeadingTitleCollector<'r, 'a>) -> Self {
+        Self {
+            inner,
+            collector,
+            finished_title: None,
+        }
+    }
+
+    /// 取出最近结束的标题的纯文本内容。
+    pub fn take_finished_title(&mut self) -> Option<String> {
+        self.finished_title.take()
+    }
+}
+
+impl<I: Iterator<Item = Event>> Iterator for HeadingTitleCollectingIter<'_, '_, I> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ev = self.inner.next()?;
+        if let Some(title) = self.collector.push(&ev) {
+            self.finished_title = Some(title);
+        }
+        Some(ev)
+    }
+}
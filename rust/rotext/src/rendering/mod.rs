@@ -1,8 +1,45 @@
+mod dicexp;
+mod extensions;
+mod heading_title;
+mod preview;
+mod ref_link;
+mod slug;
+mod table;
 mod tests;
+mod toc;
+mod wiki_link;
+mod writer;
 
+use crate::common::find_verbatim_escaping_end;
+use crate::events::BlockExtension;
 use crate::events::BlockWithId;
+use crate::events::Dicexp;
+use crate::events::ExtensionParameter;
+use crate::events::InlineElement;
+use crate::events::InlineExtension;
+use crate::events::RefLinkTarget;
+use crate::events::TableCell;
 use crate::events::VerbatimEscaping;
+use crate::events::WikiLink;
+use crate::outline::HeadingLevelAdjustment;
 use crate::Event;
+use extensions::ExtensionParameters;
+use heading_title::HeadingTitleCollectingIter;
+use std::ops::Range;
+use table::TableGrid;
+
+pub use dicexp::{DicexpResult, DicexpResultProvider};
+pub use extensions::{
+    ExtensionContext, ExtensionHandler, ExtensionKind, ExtensionRegistry, ExtensionSpec,
+    ParameterValue, SlotMode,
+};
+pub(crate) use heading_title::HeadingTitleCollector;
+pub use preview::{PreviewWarningReason, RenderMode};
+pub use ref_link::{RefLinkContext, RefLinkResolution, RefLinkResolver};
+pub use slug::{slugify, HeadingSlugger};
+pub use toc::render_table_of_contents;
+pub use wiki_link::{WikiLinkAddress, WikiLinkResolution, WikiLinkResolver, WikiPageIndex};
+pub use writer::HtmlWriter;
 
 macro_rules! write_data_block_id_attribute_if_applicable {
     ($self:ident, $data:ident) => {
@@ -17,9 +54,25 @@ macro_rules! write_data_block_id_attribute_if_applicable {
 
 pub struct NewHtmlRendererOptions<'a> {
     pub tag_name_map: TagNameMap<'a>,
+    pub extension_registry: ExtensionRegistry<'a>,
+    /// 为骰子表达式提供预先计算好的求值结果。
+    pub dicexp_result_provider: Option<&'a dyn DicexpResultProvider>,
+    /// 将引用链接的目标解析为实际的链接。
+    pub ref_link_resolver: Option<&'a dyn RefLinkResolver>,
+    /// 传给 [Self::ref_link_resolver] 的文档上下文。
+    pub ref_link_context: RefLinkContext<'a>,
+    /// 将 Wiki 链接的地址解析为实际的链接。
+    pub wiki_link_resolver: Option<&'a dyn WikiLinkResolver>,
 
     pub initial_output_string_capacity: usize,
 
+    /// 是否为标题写入由其文本生成的 `id` 属性（见 [HeadingSlugger]）。为否时，指
+    /// 向当前页面中存在的锚点的 Wiki 链接也不会带有 `href` 属性。
+    pub should_include_heading_ids: bool,
+    /// 对标题级别的调整。
+    pub heading_level_adjustment: HeadingLevelAdjustment,
+    /// 渲染模式。预览模式下会渲染预览警告。
+    pub render_mode: RenderMode,
     #[cfg(feature = "block-id")]
     pub should_include_block_ids: bool,
 }
@@ -27,38 +80,96 @@ pub struct NewHtmlRendererOptions<'a> {
 #[derive(Clone)]
 pub struct TagNameMap<'a> {
     pub code_block: &'a [u8],
+    pub block_extension: &'a [u8],
+    pub collapse: &'a [u8],
+    pub callout_note: &'a [u8],
+    pub callout_tip: &'a [u8],
+    pub callout_important: &'a [u8],
+    pub callout_warning: &'a [u8],
+    pub callout_caution: &'a [u8],
 
     pub ref_link: &'a [u8],
     pub dicexp: &'a [u8],
     pub wiki_link: &'a [u8],
+    pub inline_extension: &'a [u8],
+    pub scratch_off: &'a [u8],
+    pub external_link: &'a [u8],
+
+    pub preview_warn: &'a [u8],
 }
 impl Default for TagNameMap<'_> {
     fn default() -> Self {
         Self {
             code_block: b"x-code-block",
+            block_extension: b"x-block-extension",
+            collapse: b"x-collapse",
+            callout_note: b"x-callout-note",
+            callout_tip: b"x-callout-tip",
+            callout_important: b"x-callout-important",
+            callout_warning: b"x-callout-warning",
+            callout_caution: b"x-callout-caution",
 
             ref_link: b"x-ref-link",
             dicexp: b"x-dicexp",
             wiki_link: b"x-wiki-link",
+            inline_extension: b"x-inline-extension",
+            scratch_off: b"x-scratch-off",
+            external_link: b"x-external-link",
+
+            preview_warn: b"x-preview-warn",
         }
     }
 }
 
 pub struct HtmlRenderer<'a> {
     tag_name_map: TagNameMap<'a>,
+    extension_registry: ExtensionRegistry<'a>,
+    dicexp_result_provider: Option<&'a dyn DicexpResultProvider>,
+    ref_link_resolver: Option<&'a dyn RefLinkResolver>,
+    ref_link_context: RefLinkContext<'a>,
+    wiki_link_resolver: Option<&'a dyn WikiLinkResolver>,
 
     input: &'a [u8],
 
+    with_heading_id: bool,
+    heading_level_adjustment: HeadingLevelAdjustment,
+    with_preview_warnings: bool,
     #[cfg(feature = "block-id")]
     with_block_id: bool,
 
     result: Vec<u8>,
+    /// 已经渲染过的骰子表达式的数量。
+    dicexp_count: usize,
+    heading_slugger: HeadingSlugger,
+    /// 正在渲染的标题。
+    current_heading: Option<CurrentHeading>,
+    /// 指向当前页面锚点的 Wiki 链接，其元素的属性在渲染结果中的插入位置以及锚
+    /// 点的 slug。当前页面的标题未必都已渲染，因此留待最后处理。
+    same_page_anchor_links: Vec<(usize, String)>,
+    /// 推迟写入的内容在渲染结果中的插入位置，以及内容本身。
+    deferred_insertions: Vec<(usize, Vec<u8>)>,
+    /// 以可能保持原样的标记结尾的文本中，该标记在渲染结果中的位置，以及对应的
+    /// 预览警告的原因。标记在输入中之后紧接着行内元素的闭合部分时不被记录。若
+    /// 之后直到块级槽位结束都只有被强制闭合的行内元素的结束，该标记便位于块级
+    /// 槽位的末尾，需要预览警告。
+    pending_dangling_mark: Option<(usize, PreviewWarningReason)>,
+}
+
+struct CurrentHeading {
+    /// `id` 属性在渲染结果中的插入位置。
+    id_position: usize,
 }
 
 enum StackEntry<'a> {
     Normal(&'a [u8]),
-    Table(TableState),
+    Heading(&'a [u8]),
+    CodeSpan(CodeSpanState),
+    Table(TableState, TableGrid),
+    BlockExtension(ExtensionState),
     WikiLink,
+    InlineExtension(ExtensionState),
+    /// 由注册表中的处理器负责渲染的扩展。
+    HandledExtension(HandledExtensionState<'a>),
 }
 enum TableState {
     AtBeginning,
@@ -67,39 +178,86 @@ enum TableState {
     InHeaderCell,
     InDataCell,
 }
-impl From<TableState> for StackEntry<'_> {
-    fn from(val: TableState) -> Self {
-        StackEntry::Table(val)
-    }
+struct CodeSpanState {
+    /// 开启标签在渲染结果中的位置。
+    position: usize,
+    /// 按照目前为止的内容，行内代码是否是被强制闭合的。只在预览模式下记录。
+    is_closed_forcedly: bool,
+}
+/// 由处理器负责渲染的扩展的状态。其参数在进入时就已全部处理完毕。
+struct HandledExtensionState<'a> {
+    handler: &'a dyn ExtensionHandler,
+    parameters: ExtensionParameters,
+    block_id: Option<usize>,
+    attributes: Option<Range<usize>>,
+    /// 已经进入过的子内容的数量。
+    entered_slots: usize,
+    is_in_slot: bool,
+}
+/// 块级扩展与行内扩展的状态。
+enum ExtensionState {
+    AtBeginning,
+    InParameter,
+    InSlot,
 }
 
 impl<'a> HtmlRenderer<'a> {
     pub fn new(input: &'a [u8], opts: NewHtmlRendererOptions<'a>) -> Self {
         Self {
             tag_name_map: opts.tag_name_map,
+            extension_registry: opts.extension_registry,
+            dicexp_result_provider: opts.dicexp_result_provider,
+            ref_link_resolver: opts.ref_link_resolver,
+            ref_link_context: opts.ref_link_context,
+            wiki_link_resolver: opts.wiki_link_resolver,
             input,
+            with_heading_id: opts.should_include_heading_ids,
+            heading_level_adjustment: opts.heading_level_adjustment,
+            with_preview_warnings: opts.render_mode == RenderMode::Preview,
             #[cfg(feature = "block-id")]
             with_block_id: opts.should_include_block_ids,
             result: Vec::with_capacity(opts.initial_output_string_capacity),
+            dicexp_count: 0,
+            heading_slugger: HeadingSlugger::new(),
+            current_heading: None,
+            same_page_anchor_links: vec![],
+            deferred_insertions: vec![],
+            pending_dangling_mark: None,
         }
     }
 
     /// `input_stream` 的迭代对象是属于 `Blend` 分组的事件。
-    pub fn render(mut self, mut input_stream: impl Iterator<Item = Event>) -> String {
+    pub fn render(mut self, input_stream: impl Iterator<Item = Event>) -> String {
         let mut stack: Vec<StackEntry> = vec![];
+        let extension_registry = self.extension_registry.clone();
+        let mut input_stream = HeadingTitleCollectingIter::new(
+            input_stream,
+            HeadingTitleCollector::new(self.input, &extension_registry),
+        );
 
         loop {
             let Some(ev) = input_stream.next() else {
                 break;
             };
 
-            if let Some(StackEntry::Table(table_state)) = stack.last_mut() {
+            if let Some((position, reason)) = self.pending_dangling_mark.take() {
+                match ev {
+                    // 标记之后只有被强制闭合的行内元素的结束。
+                    Event::ExitInline(_) => self.pending_dangling_mark = Some((position, reason)),
+                    Event::ExitBlock(_) => {
+                        self.wrap_in_preview_warn(position..position + 1, reason)
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(StackEntry::Table(table_state, grid)) = stack.last_mut() {
                 #[rotext_internal_macros::ensure_cases_for_event(
                     prefix = Event,
                     group = Blend,
                 )]
                 match ev {
-                    Event::IndicateTableRow => {
+                    Event::IndicateTableRow(_) => {
                         match table_state {
                             TableState::AtBeginning => self.result.extend(b"<tr>"),
                             TableState::InCaption => self.result.extend(b"</caption><tr>"),
@@ -107,10 +265,11 @@ impl<'a> HtmlRenderer<'a> {
                             TableState::InHeaderCell => self.result.extend(b"</th></tr><tr>"),
                             TableState::InDataCell => self.result.extend(b"</td></tr><tr>"),
                         }
+                        grid.start_row();
                         *table_state = TableState::InRow;
                         continue;
                     }
-                    Event::IndicateTableCaption => {
+                    Event::IndicateTableCaption(_) => {
                         match table_state {
                             TableState::AtBeginning => self.result.extend(b"<caption>"),
                             _ => unreachable!(),
@@ -118,25 +277,15 @@ impl<'a> HtmlRenderer<'a> {
                         *table_state = TableState::InCaption;
                         continue;
                     }
-                    Event::IndicateTableHeaderCell => {
-                        match table_state {
-                            TableState::AtBeginning => self.result.extend(b"<tr><th>"),
-                            TableState::InCaption => self.result.extend(b"</caption><tr><th>"),
-                            TableState::InRow => self.result.extend(b"<th>"),
-                            TableState::InHeaderCell => self.result.extend(b"</th><th>"),
-                            TableState::InDataCell => self.result.extend(b"</td><th>"),
-                        }
+                    Event::IndicateTableHeaderCell(cell) => {
+                        self.close_table_child_before_cell(table_state, grid);
+                        self.write_table_cell_opening(grid, b"th", Some(cell));
                         *table_state = TableState::InHeaderCell;
                         continue;
                     }
-                    Event::IndicateTableDataCell => {
-                        match table_state {
-                            TableState::AtBeginning => self.result.extend(b"<tr><td>"),
-                            TableState::InCaption => self.result.extend(b"</caption><tr><td>"),
-                            TableState::InRow => self.result.extend(b"<td>"),
-                            TableState::InHeaderCell => self.result.extend(b"</th><td>"),
-                            TableState::InDataCell => self.result.extend(b"</td><td>"),
-                        };
+                    Event::IndicateTableDataCell(cell) => {
+                        self.close_table_child_before_cell(table_state, grid);
+                        self.write_table_cell_opening(grid, b"td", Some(cell));
                         *table_state = TableState::InDataCell;
                         continue;
                     }
@@ -148,32 +297,25 @@ impl<'a> HtmlRenderer<'a> {
                                 self.result.extend(top);
                                 self.result.push(b'>');
                             }
-                            StackEntry::Table(TableState::AtBeginning) => {
-                                self.result.extend(b"</table>")
-                            }
-                            StackEntry::Table(TableState::InCaption) => {
-                                self.result.extend(b"</caption></table>")
-                            }
-                            StackEntry::Table(TableState::InRow) => {
-                                self.result.extend(b"</tr></table>")
-                            }
-                            StackEntry::Table(TableState::InHeaderCell) => {
-                                self.result.extend(b"</th></tr></table>")
-                            }
-                            StackEntry::Table(TableState::InDataCell) => {
-                                self.result.extend(b"</td></tr></table>")
+                            StackEntry::Table(table_state, grid) => {
+                                match table_state {
+                                    TableState::AtBeginning => {}
+                                    TableState::InCaption => self.result.extend(b"</caption>"),
+                                    TableState::InRow => self.result.extend(b"</tr>"),
+                                    TableState::InHeaderCell => self.result.extend(b"</th></tr>"),
+                                    TableState::InDataCell => self.result.extend(b"</td></tr>"),
+                                }
+                                self.result.extend(b"</table>");
+                                grid.finish(&mut self.deferred_insertions);
                             }
                             _ => unreachable!(),
                         }
                         continue;
                     }
                     _ => match table_state {
-                        TableState::AtBeginning => {
-                            self.result.extend(b"<tr><td>");
-                            *table_state = TableState::InDataCell;
-                        }
-                        TableState::InRow => {
-                            self.result.extend(b"<td>");
+                        TableState::AtBeginning | TableState::InRow => {
+                            self.close_table_child_before_cell(table_state, grid);
+                            self.write_table_cell_opening(grid, b"td", None);
                             *table_state = TableState::InDataCell;
                         }
                         _ => {}
@@ -181,6 +323,96 @@ impl<'a> HtmlRenderer<'a> {
                 }
             }
 
+            if let Some(StackEntry::BlockExtension(block_extension_state)) = stack.last_mut() {
+                #[rotext_internal_macros::ensure_cases_for_event(
+                    prefix = Event,
+                    group = Blend,
+                )]
+                match ev {
+                    Event::IndicateExtensionParameter(parameter) => {
+                        self.close_extension_child(block_extension_state, b"div");
+                        self.write_extension_parameter_opening(parameter);
+                        *block_extension_state = ExtensionState::InParameter;
+                        continue;
+                    }
+                    Event::IndicateBlockExtensionSlot(_) => {
+                        self.close_extension_child(block_extension_state, b"div");
+                        self.write_opening_tag_with_single_attribute(b"div", b"slot", b"content");
+                        *block_extension_state = ExtensionState::InSlot;
+                        continue;
+                    }
+                    Event::ExitBlock(_) => {
+                        self.close_extension_child(block_extension_state, b"div");
+                        stack.pop();
+                        self.result.extend(b"</");
+                        self.result.extend(self.tag_name_map.block_extension);
+                        self.result.push(b'>');
+                        if self.with_preview_warnings {
+                            self.write_preview_warn_closing();
+                        }
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(StackEntry::InlineExtension(inline_extension_state)) = stack.last_mut() {
+                #[rotext_internal_macros::ensure_cases_for_event(
+                    prefix = Event,
+                    group = Blend,
+                )]
+                match ev {
+                    Event::IndicateExtensionParameter(parameter) => {
+                        self.close_extension_child(inline_extension_state, b"span");
+                        self.write_extension_parameter_opening(parameter);
+                        *inline_extension_state = ExtensionState::InParameter;
+                        continue;
+                    }
+                    Event::IndicateInlineExtensionSlot(_) => {
+                        self.close_extension_child(inline_extension_state, b"span");
+                        self.write_opening_tag_with_single_attribute(b"span", b"slot", b"content");
+                        *inline_extension_state = ExtensionState::InSlot;
+                        continue;
+                    }
+                    Event::ExitInline(_) => {
+                        self.close_extension_child(inline_extension_state, b"span");
+                        stack.pop();
+                        self.result.extend(b"</");
+                        self.result.extend(self.tag_name_map.inline_extension);
+                        self.result.push(b'>');
+                        if self.with_preview_warnings {
+                            self.write_preview_warn_closing();
+                        }
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(StackEntry::HandledExtension(handled_state)) = stack.last_mut() {
+                #[rotext_internal_macros::ensure_cases_for_event(
+                    prefix = Event,
+                    group = Blend,
+                )]
+                match ev {
+                    Event::IndicateBlockExtensionSlot(_)
+                    | Event::IndicateInlineExtensionSlot(_)
+                    | Event::ExitBlock(_)
+                    | Event::ExitInline(_) => {
+                        let has_exited = self.process_handled_extension_event(
+                            handled_state,
+                            ev,
+                            &mut input_stream,
+                        );
+                        if has_exited {
+                            stack.pop();
+                        }
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
             #[rotext_internal_macros::ensure_cases_for_event(
                 prefix = Event,
                 group = Blend,
@@ -189,13 +421,63 @@ impl<'a> HtmlRenderer<'a> {
             // 实际上并不存在问题。
             match ev {
                 Event::Raw(content) => self.write_raw_html(&self.input[content]),
-                Event::NewLine(_) => self.result.extend(b"<br>"),
-                Event::Text(content)
-                | Event::VerbatimEscaping(VerbatimEscaping { content, .. }) => {
-                    self.write_escaped_html_text(&self.input[content]);
+                Event::NewLine(_) => {
+                    if let Some(StackEntry::CodeSpan(code_span)) = stack.last_mut() {
+                        // 无从得知下一行的开头是否是闭合部分，按未被强制闭合处理。
+                        code_span.is_closed_forcedly = false;
+                    }
+                    self.result.extend(b"<br>")
+                }
+                Event::Text(content) => {
+                    self.write_escaped_html_text(&self.input[content.clone()]);
+                    if self.with_preview_warnings {
+                        match stack.last_mut() {
+                            Some(StackEntry::CodeSpan(code_span)) => {
+                                code_span.is_closed_forcedly =
+                                    !preview::is_code_span_closing_at(self.input, content.end);
+                            }
+                            Some(
+                                StackEntry::BlockExtension(ExtensionState::InParameter)
+                                | StackEntry::InlineExtension(ExtensionState::InParameter),
+                            ) => {}
+                            _ if preview::is_inline_element_closing_at(self.input, content.end) => {
+                            }
+                            _ => {
+                                let reason =
+                                    PreviewWarningReason::from_text_end(&self.input[content]);
+                                self.pending_dangling_mark =
+                                    reason.map(|reason| (self.result.len() - 1, reason));
+                            }
+                        }
+                    }
+                }
+                Event::VerbatimEscaping(VerbatimEscaping {
+                    content,
+                    is_closed_forcedly,
+                    ..
+                }) => {
+                    let should_warn = self.with_preview_warnings && is_closed_forcedly;
+                    if should_warn {
+                        self.write_preview_warn_opening(
+                            PreviewWarningReason::UnclosedVerbatimEscaping,
+                        );
+                    }
+                    self.write_escaped_html_text(&self.input[content.clone()]);
+                    if should_warn {
+                        self.write_preview_warn_closing();
+                    }
+                    if let (true, Some(StackEntry::CodeSpan(code_span))) =
+                        (self.with_preview_warnings, stack.last_mut())
+                    {
+                        code_span.is_closed_forcedly = is_closed_forcedly
+                            || !preview::is_code_span_closing_at(
+                                self.input,
+                                find_verbatim_escaping_end(self.input, content.end),
+                            );
+                    }
                 }
 
-                Event::ExitBlock(_) | Event::ExitInline => {
+                Event::ExitBlock(_) | Event::ExitInline(_) => {
                     let top = stack.pop().unwrap();
                     match top {
                         StackEntry::Normal(top) => {
@@ -203,6 +485,21 @@ impl<'a> HtmlRenderer<'a> {
                             self.result.extend(top);
                             self.result.push(b'>');
                         }
+                        StackEntry::Heading(top) => {
+                            self.result.extend(b"</");
+                            self.result.extend(top);
+                            self.result.push(b'>');
+                            self.finish_heading(input_stream.take_finished_title().unwrap());
+                        }
+                        StackEntry::CodeSpan(code_span) => {
+                            self.result.extend(b"</code>");
+                            if code_span.is_closed_forcedly {
+                                self.wrap_in_preview_warn(
+                                    code_span.position..self.result.len(),
+                                    PreviewWarningReason::UnclosedCodeSpan,
+                                );
+                            }
+                        }
                         StackEntry::WikiLink => {
                             self.result.extend(b"</span></");
                             self.result.extend(self.tag_name_map.wiki_link);
@@ -215,17 +512,18 @@ impl<'a> HtmlRenderer<'a> {
                 #[allow(unused_variables)]
                 Event::ThematicBreak(data) => {
                     self.result.extend(b"<hr");
+                    self.write_attached_attributes(b"hr", data.attributes.clone());
                     write_data_block_id_attribute_if_applicable!(self, data);
                     self.result.push(b'>');
                 }
 
                 Event::EnterParagraph(data) => self.push_simple_block(&mut stack, b"p", &data),
-                Event::EnterHeading1(data) => self.push_simple_block(&mut stack, b"h1", &data),
-                Event::EnterHeading2(data) => self.push_simple_block(&mut stack, b"h2", &data),
-                Event::EnterHeading3(data) => self.push_simple_block(&mut stack, b"h3", &data),
-                Event::EnterHeading4(data) => self.push_simple_block(&mut stack, b"h4", &data),
-                Event::EnterHeading5(data) => self.push_simple_block(&mut stack, b"h5", &data),
-                Event::EnterHeading6(data) => self.push_simple_block(&mut stack, b"h6", &data),
+                Event::EnterHeading1(data) => self.push_heading(&mut stack, 1, &data),
+                Event::EnterHeading2(data) => self.push_heading(&mut stack, 2, &data),
+                Event::EnterHeading3(data) => self.push_heading(&mut stack, 3, &data),
+                Event::EnterHeading4(data) => self.push_heading(&mut stack, 4, &data),
+                Event::EnterHeading5(data) => self.push_heading(&mut stack, 5, &data),
+                Event::EnterHeading6(data) => self.push_heading(&mut stack, 6, &data),
                 Event::EnterBlockQuote(data) => {
                     self.push_simple_block(&mut stack, b"blockquote", &data)
                 }
@@ -245,6 +543,10 @@ impl<'a> HtmlRenderer<'a> {
                 Event::EnterCodeBlock(data) => {
                     self.result.push(b'<');
                     self.result.extend(self.tag_name_map.code_block);
+                    self.write_attached_attributes(
+                        self.tag_name_map.code_block,
+                        data.attributes.clone(),
+                    );
 
                     self.result.extend(br#" info-string=""#);
                     loop {
@@ -252,7 +554,7 @@ impl<'a> HtmlRenderer<'a> {
                             Event::Text(content)
                             | Event::VerbatimEscaping(VerbatimEscaping { content, .. }) => self
                                 .write_escaped_double_quoted_attribute_value(&self.input[content]),
-                            Event::IndicateCodeBlockCode => break,
+                            Event::IndicateCodeBlockCode(_) => break,
                             _ => unreachable!(),
                         }
                     }
@@ -289,53 +591,337 @@ impl<'a> HtmlRenderer<'a> {
                 #[allow(unused_variables)]
                 Event::EnterTable(data) => {
                     self.result.extend(b"<table");
+                    self.write_attached_attributes(b"table", data.attributes.clone());
                     write_data_block_id_attribute_if_applicable!(self, data);
                     self.result.push(b'>');
-                    stack.push(TableState::AtBeginning.into())
+                    stack.push(StackEntry::Table(
+                        TableState::AtBeginning,
+                        TableGrid::default(),
+                    ))
                 }
 
-                Event::IndicateCodeBlockCode
-                | Event::IndicateTableCaption
-                | Event::IndicateTableRow
-                | Event::IndicateTableHeaderCell
-                | Event::IndicateTableDataCell => unreachable!(),
+                Event::EnterBlockExtension(data) => {
+                    let BlockExtension {
+                        name, attributes, ..
+                    } = &data;
+                    let handler = self
+                        .extension_registry
+                        .find(ExtensionKind::Block, &self.input[name.clone()]);
+                    if let Some(handler) = handler {
+                        #[allow(unused_mut)]
+                        let mut block_id = None;
+                        #[cfg(feature = "block-id")]
+                        if self.with_block_id {
+                            block_id = Some(data.id.value());
+                        }
+                        self.render_handled_extension(
+                            &mut stack,
+                            &mut input_stream,
+                            handler,
+                            block_id,
+                            attributes.clone(),
+                        );
+                        continue;
+                    }
 
-                Event::RefLink(content) => {
-                    self.write_empty_element_with_single_attribute(
-                        self.tag_name_map.ref_link,
-                        b"address",
-                        &self.input[content],
+                    if self.with_preview_warnings {
+                        self.write_preview_warn_opening(PreviewWarningReason::UnknownExtension);
+                    }
+                    self.result.push(b'<');
+                    self.result.extend(self.tag_name_map.block_extension);
+                    self.write_attached_attributes(
+                        self.tag_name_map.block_extension,
+                        attributes.clone(),
                     );
+                    self.result.extend(br#" name=""#);
+                    self.write_escaped_double_quoted_attribute_value(&self.input[name.clone()]);
+                    self.result.push(b'"');
+                    write_data_block_id_attribute_if_applicable!(self, data);
+                    self.result.push(b'>');
+                    stack.push(StackEntry::BlockExtension(ExtensionState::AtBeginning))
                 }
-                Event::Dicexp(content) => {
-                    self.write_empty_element_with_single_attribute(
-                        self.tag_name_map.dicexp,
-                        b"code",
-                        &self.input[content],
-                    );
+
+                Event::IndicateCodeBlockCode(_)
+                | Event::IndicateTableCaption(_)
+                | Event::IndicateTableRow(_)
+                | Event::IndicateTableHeaderCell(_)
+                | Event::IndicateTableDataCell(_)
+                | Event::IndicateBlockExtensionSlot(_)
+                | Event::IndicateInlineExtensionSlot(_)
+                | Event::IndicateExtensionParameter(_) => unreachable!(),
+
+                Event::RefLink(content) => {
+                    let tag_name = self.tag_name_map.ref_link;
+                    let address = &self.input[content];
+                    let target = RefLinkTarget::parse(address);
+                    let resolution = match (self.ref_link_resolver, &target) {
+                        (Some(resolver), Some(target)) => {
+                            resolver.resolve(target, &self.ref_link_context)
+                        }
+                        _ => None,
+                    };
+
+                    let mut w = HtmlWriter::new(&mut self.result);
+                    w.write_opening_tag_start(tag_name);
+                    w.write_attribute(b"address", address);
+                    match target {
+                        Some(RefLinkTarget::Floor(floor)) => {
+                            write_number_attribute(&mut w, b"floor", floor);
+                        }
+                        Some(RefLinkTarget::Thread {
+                            prefix,
+                            thread_id,
+                            floor,
+                        }) => {
+                            w.write_attribute(b"prefix", prefix.as_bytes());
+                            w.write_attribute(b"thread-id", thread_id.as_bytes());
+                            if let Some(floor) = floor {
+                                write_number_attribute(&mut w, b"floor", floor);
+                            }
+                        }
+                        Some(RefLinkTarget::Post {
+                            prefix,
+                            post_number,
+                        }) => {
+                            w.write_attribute(b"prefix", prefix.as_bytes());
+                            write_number_attribute(&mut w, b"post-number", post_number);
+                        }
+                        None => {}
+                    }
+                    match resolution {
+                        Some(RefLinkResolution::Resolved { href, label }) => {
+                            w.write_attribute(b"href", href.as_bytes());
+                            w.write_opening_tag_end();
+                            if let Some(label) = label {
+                                w.write_escaped_text(label.as_bytes());
+                            }
+                        }
+                        Some(RefLinkResolution::Dangling) => {
+                            w.write_boolean_attribute(b"dangling");
+                            w.write_opening_tag_end();
+                        }
+                        None => w.write_opening_tag_end(),
+                    }
+                    w.write_closing_tag(tag_name);
                 }
+                Event::EnterDicexp(Dicexp { assigning_to, .. }) => {
+                    let mut code: Vec<u8> = vec![];
+                    // 输入不符合预期（提前结束或出现其他事件）时，代码在此结束。
+                    for ev in input_stream.by_ref() {
+                        match ev {
+                            Event::Text(content)
+                            | Event::VerbatimEscaping(VerbatimEscaping { content, .. }) => {
+                                code.extend(&self.input[content])
+                            }
+                            Event::NewLine(_) => code.push(b'\n'),
+                            ev => {
+                                debug_assert!(
+                                    matches!(ev, Event::ExitInline(_)),
+                                    "unexpected event in dicexp: {:?}",
+                                    ev
+                                );
+                                break;
+                            }
+                        }
+                    }
+                    let evaluation = self
+                        .dicexp_result_provider
+                        .and_then(|provider| provider.provide(self.dicexp_count, &code));
+                    self.dicexp_count += 1;
 
-                Event::EnterCodeSpan => self.push_simple_inline(&mut stack, b"code"),
-                Event::EnterStrong => self.push_simple_inline(&mut stack, b"strong"),
-                Event::EnterStrikethrough => self.push_simple_inline(&mut stack, b"s"),
+                    let tag_name = self.tag_name_map.dicexp;
+                    self.result.push(b'<');
+                    self.result.extend(tag_name);
+                    self.result.extend(br#" code=""#);
+                    for (i, line) in code.split(|char| *char == b'\n').enumerate() {
+                        if i > 0 {
+                            self.result.extend(b"&#10;");
+                        }
+                        self.write_escaped_double_quoted_attribute_value(line);
+                    }
+                    self.result.push(b'"');
 
-                Event::EnterWikiLink(address) => {
-                    self.write_opening_tag_with_single_attribute(
-                        self.tag_name_map.wiki_link,
-                        b"address",
-                        &self.input[address],
-                    );
+                    let mut w = HtmlWriter::new(&mut self.result);
+                    if let Some(assigning_to) = assigning_to {
+                        w.write_attribute(b"assigning-to", &self.input[assigning_to]);
+                    }
+                    if let Some(evaluation) = evaluation {
+                        w.write_attribute(
+                            b"evaluation",
+                            evaluation.to_evaluation_json().as_bytes(),
+                        );
+                    }
+                    w.write_opening_tag_end();
+                    w.write_closing_tag(tag_name);
+                }
+
+                Event::EnterCodeSpan(data) => {
+                    let position = self.result.len();
+                    self.write_simple_inline_opening(b"code", data);
+                    stack.push(StackEntry::CodeSpan(CodeSpanState {
+                        position,
+                        is_closed_forcedly: false,
+                    }));
+                }
+                Event::EnterStrong(data) => self.push_simple_inline(&mut stack, b"strong", data),
+                Event::EnterStrikethrough(data) => self.push_simple_inline(&mut stack, b"s", data),
+
+                Event::EnterWikiLink(WikiLink {
+                    address,
+                    attributes,
+                    ..
+                }) => {
+                    self.result.push(b'<');
+                    self.result.extend(self.tag_name_map.wiki_link);
+                    self.write_attached_attributes(self.tag_name_map.wiki_link, attributes);
+                    // SAFETY: Wiki 链接的地址来自有效的 UTF-8 输入，且在 ASCII 字
+                    // 符处截断。
+                    let address = unsafe { std::str::from_utf8_unchecked(&self.input[address]) };
+                    HtmlWriter::new(&mut self.result)
+                        .write_attribute(b"address", address.as_bytes());
+                    match WikiLinkAddress::parse(address) {
+                        WikiLinkAddress {
+                            namespace: None,
+                            page_name: "",
+                            anchor: Some(anchor),
+                        } => {
+                            self.same_page_anchor_links
+                                .push((self.result.len(), slugify(anchor)));
+                        }
+                        address => {
+                            let resolution = self
+                                .wiki_link_resolver
+                                .and_then(|resolver| resolver.resolve(&address));
+                            let mut w = HtmlWriter::new(&mut self.result);
+                            match resolution {
+                                Some(WikiLinkResolution::Resolved { href }) => {
+                                    w.write_attribute(b"href", href.as_bytes());
+                                }
+                                Some(WikiLinkResolution::Missing) => {
+                                    w.write_boolean_attribute(b"missing");
+                                }
+                                None => {}
+                            }
+                        }
+                    }
+                    self.result.push(b'>');
                     self.write_opening_tag_with_single_attribute(b"span", b"slot", b"content");
                     stack.push(StackEntry::WikiLink);
                 }
+                Event::EnterInlineExtension(InlineExtension {
+                    name, attributes, ..
+                }) => {
+                    let handler = self
+                        .extension_registry
+                        .find(ExtensionKind::Inline, &self.input[name.clone()]);
+                    if let Some(handler) = handler {
+                        self.render_handled_extension(
+                            &mut stack,
+                            &mut input_stream,
+                            handler,
+                            None,
+                            attributes,
+                        );
+                        continue;
+                    }
+
+                    if self.with_preview_warnings {
+                        self.write_preview_warn_opening(PreviewWarningReason::UnknownExtension);
+                    }
+                    self.result.push(b'<');
+                    self.result.extend(self.tag_name_map.inline_extension);
+                    self.write_attached_attributes(self.tag_name_map.inline_extension, attributes);
+                    self.result.extend(br#" name=""#);
+                    self.write_escaped_double_quoted_attribute_value(&self.input[name]);
+                    self.result.extend(br#"">"#);
+                    stack.push(StackEntry::InlineExtension(ExtensionState::AtBeginning));
+                }
             }
         }
 
         debug_assert!(stack.is_empty());
 
+        self.resolve_same_page_anchor_links();
+        self.apply_deferred_insertions();
+
         unsafe { String::from_utf8_unchecked(self.result) }
     }
 
+    fn push_heading(
+        &mut self,
+        stack: &mut Vec<StackEntry>,
+        source_level: usize,
+        #[allow(unused_variables)] data: &BlockWithId,
+    ) {
+        const TAG_NAMES: [&[u8]; 6] = [b"h1", b"h2", b"h3", b"h4", b"h5", b"h6"];
+        let tag_name = TAG_NAMES[self.heading_level_adjustment.apply(source_level) - 1];
+
+        self.result.push(b'<');
+        self.result.extend(tag_name);
+        self.write_attached_attributes(tag_name, data.attributes.clone());
+        write_data_block_id_attribute_if_applicable!(self, data);
+        self.current_heading = Some(CurrentHeading {
+            id_position: self.result.len(),
+        });
+        self.result.push(b'>');
+
+        stack.push(StackEntry::Heading(tag_name));
+    }
+
+    /// `title` 是标题的纯文本内容（见 [HeadingTitleCollector]）。
+    fn finish_heading(&mut self, title: String) {
+        let heading = self.current_heading.take().unwrap();
+        let slug = self.heading_slugger.slug(&title);
+        if self.with_heading_id {
+            let mut attribute = vec![];
+            HtmlWriter::new(&mut attribute).write_attribute(b"id", slug.as_bytes());
+            self.deferred_insertions
+                .push((heading.id_position, attribute));
+        }
+    }
+
+    /// 对于指向当前页面中存在的锚点的 Wiki 链接，在标题带有 `id` 属性时写入
+    /// `href` 属性，否则不做处理；对于指向不存在的锚点的，写入 `broken-anchor`
+    /// 属性。
+    fn resolve_same_page_anchor_links(&mut self) {
+        for (position, slug) in std::mem::take(&mut self.same_page_anchor_links) {
+            let mut attribute = vec![];
+            let mut w = HtmlWriter::new(&mut attribute);
+            if !self.heading_slugger.contains(&slug) {
+                w.write_boolean_attribute(b"broken-anchor");
+            } else if self.with_heading_id {
+                w.write_attribute(b"href", format!("#{}", slug).as_bytes());
+            } else {
+                continue;
+            }
+            self.deferred_insertions.push((position, attribute));
+        }
+    }
+
+    fn apply_deferred_insertions(&mut self) {
+        if self.deferred_insertions.is_empty() {
+            return;
+        }
+        self.deferred_insertions
+            .sort_by_key(|(position, _)| *position);
+
+        let extra_len: usize = self
+            .deferred_insertions
+            .iter()
+            .map(|(_, content)| content.len())
+            .sum();
+        let mut result = Vec::with_capacity(self.result.len() + extra_len);
+        let mut last_position = 0;
+        for (position, content) in &self.deferred_insertions {
+            result.extend(&self.result[last_position..*position]);
+            result.extend(content);
+            last_position = *position;
+        }
+        result.extend(&self.result[last_position..]);
+
+        self.result = result;
+    }
+
     fn push_simple_block(
         &mut self,
         stack: &mut Vec<StackEntry>,
@@ -344,42 +930,242 @@ impl<'a> HtmlRenderer<'a> {
     ) {
         self.result.push(b'<');
         self.result.extend(tag_name);
+        self.write_attached_attributes(tag_name, data.attributes.clone());
         write_data_block_id_attribute_if_applicable!(self, data);
         self.result.push(b'>');
 
         stack.push(StackEntry::Normal(tag_name));
     }
 
-    fn push_simple_inline(&mut self, stack: &mut Vec<StackEntry>, tag_name: &'static [u8]) {
+    /// `slot_tag_name` 是扩展的子内容槽位所用的元素。
+    fn close_extension_child(&mut self, state: &ExtensionState, slot_tag_name: &[u8]) {
+        match state {
+            ExtensionState::AtBeginning => {}
+            ExtensionState::InParameter => self.result.extend(b"</span>"),
+            ExtensionState::InSlot => {
+                self.result.extend(b"</");
+                self.result.extend(slot_tag_name);
+                self.result.push(b'>');
+            }
+        }
+    }
+
+    fn write_extension_parameter_opening(&mut self, parameter: ExtensionParameter) {
+        self.result.extend(br#"<span slot="parameter""#);
+        if let Some(name) = parameter.name {
+            self.result.extend(br#" data-name=""#);
+            self.write_escaped_double_quoted_attribute_value(&self.input[name]);
+            self.result.push(b'"');
+        }
+        self.result.push(b'>');
+    }
+
+    fn render_handled_extension(
+        &mut self,
+        stack: &mut Vec<StackEntry<'a>>,
+        input_stream: &mut impl Iterator<Item = Event>,
+        handler: &'a dyn ExtensionHandler,
+        block_id: Option<usize>,
+        attributes: Option<Range<usize>>,
+    ) {
+        let spec = handler.spec();
+        let (mut parameters, ev_after_parameters) = ExtensionParameters::collect(input_stream);
+        parameters.retain_allowed(self.input, &spec);
+
+        if spec.slot_mode == SlotMode::Text {
+            let mut slot_texts: Vec<Vec<u8>> = vec![];
+            let mut ev = ev_after_parameters;
+            while let Event::IndicateBlockExtensionSlot(_) | Event::IndicateInlineExtensionSlot(_) =
+                ev
+            {
+                let is_allowed = spec.max_slots.map_or(true, |max| slot_texts.len() < max);
+                let mut text = vec![];
+                ev = skip_extension_slot(input_stream, |content| {
+                    if is_allowed {
+                        text.extend(&self.input[content]);
+                    }
+                });
+                if is_allowed {
+                    slot_texts.push(text);
+                }
+            }
+
+            let ctx = ExtensionContext {
+                input: self.input,
+                tag_name_map: &self.tag_name_map,
+                parameters: &parameters,
+                slot_texts: &slot_texts,
+                block_id,
+                attributes,
+            };
+            let mut w = HtmlWriter::new(&mut self.result);
+            handler.render_opening(&mut w, &ctx);
+            handler.render_closing(&mut w, &ctx);
+            return;
+        }
+
+        let mut state = HandledExtensionState {
+            handler,
+            parameters,
+            block_id,
+            attributes,
+            entered_slots: 0,
+            is_in_slot: false,
+        };
+        {
+            let ctx = ExtensionContext {
+                input: self.input,
+                tag_name_map: &self.tag_name_map,
+                parameters: &state.parameters,
+                slot_texts: &[],
+                block_id,
+                attributes: state.attributes.clone(),
+            };
+            handler.render_opening(&mut HtmlWriter::new(&mut self.result), &ctx);
+        }
+        if !self.process_handled_extension_event(&mut state, ev_after_parameters, input_stream) {
+            stack.push(StackEntry::HandledExtension(state));
+        }
+    }
+
+    /// 处理属于由处理器负责渲染的扩展的 “进入子内容” 或 “退出” 事件。返回扩展是
+    /// 否已经退出。
+    fn process_handled_extension_event(
+        &mut self,
+        state: &mut HandledExtensionState,
+        mut ev: Event,
+        input_stream: &mut impl Iterator<Item = Event>,
+    ) -> bool {
+        let max_slots = state.handler.spec().max_slots;
+        loop {
+            let ctx = ExtensionContext {
+                input: self.input,
+                tag_name_map: &self.tag_name_map,
+                parameters: &state.parameters,
+                slot_texts: &[],
+                block_id: state.block_id,
+                attributes: state.attributes.clone(),
+            };
+            let mut w = HtmlWriter::new(&mut self.result);
+
+            if state.is_in_slot {
+                state
+                    .handler
+                    .render_slot_closing(&mut w, &ctx, state.entered_slots - 1);
+                state.is_in_slot = false;
+            }
+
+            match ev {
+                Event::IndicateBlockExtensionSlot(_) | Event::IndicateInlineExtensionSlot(_) => {
+                    if max_slots.is_some_and(|max| state.entered_slots >= max) {
+                        ev = skip_extension_slot(input_stream, |_| {});
+                        continue;
+                    }
+                    state
+                        .handler
+                        .render_slot_opening(&mut w, &ctx, state.entered_slots);
+                    state.entered_slots += 1;
+                    state.is_in_slot = true;
+                    break false;
+                }
+                Event::ExitBlock(_) | Event::ExitInline(_) => {
+                    state.handler.render_closing(&mut w, &ctx);
+                    break true;
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// 在写入单元格的开启标签之前，闭合表格中此前的部分，并在需要时开启新的行。
+    fn close_table_child_before_cell(&mut self, table_state: &TableState, grid: &mut TableGrid) {
+        match table_state {
+            TableState::AtBeginning => self.result.extend(b"<tr>"),
+            TableState::InCaption => self.result.extend(b"</caption><tr>"),
+            TableState::InRow => {}
+            TableState::InHeaderCell => self.result.extend(b"</th>"),
+            TableState::InDataCell => self.result.extend(b"</td>"),
+        }
+        if matches!(table_state, TableState::AtBeginning | TableState::InCaption) {
+            grid.start_row();
+        }
+    }
+
+    fn write_table_cell_opening(
+        &mut self,
+        grid: &mut TableGrid,
+        tag_name: &'static [u8],
+        cell: Option<TableCell>,
+    ) {
+        let (colspan, rowspan) = cell.as_ref().map_or((1, 1), |cell| cell.spans(self.input));
+        let attributes = cell.and_then(|cell| cell.attributes);
+
         self.result.push(b'<');
         self.result.extend(tag_name);
+        self.write_attached_attributes(tag_name, attributes);
+        grid.add_cell(self.result.len(), colspan, rowspan);
         self.result.push(b'>');
+    }
 
+    fn push_simple_inline(
+        &mut self,
+        stack: &mut Vec<StackEntry>,
+        tag_name: &'static [u8],
+        data: InlineElement,
+    ) {
+        self.write_simple_inline_opening(tag_name, data);
         stack.push(StackEntry::Normal(tag_name));
     }
 
+    fn write_simple_inline_opening(&mut self, tag_name: &'static [u8], data: InlineElement) {
+        self.result.push(b'<');
+        self.result.extend(tag_name);
+        self.write_attached_attributes(tag_name, data.attributes);
+        self.result.push(b'>');
+    }
+
+    fn write_preview_warn_opening(&mut self, reason: PreviewWarningReason) {
+        preview::write_preview_warn_opening(
+            &mut HtmlWriter::new(&mut self.result),
+            self.tag_name_map.preview_warn,
+            reason,
+        );
+    }
+
+    fn write_preview_warn_closing(&mut self) {
+        HtmlWriter::new(&mut self.result).write_closing_tag(self.tag_name_map.preview_warn);
+    }
+
+    /// 用预览警告包裹渲染结果中位于 `range` 的内容。由于可能需要在已经写入的内
+    /// 容之前插入，统一通过推迟写入的方式进行。
+    fn wrap_in_preview_warn(&mut self, range: Range<usize>, reason: PreviewWarningReason) {
+        let tag_name = self.tag_name_map.preview_warn;
+        let mut opening = vec![];
+        preview::write_preview_warn_opening(&mut HtmlWriter::new(&mut opening), tag_name, reason);
+        let mut closing = vec![];
+        HtmlWriter::new(&mut closing).write_closing_tag(tag_name);
+
+        self.deferred_insertions.push((range.start, opening));
+        self.deferred_insertions.push((range.end, closing));
+    }
+
+    fn write_attached_attributes(&mut self, tag_name: &[u8], attributes: Option<Range<usize>>) {
+        if let Some(attributes) = attributes {
+            HtmlWriter::new(&mut self.result)
+                .write_attached_attributes(self.input, attributes, tag_name);
+        }
+    }
+
     fn write_raw_html(&mut self, input: &[u8]) {
         self.result.extend(input);
     }
 
     fn write_escaped_html_text(&mut self, input: &[u8]) {
-        for char in input {
-            match *char {
-                b'<' => self.result.extend(b"&lt;"),
-                b'&' => self.result.extend(b"&amp;"),
-                char => self.result.push(char),
-            }
-        }
+        HtmlWriter::new(&mut self.result).write_escaped_text(input);
     }
 
     fn write_escaped_double_quoted_attribute_value(&mut self, input: &[u8]) {
-        for char in input {
-            match *char {
-                b'"' => self.result.extend(b"&quot;"),
-                b'&' => self.result.extend(b"&amp;"),
-                char => self.result.push(char),
-            }
-        }
+        HtmlWriter::new(&mut self.result).write_escaped_double_quoted_attribute_value(input);
     }
 
     #[cfg(feature = "block-id")]
@@ -409,16 +1195,69 @@ impl<'a> HtmlRenderer<'a> {
         self.write_escaped_double_quoted_attribute_value(attr_value);
         self.result.extend(br#"">"#);
     }
+}
 
-    fn write_empty_element_with_single_attribute(
-        &mut self,
-        tag_name: &[u8],
-        attr_name: &[u8],
-        attr_value: &[u8],
-    ) {
-        self.write_opening_tag_with_single_attribute(tag_name, attr_name, attr_value);
-        self.result.extend(br#"</"#);
-        self.result.extend(tag_name);
-        self.result.push(b'>');
+fn write_number_attribute(w: &mut HtmlWriter, name: &[u8], value: u64) {
+    let mut buffer = itoa::Buffer::new();
+    w.write_attribute(name, buffer.format(value).as_bytes());
+}
+
+/// 跳过扩展的一个子内容，返回该子内容之后属于该扩展的第一个事件，即 “进入子内
+/// 容” 或 “退出” 事件。子内容中的 `Text` 与 `VerbatimEscaping` 事件的内容会被
+/// 传给 `on_text`。
+///
+/// `input_stream` 的迭代对象是属于 `Blend` 分组的事件。
+fn skip_extension_slot(
+    input_stream: &mut impl Iterator<Item = Event>,
+    mut on_text: impl FnMut(Range<usize>),
+) -> Event {
+    let mut depth = 0;
+    loop {
+        let ev = input_stream.next().unwrap();
+        #[rotext_internal_macros::ensure_cases_for_event(
+            prefix = Event,
+            group = Blend,
+        )]
+        match ev {
+            Event::IndicateBlockExtensionSlot(_)
+            | Event::IndicateInlineExtensionSlot(_)
+            | Event::ExitBlock(_)
+            | Event::ExitInline(_)
+                if depth == 0 =>
+            {
+                break ev
+            }
+            Event::ExitBlock(_) | Event::ExitInline(_) => depth -= 1,
+            Event::EnterDicexp(_) => {
+                // 骰子表达式的代码不属于文本。
+                input_stream.find(|ev| matches!(ev, Event::ExitInline(_)));
+            }
+            Event::EnterParagraph(_)
+            | Event::EnterHeading1(_)
+            | Event::EnterHeading2(_)
+            | Event::EnterHeading3(_)
+            | Event::EnterHeading4(_)
+            | Event::EnterHeading5(_)
+            | Event::EnterHeading6(_)
+            | Event::EnterBlockQuote(_)
+            | Event::EnterOrderedList(_)
+            | Event::EnterUnorderedList(_)
+            | Event::EnterListItem(_)
+            | Event::EnterDescriptionList(_)
+            | Event::EnterDescriptionTerm(_)
+            | Event::EnterDescriptionDetails(_)
+            | Event::EnterCodeBlock(_)
+            | Event::EnterTable(_)
+            | Event::EnterBlockExtension(_)
+            | Event::EnterCodeSpan(_)
+            | Event::EnterStrong(_)
+            | Event::EnterStrikethrough(_)
+            | Event::EnterWikiLink(_)
+            | Event::EnterInlineExtension(_) => depth += 1,
+            Event::Text(content) | Event::VerbatimEscaping(VerbatimEscaping { content, .. }) => {
+                on_text(content)
+            }
+            _ => {}
+        }
     }
 }
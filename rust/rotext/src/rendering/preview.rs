@@ -0,0 +1,85 @@
+use super::HtmlWriter;
+
+/// 渲染模式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderMode {
+    /// 预览模式。可疑的结构会被预览警告（[super::TagNameMap::preview_warn] 对应
+    /// 的元素）包裹，其 `reason` 属性为 [PreviewWarningReason::name]。
+    Preview,
+    /// 发布模式。不渲染预览警告，原先会被其包裹的内容照常渲染。
+    #[default]
+    Publish,
+}
+
+/// 预览警告的原因。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewWarningReason {
+    /// 位于块级槽位末尾、因而保持原样的硬换行标记（`\`）。
+    DanglingHardBreakMark,
+    /// 位于块级槽位末尾、因而保持原样的续行标记（`_`）。
+    DanglingLinesJointMark,
+    /// 被强制闭合的逐字转义。
+    UnclosedVerbatimEscaping,
+    /// 被强制闭合的行内代码。
+    UnclosedCodeSpan,
+    /// 注册表中没有对应处理器的扩展。
+    UnknownExtension,
+}
+
+impl PreviewWarningReason {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::DanglingHardBreakMark => "dangling-hard-break-mark",
+            Self::DanglingLinesJointMark => "dangling-lines-joint-mark",
+            Self::UnclosedVerbatimEscaping => "unclosed-verbatim-escaping",
+            Self::UnclosedCodeSpan => "unclosed-code-span",
+            Self::UnknownExtension => "unknown-extension",
+        }
+    }
+
+    /// 若文本以在块级槽位末尾时会保持原样的标记结尾，返回对应的原因。
+    pub(super) fn from_text_end(text: &[u8]) -> Option<Self> {
+        match text.last() {
+            Some(b'\\') => Some(Self::DanglingHardBreakMark),
+            Some(b'_') => Some(Self::DanglingLinesJointMark),
+            _ => None,
+        }
+    }
+}
+
+/// 返回输入中位于 `position` 处的是否是行内代码的闭合部分（`` `] ``，可能带有
+/// 更多的 “`”，之前可能还有一个空格）。
+pub(super) fn is_code_span_closing_at(input: &[u8], mut position: usize) -> bool {
+    if input.get(position) == Some(&b' ') {
+        position += 1;
+    }
+    let backticks = count_backticks(input, position);
+
+    backticks > 0 && input.get(position + backticks) == Some(&b']')
+}
+
+/// 返回输入中位于 `position` 处的是否是除行内代码外的行内元素的闭合部分（`']`、
+/// `~]`、`]]` 或 `}]`）。
+pub(super) fn is_inline_element_closing_at(input: &[u8], position: usize) -> bool {
+    matches!(
+        input.get(position..position + 2),
+        Some(b"']" | b"~]" | b"]]" | b"}]")
+    )
+}
+
+fn count_backticks(input: &[u8], start: usize) -> usize {
+    input[start.min(input.len())..]
+        .iter()
+        .take_while(|char| **char == b'`')
+        .count()
+}
+
+pub(super) fn write_preview_warn_opening(
+    w: &mut HtmlWriter,
+    tag_name: &[u8],
+    reason: PreviewWarningReason,
+) {
+    w.write_opening_tag_start(tag_name);
+    w.write_attribute(b"reason", reason.name().as_bytes());
+    w.write_opening_tag_end();
+}
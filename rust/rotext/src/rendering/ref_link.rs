@@ -0,0 +1,36 @@
+use crate::events::RefLinkTarget;
+
+/// 解析引用链接时所需的文档上下文。
+#[derive(Debug, Clone, Default)]
+pub struct RefLinkContext<'a> {
+    /// 文档所在的串的串号。用于解析只有楼层的引用链接（`>>#<floor>`）。
+    pub current_thread_id: Option<&'a str>,
+}
+
+/// 引用链接的解析结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefLinkResolution {
+    /// 引用链接指向存在的目标。
+    Resolved {
+        href: String,
+        /// 代替引用链接原本内容显示的文本。
+        label: Option<String>,
+    },
+    /// 引用链接指向的目标不存在。
+    Dangling,
+}
+
+/// 将引用链接的目标解析为实际的链接。
+///
+/// 解析结果会写入引用链接元素：
+/// - [RefLinkResolution::Resolved] 写入 `href` 属性，以及作为子内容的 `label`
+///   （若有）；
+/// - [RefLinkResolution::Dangling] 写入 `dangling` 属性。
+pub trait RefLinkResolver {
+    /// 返回 `None` 时，引用链接按照没有解析器时的方式渲染。
+    fn resolve(
+        &self,
+        target: &RefLinkTarget,
+        context: &RefLinkContext,
+    ) -> Option<RefLinkResolution>;
+}
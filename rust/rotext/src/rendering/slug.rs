@@ -0,0 +1,67 @@
+use std::collections::{HashMap, HashSet};
+
+/// 将标题的文本转换为可用作锚点的 slug。
+///
+/// 字母与数字（包括非 ASCII 的，如汉字）转为小写后保留，`-` 与 `_` 保留，连续
+/// 的空白转为一个 `-`，其他字符则被舍弃。
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    let mut is_after_whitespace = false;
+    for char in text.trim().chars() {
+        if char.is_whitespace() {
+            is_after_whitespace = true;
+            continue;
+        }
+        if !(char.is_alphanumeric() || char == '-' || char == '_') {
+            continue;
+        }
+        if is_after_whitespace {
+            slug.push('-');
+            is_after_whitespace = false;
+        }
+        slug.extend(char.to_lowercase());
+    }
+
+    slug
+}
+
+/// 为文档中的各个标题依次生成互不重复的 slug。
+#[derive(Debug, Default)]
+pub struct HeadingSlugger {
+    /// 基础 slug => 下次尝试的后缀。
+    suffixes: HashMap<String, usize>,
+    used: HashSet<String>,
+}
+
+impl HeadingSlugger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 返回标题 `title` 的 slug。与之前生成过的 slug 重复时，依次在末尾追加
+    /// `-1`、`-2` 等加以区分。标题中没有可用的字符时，以 `section` 为基础。
+    pub fn slug(&mut self, title: &str) -> String {
+        let mut base = slugify(title);
+        if base.is_empty() {
+            base.push_str("section");
+        }
+
+        let suffix = self.suffixes.entry(base.clone()).or_insert(0);
+        loop {
+            let slug = if *suffix == 0 {
+                base.clone()
+            } else {
+                format!("{}-{}", base, suffix)
+            };
+            *suffix += 1;
+            if self.used.insert(slug.clone()) {
+                return slug;
+            }
+        }
+    }
+
+    /// 返回 `slug` 是否已经生成过。
+    pub fn contains(&self, slug: &str) -> bool {
+        self.used.contains(slug)
+    }
+}
@@ -0,0 +1,95 @@
+/// 表格的网格，用于校验单元格的跨列（`colspan`）与跨行（`rowspan`）。
+///
+/// 渲染是流式的，遇到单元格时还不知道表格最终有多少行、多少列，因此对于跨行或跨
+/// 列的单元格，先记下其开启标签中属性结尾的位置，待表格结束后再将校验后的属性插
+/// 入到那里。校验的规则是：
+/// - 跨列不超出表格的宽度，即各单元格所在的列中最靠右的那一列；
+/// - 跨行不超出表格的最后一行。
+#[derive(Default)]
+pub(super) struct TableGrid {
+    /// 已经开始的行的数量。当前行的索引为其减一。
+    rows: usize,
+    /// 当前行中下一个单元格最早可能位于的列。
+    next_column: usize,
+    /// 各列被跨行的单元格占据至哪一行（不含）。
+    occupied_until_row: Vec<usize>,
+    width: usize,
+    spanned_cells: Vec<SpannedCell>,
+}
+
+struct SpannedCell {
+    /// 在渲染结果中插入属性的位置。
+    position: usize,
+    row: usize,
+    column: usize,
+    colspan: usize,
+    rowspan: usize,
+}
+
+impl TableGrid {
+    pub fn start_row(&mut self) {
+        self.rows += 1;
+        self.next_column = 0;
+    }
+
+    /// 在当前行中加入单元格。`position` 是在渲染结果中插入跨列、跨行属性的位置。
+    pub fn add_cell(&mut self, position: usize, colspan: usize, rowspan: usize) {
+        if self.rows == 0 {
+            self.start_row();
+        }
+        let row = self.rows - 1;
+
+        let mut column = self.next_column;
+        while self
+            .occupied_until_row
+            .get(column)
+            .is_some_and(|until| *until > row)
+        {
+            column += 1;
+        }
+        self.width = self.width.max(column + 1);
+
+        if rowspan > 1 {
+            if self.occupied_until_row.len() < column + colspan {
+                self.occupied_until_row.resize(column + colspan, 0);
+            }
+            for until in &mut self.occupied_until_row[column..column + colspan] {
+                *until = (*until).max(row + rowspan);
+            }
+        }
+        if colspan > 1 || rowspan > 1 {
+            self.spanned_cells.push(SpannedCell {
+                position,
+                row,
+                column,
+                colspan,
+                rowspan,
+            });
+        }
+
+        self.next_column = column + colspan;
+    }
+
+    /// 将校验后的跨列、跨行属性连同记下的位置加入 `deferred_insertions`，留待渲
+    /// 染结束后统一插入。
+    pub fn finish(self, deferred_insertions: &mut Vec<(usize, Vec<u8>)>) {
+        for cell in self.spanned_cells {
+            let colspan = cell.colspan.min(self.width - cell.column);
+            let rowspan = cell.rowspan.min(self.rows - cell.row);
+
+            let mut attributes: Vec<u8> = vec![];
+            let mut buffer = itoa::Buffer::new();
+            if colspan > 1 {
+                attributes.extend(br#" colspan=""#);
+                attributes.extend(buffer.format(colspan).as_bytes());
+                attributes.push(b'"');
+            }
+            if rowspan > 1 {
+                attributes.extend(br#" rowspan=""#);
+                attributes.extend(buffer.format(rowspan).as_bytes());
+                attributes.push(b'"');
+            }
+            deferred_insertions.push((cell.position, attributes));
+        }
+    }
+}
@@ -413,44 +413,1168 @@ fn it_works_in_block_phase() {
                 ),
             ],
         },
+        GroupedCases {
+            group: "表格>跨行与跨列",
+            cases: vec![
+                case!(
+                    r#"{! colspan="2" .x}{! rowspan="2"}"#,
+                    [
+                        (EnterTable(..)),
+                        (IndicateTableHeaderCell(attributes = 0..18)),
+                        (IndicateTableRow()),
+                        (IndicateTableDataCell(attributes = 18..33)),
+                        (IndicateTableDataCell()),
+                        (IndicateTableRow()),
+                        (IndicateTableDataCell()),
+                        (ExitBlock(..)),
+                    ],
+                    concat!(
+                        r#"<table><tr><th class="x" colspan="2"></th></tr>"#,
+                        r#"<tr><td rowspan="2"></td><td></td></tr>"#,
+                        "<tr><td></td></tr></table>",
+                    ),
+                ),
+                case!(
+                    r#"{! colspan="3"}{! rowspan="3"}"#,
+                    [
+                        (EnterTable(..)),
+                        (IndicateTableDataCell(attributes = 0..15)),
+                        (IndicateTableRow()),
+                        (IndicateTableDataCell(attributes = 15..30)),
+                        (IndicateTableDataCell()),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<table><tr><td colspan="2"></td></tr><tr><td></td><td></td></tr></table>"#,
+                ),
+                case!(
+                    r#"{! rowspan="2"}"#,
+                    [
+                        (EnterTable(..)),
+                        (IndicateTableDataCell(attributes = 0..15)),
+                        (IndicateTableDataCell()),
+                        (IndicateTableRow()),
+                        (IndicateTableDataCell()),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<table><tr><td rowspan="2"></td><td></td></tr><tr><td></td></tr></table>"#,
+                ),
+                case!(
+                    r#"{! colspan="0" rowspan="x"}"#,
+                    [
+                        (EnterTable(..)),
+                        (IndicateTableDataCell(attributes = 0..27)),
+                        (IndicateTableDataCell()),
+                        (ExitBlock(..)),
+                    ],
+                    "<table><tr><td></td><td></td></tr></table>",
+                ),
+                case!(
+                    r#"{! colspan="2"}"#,
+                    [
+                        (EnterTable(..)),
+                        (IndicateTableDataCell(attributes = 0..15)),
+                        (EnterTable(..)),
+                        (IndicateTableDataCell(attributes = 0..15)),
+                        (ExitBlock(..)),
+                        (IndicateTableDataCell()),
+                        (IndicateTableRow()),
+                        (IndicateTableDataCell()),
+                        (IndicateTableDataCell()),
+                        (IndicateTableDataCell()),
+                        (ExitBlock(..)),
+                    ],
+                    concat!(
+                        r#"<table><tr><td colspan="2"><table><tr><td></td></tr></table></td><td></td></tr>"#,
+                        "<tr><td></td><td></td><td></td></tr></table>",
+                    ),
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "块级扩展",
+            cases: vec![
+                case!(
+                    "Foo",
+                    [
+                        (EnterBlockExtension(0..3)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<x-block-extension name="Foo"></x-block-extension>"#,
+                ),
+                case!(
+                    "Foo:0/bar:6",
+                    [
+                        (EnterBlockExtension(0..3)),
+                        (IndicateBlockExtensionSlot()),
+                        (EnterParagraph(..)),
+                        (Text(6..9)),
+                        (ExitBlock(..)),
+                        (IndicateBlockExtensionSlot()),
+                        (ExitBlock(..)),
+                    ],
+                    concat!(
+                        r#"<x-block-extension name="Foo">"#,
+                        r#"<div slot="content"><p>bar</p></div>"#,
+                        r#"<div slot="content"></div>"#,
+                        "</x-block-extension>",
+                    ),
+                ),
+                case!(
+                    "Foo:0/a:6/1:10/2:15/bar:20",
+                    [
+                        (EnterBlockExtension(0..3)),
+                        (IndicateExtensionParameter(6..7)),
+                        (Text(10..11)),
+                        (IndicateExtensionParameter()),
+                        (VerbatimEscaping(15..16)),
+                        (IndicateBlockExtensionSlot()),
+                        (EnterParagraph(..)),
+                        (Text(20..23)),
+                        (ExitBlock(..)),
+                        (ExitBlock(..)),
+                    ],
+                    concat!(
+                        r#"<x-block-extension name="Foo">"#,
+                        r#"<span slot="parameter" data-name="a">1</span>"#,
+                        r#"<span slot="parameter">2</span>"#,
+                        r#"<div slot="content"><p>bar</p></div>"#,
+                        "</x-block-extension>",
+                    ),
+                ),
+                case!(
+                    "Foo:0/Bar:6",
+                    [
+                        (EnterBlockExtension(0..3)),
+                        (IndicateBlockExtensionSlot()),
+                        (EnterBlockExtension(6..9)),
+                        (IndicateBlockExtensionSlot()),
+                        (ExitBlock(..)),
+                        (ExitBlock(..)),
+                    ],
+                    concat!(
+                        r#"<x-block-extension name="Foo"><div slot="content">"#,
+                        r#"<x-block-extension name="Bar"><div slot="content"></div></x-block-extension>"#,
+                        "</div></x-block-extension>",
+                    ),
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "内置块级扩展",
+            cases: vec![
+                case!(
+                    "注:0/NOTE:6",
+                    [
+                        (EnterBlockExtension(0..3)),
+                        (IndicateBlockExtensionSlot()),
+                        (EnterParagraph(..)),
+                        (Text(6..10)),
+                        (ExitBlock(..)),
+                        (ExitBlock(..)),
+                    ],
+                    "<x-callout-note><p>NOTE</p></x-callout-note>",
+                ),
+                case!(
+                    "当心",
+                    [
+                        (EnterBlockExtension(0..6)),
+                        (ExitBlock(..)),
+                    ],
+                    "<x-callout-caution></x-callout-caution>",
+                ),
+                case!(
+                    "折叠:0/总结:9/标题:18/默认展开:28/是:44/内容:51",
+                    [
+                        (EnterBlockExtension(0..6)),
+                        (IndicateExtensionParameter(9..15)),
+                        (Text(18..24)),
+                        (IndicateExtensionParameter(28..40)),
+                        (VerbatimEscaping(44..47)),
+                        (IndicateBlockExtensionSlot()),
+                        (EnterParagraph(..)),
+                        (Text(51..57)),
+                        (ExitBlock(..)),
+                        (IndicateBlockExtensionSlot()),
+                        (ExitBlock(..)),
+                    ],
+                    concat!(
+                        r#"<x-collapse open><span slot="title">标题</span>"#,
+                        r#"<div slot="content"><p>内容</p></div>"#,
+                        "</x-collapse>",
+                    ),
+                ),
+                case!(
+                    "折叠:0/默认展开:9/否:24",
+                    [
+                        (EnterBlockExtension(0..6)),
+                        (IndicateExtensionParameter(9..21)),
+                        (Text(24..27)),
+                        (ExitBlock(..)),
+                    ],
+                    "<x-collapse></x-collapse>",
+                ),
+                case!(
+                    "div:0/内容:6",
+                    [
+                        (EnterBlockExtension(0..3)),
+                        (IndicateBlockExtensionSlot()),
+                        (EnterParagraph(..)),
+                        (Text(6..12)),
+                        (ExitBlock(..)),
+                        (ExitBlock(..)),
+                    ],
+                    "<div><p>内容</p></div>",
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "块级附加信息",
+            cases: vec![
+                case!(
+                    r#"{! .foo .bar class="baz  qux" title="t" onclick="x" TITLE="u"}a"#,
+                    [
+                        (EnterParagraph(.., attributes = 0..62)),
+                        (Text(62..63)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p class="foo bar baz qux" title="t">a</p>"#,
+                ),
+                case!(
+                    r#"{! start="3" .x}"#,
+                    [
+                        (EnterOrderedList(.., attributes = 0..16)),
+                        (ExitBlock(..)),
+                        (EnterUnorderedList(.., attributes = 0..16)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<ol class="x" start="3"></ol><ul class="x"></ul>"#,
+                ),
+                case!(
+                    "{! .foo}",
+                    [
+                        (ThematicBreak(.., attributes = 0..8)),
+                        (EnterTable(.., attributes = 0..8)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<hr class="foo"><table class="foo"></table>"#,
+                ),
+                case!(
+                    "{! .foo}Div",
+                    [
+                        (EnterBlockExtension(8..11, attributes = 0..8)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<div class="foo"></div>"#,
+                ),
+                case!(
+                    "{! .foo}注",
+                    [
+                        (EnterBlockExtension(8..11, attributes = 0..8)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<x-callout-note class="foo"></x-callout-note>"#,
+                ),
+                case!(
+                    "{! .foo}Foo",
+                    [
+                        (EnterBlockExtension(8..11, attributes = 0..8)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<x-block-extension class="foo" name="Foo"></x-block-extension>"#,
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "行内附加信息",
+            cases: vec![
+                case!(
+                    r#"[! .foo title="t" onclick="x"]a"#,
+                    [
+                        (EnterParagraph(..)),
+                        (@inline EnterStrong(.., attributes = 0..30)),
+                        (Text(30..31)),
+                        (@inline ExitInline(..)),
+                        (@inline EnterCodeSpan(.., attributes = 0..30)),
+                        (@inline ExitInline(..)),
+                        (@inline EnterStrikethrough(.., attributes = 0..30)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><strong class="foo" title="t">a</strong><code class="foo" title="t"></code><s class="foo" title="t"></s></p>"#,
+                ),
+                case!(
+                    "[! .foo]ADDR",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterWikiLink(8..12, attributes = 0..8)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><x-wiki-link class="foo" address="ADDR"><span slot="content"></span></x-wiki-link></p>"#,
+                ),
+                case!(
+                    "[! .foo]Foo",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterInlineExtension(8..11, attributes = 0..8)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><x-inline-extension class="foo" name="Foo"></x-inline-extension></p>"#,
+                ),
+                case!(
+                    "[! .foo]Span",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterInlineExtension(8..12, attributes = 0..8)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><span class="foo"></span></p>"#,
+                ),
+            ],
+        },
         GroupedCases {
             group: "Wiki链接",
             cases: vec![
                 case!(
-                    "ADDR:0/title:10",
+                    "ADDR:0/title:10",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterWikiLink(0..4)),
+                        (Text(7..12)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><x-wiki-link address="ADDR"><span slot="content">title</span></x-wiki-link></p>"#,
+                ),
+            ]
+        },
+        GroupedCases {
+            group: "行内扩展",
+            cases: vec![
+                case!(
+                    "Foo",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterInlineExtension(0..3)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><x-inline-extension name="Foo"></x-inline-extension></p>"#,
+                ),
+                case!(
+                    "Foo:0/a:6/1:10/2:15/bar:20",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterInlineExtension(0..3)),
+                        (IndicateExtensionParameter(6..7)),
+                        (Text(10..11)),
+                        (IndicateExtensionParameter()),
+                        (VerbatimEscaping(15..16)),
+                        (IndicateInlineExtensionSlot()),
+                        (@inline EnterStrong(..)),
+                        (Text(20..23)),
+                        (@inline ExitInline(..)),
+                        (IndicateInlineExtensionSlot()),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    concat!(
+                        r#"<p><x-inline-extension name="Foo">"#,
+                        r#"<span slot="parameter" data-name="a">1</span>"#,
+                        r#"<span slot="parameter">2</span>"#,
+                        r#"<span slot="content"><strong>bar</strong></span>"#,
+                        r#"<span slot="content"></span>"#,
+                        "</x-inline-extension></p>",
+                    ),
+                ),
+            ]
+        },
+        GroupedCases {
+            group: "内置行内扩展",
+            cases: vec![
+                case!(
+                    "注音:0/基:9/ji:15",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterInlineExtension(0..6)),
+                        (IndicateInlineExtensionSlot()),
+                        (Text(9..12)),
+                        (IndicateInlineExtensionSlot()),
+                        (Text(15..17)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    "<p><ruby>基<rt>ji</rt></ruby></p>",
+                ),
+                case!(
+                    "刮开:0/a:9/b:13",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterInlineExtension(0..6)),
+                        (IndicateInlineExtensionSlot()),
+                        (Text(9..10)),
+                        (IndicateInlineExtensionSlot()),
+                        (Text(13..14)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><x-scratch-off><span slot="content">a</span></x-scratch-off></p>"#,
+                ),
+                case!(
+                    "Span:0/x:7",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterInlineExtension(0..4)),
+                        (IndicateInlineExtensionSlot()),
+                        (@inline EnterStrong(..)),
+                        (Text(7..8)),
+                        (@inline ExitInline(..)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    "<p><span><strong>x</strong></span></p>",
+                ),
+                case!(
+                    "外部链接:0/[`https://example.com`]:15",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterInlineExtension(0..12)),
+                        (IndicateExtensionParameter()),
+                        (Text(15..38)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><x-external-link address="https://example.com"></x-external-link></p>"#,
+                ),
+                case!(
+                    "外部链接:0/https://example.com:15",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterInlineExtension(0..12)),
+                        (IndicateInlineExtensionSlot()),
+                        (@inline EnterCodeSpan(..)),
+                        (Text(15..34)),
+                        (@inline ExitInline(..)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><x-external-link address="https://example.com"></x-external-link></p>"#,
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "XSS",
+            cases: vec![
+                case!(
+                    r#"<script>"#,
+                    [
+                        (EnterParagraph(..)),
+                        (Text(0..8)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p>&lt;script></p>"#,
+                ),
+                case!(
+                    r#"">"#,
+                    [
+                        (EnterCodeBlock(..)),
+                        (Text(0..2)),
+                        (IndicateCodeBlockCode()),
+                        (Text(0..2)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<x-code-block info-string="&quot;>" content="&quot;>"></x-code-block>"#,
+                ),
+                case!(
+                    r#"">"#,
+                    [
+                        (EnterBlockExtension(0..2)),
+                        (IndicateExtensionParameter(0..2)),
+                        (Text(0..2)),
+                        (ExitBlock(..)),
+                    ],
+                    concat!(
+                        r#"<x-block-extension name="&quot;>">"#,
+                        r#"<span slot="parameter" data-name="&quot;>">"></span>"#,
+                        "</x-block-extension>",
+                    ),
+                ),
+                case!(
+                    r#"">"#,
+                    [
+                        (EnterParagraph(..)),
+                        (EnterInlineExtension(0..2)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><x-inline-extension name="&quot;>"></x-inline-extension></p>"#,
+                ),
+                case!(
+                    "折叠:0/总结:9/<script>:18",
+                    [
+                        (EnterBlockExtension(0..6)),
+                        (IndicateExtensionParameter(9..15)),
+                        (Text(18..26)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<x-collapse><span slot="title">&lt;script></span></x-collapse>"#,
+                ),
+                case!(
+                    r#"外部链接:0/">:15"#,
+                    [
+                        (EnterParagraph(..)),
+                        (EnterInlineExtension(0..12)),
+                        (IndicateExtensionParameter()),
+                        (Text(15..17)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><x-external-link address="&quot;>"></x-external-link></p>"#,
+                ),
+                case!(
+                    r#"{! ."><x title='"&'}"#,
+                    [
+                        (EnterParagraph(.., attributes = 0..20)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p class="&quot;><x" title="&quot;&amp;"></p>"#,
+                ),
+                case!(
+                    r#"{! title="a&quot;b&amp;lt;&#x4E2D;&#25991;&bogus;&#0;&" class="&#120; y"}"#,
+                    [
+                        (EnterParagraph(.., attributes = 0..73)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p class="x y" title="a&quot;b&amp;lt;中文&amp;bogus;�&amp;"></p>"#,
+                ),
+            ],
+        },
+    ];
+
+    run_cases(table);
+}
+
+#[cfg(feature = "block-id")]
+#[test]
+fn it_works_with_block_id() {
+    let table: Vec<GroupedCases<_>> = vec![GroupedCases {
+        group: "基础",
+        cases: vec![
+            case!(
+                @with_id,
+                "",
+                [(ThematicBreak(.., id = 1)),],
+                r#"<hr data-block-id="1">"#,
+            ),
+            case!(
+                @with_id,
+                "foo",
+                [
+                    (EnterParagraph(.., id = 1)),
+                    (Text(0..3)),
+                    (ExitBlock(.., id = 1)),
+                ],
+                r#"<p data-block-id="1">foo</p>"#,
+            ),
+            case!(
+                @with_id,
+                "foo:0/bar:6",
+                [
+                    (EnterTable(.., id = 1)),
+                    (IndicateTableHeaderCell()),
+                    (EnterParagraph(.., id = 2)),
+                    (Text(0..3)),
+                    (ExitBlock(.., id = 2)),
+                    (IndicateTableRow()),
+                    (EnterParagraph(.., id = 3)),
+                    (Text(6..9)),
+                    (ExitBlock(.., id = 3)),
+                    (ExitBlock(.., id = 1)),
+                ],
+                concat!(
+                    r#"<table data-block-id="1">"#,
+                    r#"<tr><th><p data-block-id="2">foo</p></th></tr>"#,
+                    r#"<tr><td><p data-block-id="3">bar</p></td></tr>"#,
+                    "</table>"
+                ),
+            ),
+            case!(
+                @with_id,
+                "Foo:0/bar:6",
+                [
+                    (EnterBlockExtension(0..3, id = 1)),
+                    (IndicateBlockExtensionSlot()),
+                    (EnterParagraph(.., id = 2)),
+                    (Text(6..9)),
+                    (ExitBlock(.., id = 2)),
+                    (ExitBlock(.., id = 1)),
+                ],
+                concat!(
+                    r#"<x-block-extension name="Foo" data-block-id="1">"#,
+                    r#"<div slot="content"><p data-block-id="2">bar</p></div>"#,
+                    "</x-block-extension>"
+                ),
+            ),
+            case!(
+                @with_id,
+                "折叠:0/bar:9",
+                [
+                    (EnterBlockExtension(0..6, id = 1)),
+                    (IndicateBlockExtensionSlot()),
+                    (EnterParagraph(.., id = 2)),
+                    (Text(9..12)),
+                    (ExitBlock(.., id = 2)),
+                    (ExitBlock(.., id = 1)),
+                ],
+                concat!(
+                    r#"<x-collapse data-block-id="1">"#,
+                    r#"<div slot="content"><p data-block-id="2">bar</p></div>"#,
+                    "</x-collapse>"
+                ),
+            ),
+        ],
+    }];
+
+    run_cases(table);
+}
+
+#[test]
+fn it_works_with_heading_ids() {
+    let table: Vec<GroupedCases<_>> = vec![
+        GroupedCases {
+            group: "标题的id",
+            cases: vec![
+                case!(
+                    @with_heading_ids,
+                    "Hello, World!",
+                    [(EnterHeading1(..)), (Text(0..13)), (ExitBlock(..)),],
+                    r#"<h1 id="hello-world">Hello, World!</h1>"#,
+                ),
+                case!(
+                    @with_heading_ids,
+                    "转义  Ä_b-c",
+                    [(EnterHeading2(..)), (Text(0..14)), (ExitBlock(..)),],
+                    r#"<h2 id="转义-ä_b-c">转义  Ä_b-c</h2>"#,
+                ),
+                case!(
+                    @with_heading_ids,
+                    "ab-1",
+                    [
+                        (EnterHeading2(..)),
+                        (Text(0..2)),
+                        (ExitBlock(..)),
+                        (EnterHeading2(..)),
+                        (@inline EnterStrong(..)),
+                        (Text(0..2)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                        (EnterHeading2(..)),
+                        (Text(0..4)),
+                        (ExitBlock(..)),
+                    ],
+                    concat!(
+                        r#"<h2 id="ab">ab</h2>"#,
+                        r#"<h2 id="ab-1"><strong>ab</strong></h2>"#,
+                        r#"<h2 id="ab-1-1">ab-1</h2>"#,
+                    ),
+                ),
+                case!(
+                    @with_heading_ids,
+                    "!?",
+                    [(EnterHeading3(..)), (Text(0..2)), (ExitBlock(..)),],
+                    r#"<h3 id="section">!?</h3>"#,
+                ),
+                case!(
+                    @with_heading_ids,
+                    "[! .x]ab",
+                    [(EnterHeading3(.., attributes = 0..6)), (Text(6..8)), (ExitBlock(..)),],
+                    r#"<h3 class="x" id="ab">ab</h3>"#,
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "标题级别的调整",
+            cases: vec![case!(
+                @with_heading_level_offset 1,
+                "a",
+                [
+                    (EnterHeading1(..)),
+                    (Text(0..1)),
+                    (ExitBlock(..)),
+                    (EnterHeading5(..)),
+                    (Text(0..1)),
+                    (ExitBlock(..)),
+                    (EnterHeading6(..)),
+                    (Text(0..1)),
+                    (ExitBlock(..)),
+                ],
+                "<h2>a</h2><h6>a</h6><h6>a</h6>",
+            )],
+        },
+        GroupedCases {
+            group: "同页锚点",
+            cases: vec![
+                case!(
+                    @with_heading_ids,
+                    "#转义 #无",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterWikiLink(0..7)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                        (EnterHeading2(..)),
+                        (Text(1..7)),
+                        (ExitBlock(..)),
+                        (EnterParagraph(..)),
+                        (EnterWikiLink(8..12)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    concat!(
+                        r##"<p><x-wiki-link address="#转义" href="#转义"><span slot="content"></span></x-wiki-link></p>"##,
+                        r#"<h2 id="转义">转义</h2>"#,
+                        r##"<p><x-wiki-link address="#无" broken-anchor><span slot="content"></span></x-wiki-link></p>"##,
+                    ),
+                ),
+                case!(
+                    "#标题 #无",
+                    [
+                        (EnterHeading2(..)),
+                        (Text(1..7)),
+                        (ExitBlock(..)),
+                        (EnterParagraph(..)),
+                        (EnterWikiLink(0..7)),
+                        (@inline ExitInline(..)),
+                        (EnterWikiLink(8..12)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    concat!(
+                        "<h2>标题</h2>",
+                        r##"<p><x-wiki-link address="#标题"><span slot="content"></span></x-wiki-link>"##,
+                        r##"<x-wiki-link address="#无" broken-anchor><span slot="content"></span></x-wiki-link></p>"##,
+                    ),
+                ),
+                case!(
+                    @with_heading_ids,
+                    "#b-1 b",
+                    [
+                        (EnterHeading2(..)),
+                        (Text(5..6)),
+                        (ExitBlock(..)),
+                        (EnterHeading2(..)),
+                        (Text(5..6)),
+                        (ExitBlock(..)),
+                        (EnterParagraph(..)),
+                        (EnterWikiLink(0..4)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    concat!(
+                        r#"<h2 id="b">b</h2>"#,
+                        r#"<h2 id="b-1">b</h2>"#,
+                        r##"<p><x-wiki-link address="#b-1" href="#b-1"><span slot="content"></span></x-wiki-link></p>"##,
+                    ),
+                ),
+            ],
+        },
+    ];
+
+    run_cases(table);
+}
+
+#[test]
+fn it_renders_table_of_contents() {
+    let input = "= A =\n== <b> ==\n== <b> ==\n= ! =";
+    let events: Vec<_> = crate::parse(input.as_bytes())
+        .collect::<crate::Result<_>>()
+        .unwrap();
+
+    let outline = crate::outline::extract_outline(
+        input.as_bytes(),
+        &events,
+        &Default::default(),
+        Default::default(),
+    );
+    assert_eq!(
+        concat!(
+            r##"<ol><li><a href="#a">A</a><ol>"##,
+            r##"<li><a href="#b">&lt;b></a></li>"##,
+            r##"<li><a href="#b-1">&lt;b></a></li>"##,
+            r##"</ol></li><li><a href="#section">!</a></li></ol>"##,
+        ),
+        render_table_of_contents(&outline)
+    );
+    assert_eq!("", render_table_of_contents(&[]));
+
+    let renderer = HtmlRenderer::new(
+        input.as_bytes(),
+        NewHtmlRendererOptions {
+            tag_name_map: Default::default(),
+            extension_registry: Default::default(),
+            dicexp_result_provider: None,
+            ref_link_resolver: None,
+            ref_link_context: Default::default(),
+            wiki_link_resolver: None,
+            initial_output_string_capacity: 0,
+            should_include_heading_ids: true,
+            heading_level_adjustment: Default::default(),
+            render_mode: Default::default(),
+            #[cfg(feature = "block-id")]
+            should_include_block_ids: false,
+        },
+    );
+    assert_eq!(
+        r#"<h1 id="a">A</h1><h2 id="b">&lt;b></h2><h2 id="b-1">&lt;b></h2><h1 id="section">!</h1>"#,
+        renderer.render(events.into_iter())
+    );
+}
+
+#[test]
+fn it_links_table_of_contents_to_rendered_heading_ids() {
+    let input = indoc::indoc! {"
+        = A [{#外部链接?[`http://x`]}] B =
+        == [{#外部链接|http://y}] [{#Span|C}] ==
+        == [{#Foo?d|e}] [=d6] =="};
+    let events: Vec<_> = crate::parse(input.as_bytes())
+        .collect::<crate::Result<_>>()
+        .unwrap();
+
+    let outline = crate::outline::extract_outline(
+        input.as_bytes(),
+        &events,
+        &Default::default(),
+        Default::default(),
+    );
+    let table_of_contents = render_table_of_contents(&outline);
+    let hrefs: Vec<_> = table_of_contents
+        .split(r##"href="#"##)
+        .skip(1)
+        .map(|rest| &rest[..rest.find('"').unwrap()])
+        .collect();
+    assert_eq!(vec!["a-b", "c", "de"], hrefs);
+
+    let renderer = HtmlRenderer::new(
+        input.as_bytes(),
+        NewHtmlRendererOptions {
+            tag_name_map: Default::default(),
+            extension_registry: Default::default(),
+            dicexp_result_provider: None,
+            ref_link_resolver: None,
+            ref_link_context: Default::default(),
+            wiki_link_resolver: None,
+            initial_output_string_capacity: 0,
+            should_include_heading_ids: true,
+            heading_level_adjustment: Default::default(),
+            render_mode: Default::default(),
+            #[cfg(feature = "block-id")]
+            should_include_block_ids: false,
+        },
+    );
+    let html = renderer.render(events.into_iter());
+    for href in hrefs {
+        assert!(
+            html.contains(&format!(r#" id="{}">"#, href)),
+            "href: {:?}, html: {}",
+            href,
+            html
+        );
+    }
+}
+
+#[test]
+fn it_works_in_inline_phase() {
+    let table: Vec<GroupedCases<_>> = vec![
+        GroupedCases {
+            group: "引用链接",
+            cases: vec![
+                case!(
+                    "TP.abc",
+                    [(RefLink(0..6)),],
+                    r#"<x-ref-link address="TP.abc" prefix="TP" thread-id="abc"></x-ref-link>"#,
+                ),
+                case!(
+                    "TP.abc#123",
+                    [(RefLink(0..10)),],
+                    r#"<x-ref-link address="TP.abc#123" prefix="TP" thread-id="abc" floor="123"></x-ref-link>"#,
+                ),
+                case!(
+                    "TP.456",
+                    [(RefLink(0..6)),],
+                    r#"<x-ref-link address="TP.456" prefix="TP" post-number="456"></x-ref-link>"#,
+                ),
+                case!(
+                    "#123",
+                    [(RefLink(0..4)),],
+                    r##"<x-ref-link address="#123" floor="123"></x-ref-link>"##,
+                ),
+                case!(
+                    "#99999999999999999999",
+                    [(RefLink(0..21)),],
+                    r##"<x-ref-link address="#99999999999999999999"></x-ref-link>"##,
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "Dicexp",
+            cases: vec![
+                case!(
+                    "d100",
+                    [
+                        (EnterDicexp(..)),
+                        (Text(0..4)),
+                        (@inline ExitInline(..)),
+                    ],
+                    r#"<x-dicexp code="d100"></x-dicexp>"#,
+                ),
+                case!(
+                    "_balls d100",
+                    [
+                        (EnterDicexp(.., assigning_to = 0..6)),
+                        (Text(7..11)),
+                        (@inline ExitInline(..)),
+                    ],
+                    r#"<x-dicexp code="d100" assigning-to="_balls"></x-dicexp>"#,
+                ),
+                case!(
+                    "d100+\nd6",
+                    [
+                        (EnterDicexp(..)),
+                        (Text(0..5)),
+                        (NewLine(..)),
+                        (Text(6..8)),
+                        (@inline ExitInline(..)),
+                    ],
+                    r#"<x-dicexp code="d100+&#10;d6"></x-dicexp>"#,
+                ),
+                // 以下的事件不是由解析器产出的。
+                case!(
+                    "d100+d6",
+                    [
+                        (EnterDicexp(..)),
+                        (Text(0..5)),
+                        (VerbatimEscaping(5..7)),
+                        (@inline ExitInline(..)),
+                    ],
+                    r#"<x-dicexp code="d100+d6"></x-dicexp>"#,
+                ),
+                case!(
+                    "d100",
+                    [(EnterDicexp(..)), (Text(0..4)),],
+                    r#"<x-dicexp code="d100"></x-dicexp>"#,
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "Dicexp>求值结果",
+            cases: vec![
+                case!(
+                    @with_dicexp_results [DicexpResult::Value("42".to_string())],
+                    "d100",
+                    [
+                        (EnterDicexp(..)),
+                        (Text(0..4)),
+                        (@inline ExitInline(..)),
+                    ],
+                    r#"<x-dicexp code="d100" evaluation="{&quot;result&quot;:[&quot;value&quot;,42],&quot;location&quot;:&quot;server&quot;}"></x-dicexp>"#,
+                ),
+                case!(
+                    @with_dicexp_results [DicexpResult::Error {
+                        kind: "runtime".to_string(),
+                        message: "\"<a>\"\n".to_string(),
+                    }],
+                    "_x d0",
+                    [
+                        (EnterDicexp(.., assigning_to = 0..2)),
+                        (Text(3..5)),
+                        (@inline ExitInline(..)),
+                    ],
+                    r#"<x-dicexp code="d0" assigning-to="_x" evaluation="{&quot;result&quot;:[&quot;error&quot;,&quot;runtime&quot;,&quot;\&quot;<a>\&quot;\n&quot;],&quot;location&quot;:&quot;server&quot;}"></x-dicexp>"#,
+                ),
+                case!(
+                    @with_dicexp_results [],
+                    "d100",
+                    [
+                        (EnterDicexp(..)),
+                        (Text(0..4)),
+                        (@inline ExitInline(..)),
+                    ],
+                    r#"<x-dicexp code="d100"></x-dicexp>"#,
+                ),
+                case!(
+                    @with_dicexp_results [DicexpResult::Value("1".to_string())],
+                    "d1 d6",
+                    [
+                        (EnterDicexp(..)),
+                        (Text(0..2)),
+                        (@inline ExitInline(..)),
+                        (EnterDicexp(..)),
+                        (Text(3..5)),
+                        (@inline ExitInline(..)),
+                    ],
+                    r#"<x-dicexp code="d1" evaluation="{&quot;result&quot;:[&quot;value&quot;,1],&quot;location&quot;:&quot;server&quot;}"></x-dicexp><x-dicexp code="d6"></x-dicexp>"#,
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "加粗强调与删除线",
+            cases: vec![
+                case!(
+                    "foo",
+                    [
+                        (@inline EnterStrong(..)),
+                        (Text(0..3)),
+                        (@inline ExitInline(..)),
+                    ],
+                    r#"<strong>foo</strong>"#,
+                ),
+                case!(
+                    "foo",
+                    [
+                        (@inline EnterStrikethrough(..)),
+                        (Text(0..3)),
+                        (@inline ExitInline(..)),
+                    ],
+                    r#"<s>foo</s>"#,
+                ),
+                case!(
+                    "foo:0/bar:6/baz:12",
+                    [
+                        (@inline EnterStrong(..)),
+                        (@inline EnterStrikethrough(..)),
+                        (Text(0..3)),
+                        (@inline ExitInline(..)),
+                        (@inline EnterStrong(..)),
+                        (Text(6..9)),
+                        (@inline ExitInline(..)),
+                        (Text(12..15)),
+                        (@inline ExitInline(..)),
+                    ],
+                    r#"<strong><s>foo</s><strong>bar</strong>baz</strong>"#,
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "XSS",
+            cases: vec![case!(
+                r#"""#,
+                [
+                    (EnterDicexp(..)),
+                    (Text(0..1)),
+                    (@inline ExitInline(..)),
+                ],
+                r#"<x-dicexp code="&quot;"></x-dicexp>"#,
+            )],
+        },
+    ];
+
+    run_cases(table);
+}
+
+#[test]
+fn it_works_with_extension_registry() {
+    let table: Vec<GroupedCases<_>> = vec![
+        GroupedCases {
+            group: "内置扩展的别名",
+            cases: vec![
+                case!(
+                    "NOTE",
+                    [(EnterBlockExtension(0..4)), (ExitBlock(..)),],
+                    "<x-callout-note></x-callout-note>",
+                ),
+                case!(
+                    "collapse",
+                    [(EnterBlockExtension(0..8)), (ExitBlock(..)),],
+                    "<x-collapse></x-collapse>",
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "自定义扩展",
+            cases: vec![
+                case!(
+                    @with_extension_registry new_registry_with_test_handlers(),
+                    "tabs:0/默认:7/1:16/a:21",
+                    [
+                        (EnterBlockExtension(0..4)),
+                        (IndicateExtensionParameter(7..13)),
+                        (Text(16..17)),
+                        (IndicateExtensionParameter(0..4)),
+                        (Text(16..17)),
+                        (IndicateBlockExtensionSlot()),
+                        (EnterParagraph(..)),
+                        (Text(21..22)),
+                        (ExitBlock(..)),
+                        (IndicateBlockExtensionSlot()),
+                        (IndicateBlockExtensionSlot()),
+                        (EnterParagraph(..)),
+                        (Text(21..22)),
+                        (ExitBlock(..)),
+                        (ExitBlock(..)),
+                    ],
+                    concat!(
+                        r#"<x-tabs default="1">"#,
+                        r#"<div slot="tab"><p>a</p></div>"#,
+                        r#"<div slot="tab"></div>"#,
+                        "</x-tabs>",
+                    ),
+                ),
+                case!(
+                    @with_extension_registry new_registry_with_test_handlers(),
+                    "标签页",
+                    [
+                        (EnterBlockExtension(0..9)),
+                        (ExitBlock(..)),
+                    ],
+                    "<x-tabs></x-tabs>",
+                ),
+                case!(
+                    @with_extension_registry new_registry_with_test_handlers(),
+                    "KBD:0/Ctrl+:6/C:14",
                     [
                         (EnterParagraph(..)),
-                        (EnterWikiLink(0..4)),
-                        (Text(7..12)),
+                        (EnterInlineExtension(0..3)),
+                        (IndicateInlineExtensionSlot()),
+                        (@inline EnterStrong(..)),
+                        (Text(6..11)),
+                        (@inline ExitInline(..)),
+                        (Text(14..15)),
                         (@inline ExitInline(..)),
                         (ExitBlock(..)),
                     ],
-                    r#"<p><x-wiki-link address="ADDR"><span slot="content">title</span></x-wiki-link></p>"#,
+                    "<p><kbd>Ctrl+C</kbd></p>",
                 ),
-            ]
-        },
-        GroupedCases {
-            group: "XSS",
-            cases: vec![
                 case!(
-                    r#"<script>"#,
+                    @with_extension_registry new_registry_with_test_handlers(),
+                    "注:0/x:6",
                     [
+                        (EnterBlockExtension(0..3)),
+                        (IndicateBlockExtensionSlot()),
                         (EnterParagraph(..)),
-                        (Text(0..8)),
+                        (Text(6..7)),
+                        (ExitBlock(..)),
                         (ExitBlock(..)),
                     ],
-                    r#"<p>&lt;script></p>"#,
+                    "<aside><p>x</p></aside>",
                 ),
                 case!(
-                    r#"">"#,
+                    @with_extension_registry ExtensionRegistry::new(),
+                    "注:0/x:6",
                     [
-                        (EnterCodeBlock(..)),
-                        (Text(0..2)),
-                        (IndicateCodeBlockCode()),
-                        (Text(0..2)),
+                        (EnterBlockExtension(0..3)),
+                        (IndicateBlockExtensionSlot()),
+                        (EnterParagraph(..)),
+                        (Text(6..7)),
+                        (ExitBlock(..)),
                         (ExitBlock(..)),
                     ],
-                    r#"<x-code-block info-string="&quot;>" content="&quot;>"></x-code-block>"#,
+                    concat!(
+                        r#"<x-block-extension name="注">"#,
+                        r#"<div slot="content"><p>x</p></div>"#,
+                        "</x-block-extension>",
+                    ),
                 ),
             ],
         },
@@ -459,49 +1583,43 @@ fn it_works_in_block_phase() {
     run_cases(table);
 }
 
-#[cfg(feature = "block-id")]
 #[test]
-fn it_works_with_block_id() {
+fn it_works_with_ref_link_resolver() {
+    let in_thread = || RefLinkContext {
+        current_thread_id: Some("abc"),
+    };
     let table: Vec<GroupedCases<_>> = vec![GroupedCases {
-        group: "基础",
+        group: "引用链接解析",
         cases: vec![
             case!(
-                @with_id,
-                "",
-                [(ThematicBreak(.., id = 1)),],
-                r#"<hr data-block-id="1">"#,
+                @with_ref_link_resolver &ForumRefLinkResolver, in_thread(),
+                "#123",
+                [(RefLink(0..4)),],
+                r##"<x-ref-link address="#123" floor="123" href="/t/abc#123"></x-ref-link>"##,
             ),
             case!(
-                @with_id,
-                "foo",
-                [
-                    (EnterParagraph(.., id = 1)),
-                    (Text(0..3)),
-                    (ExitBlock(.., id = 1)),
-                ],
-                r#"<p data-block-id="1">foo</p>"#,
+                @with_ref_link_resolver &ForumRefLinkResolver, Default::default(),
+                "#123",
+                [(RefLink(0..4)),],
+                r##"<x-ref-link address="#123" floor="123"></x-ref-link>"##,
             ),
             case!(
-                @with_id,
-                "foo:0/bar:6",
-                [
-                    (EnterTable(.., id = 1)),
-                    (IndicateTableHeaderCell()),
-                    (EnterParagraph(.., id = 2)),
-                    (Text(0..3)),
-                    (ExitBlock(.., id = 2)),
-                    (IndicateTableRow()),
-                    (EnterParagraph(.., id = 3)),
-                    (Text(6..9)),
-                    (ExitBlock(.., id = 3)),
-                    (ExitBlock(.., id = 1)),
-                ],
-                concat!(
-                    r#"<table data-block-id="1">"#,
-                    r#"<tr><th><p data-block-id="2">foo</p></th></tr>"#,
-                    r#"<tr><td><p data-block-id="3">bar</p></td></tr>"#,
-                    "</table>"
-                ),
+                @with_ref_link_resolver &ForumRefLinkResolver, in_thread(),
+                "TP.def#1",
+                [(RefLink(0..8)),],
+                r##"<x-ref-link address="TP.def#1" prefix="TP" thread-id="def" floor="1" href="/t/def#1"></x-ref-link>"##,
+            ),
+            case!(
+                @with_ref_link_resolver &ForumRefLinkResolver, in_thread(),
+                "TP.456",
+                [(RefLink(0..6)),],
+                r#"<x-ref-link address="TP.456" prefix="TP" post-number="456" href="/p/456">&lt;No.456></x-ref-link>"#,
+            ),
+            case!(
+                @with_ref_link_resolver &ForumRefLinkResolver, in_thread(),
+                "TP.789",
+                [(RefLink(0..6)),],
+                r#"<x-ref-link address="TP.789" prefix="TP" post-number="789" dangling></x-ref-link>"#,
             ),
         ],
     }];
@@ -510,71 +1628,457 @@ fn it_works_with_block_id() {
 }
 
 #[test]
-fn it_works_in_inline_phase() {
+fn it_works_with_wiki_link_resolver() {
+    let index = Box::leak(Box::new(new_wiki_page_index()));
+    let table: Vec<GroupedCases<_>> = vec![GroupedCases {
+        group: "Wiki链接解析",
+        cases: vec![
+            case!(
+                @with_wiki_link_resolver index,
+                " s: 通用概念 #元素",
+                [(EnterWikiLink(0..24)), (@inline ExitInline(..)),],
+                r#"<x-wiki-link address=" s: 通用概念 #元素" href="/wiki/语法参考/通用概念#元素"><span slot="content"></span></x-wiki-link>"#,
+            ),
+            case!(
+                @with_wiki_link_resolver index,
+                "rotext",
+                [(EnterWikiLink(0..6)), (@inline ExitInline(..)),],
+                r#"<x-wiki-link address="rotext" href="/wiki/main/Rotext"><span slot="content"></span></x-wiki-link>"#,
+            ),
+            case!(
+                @with_wiki_link_resolver index,
+                "#元素",
+                [(EnterWikiLink(0..7)), (@inline ExitInline(..)),],
+                r##"<x-wiki-link address="#元素" broken-anchor><span slot="content"></span></x-wiki-link>"##,
+            ),
+            case!(
+                @with_wiki_link_resolver index,
+                "s:不存在",
+                [(EnterWikiLink(0..11)), (@inline ExitInline(..)),],
+                r#"<x-wiki-link address="s:不存在" missing><span slot="content"></span></x-wiki-link>"#,
+            ),
+            case!(
+                @with_wiki_link_resolver index,
+                "x:通用概念",
+                [(EnterWikiLink(0..14)), (@inline ExitInline(..)),],
+                r#"<x-wiki-link address="x:通用概念" missing><span slot="content"></span></x-wiki-link>"#,
+            ),
+        ],
+    }];
+
+    run_cases(table);
+}
+
+#[test]
+fn it_works_in_preview_mode() {
     let table: Vec<GroupedCases<_>> = vec![
         GroupedCases {
-            group: "引用链接",
-            cases: vec![case!(
-                "TP.abc",
-                [(RefLink(0..6)),],
-                r#"<x-ref-link address="TP.abc"></x-ref-link>"#,
-            )],
-        },
-        GroupedCases {
-            group: "Dicexp",
-            cases: vec![case!(
-                "d100",
-                [(Dicexp(0..4)),],
-                r#"<x-dicexp code="d100"></x-dicexp>"#,
-            )],
-        },
-        GroupedCases {
-            group: "加粗强调与删除线",
+            group: "位于块级槽位末尾的标记",
             cases: vec![
                 case!(
-                    "foo",
+                    @in_preview_mode,
+                    "a\\",
+                    [(EnterParagraph(..)), (Text(0..2)), (ExitBlock(..)),],
+                    r#"<p>a<x-preview-warn reason="dangling-hard-break-mark">\</x-preview-warn></p>"#,
+                ),
+                case!(
+                    @in_preview_mode,
+                    "['a_",
                     [
+                        (EnterParagraph(..)),
                         (@inline EnterStrong(..)),
-                        (Text(0..3)),
+                        (Text(2..4)),
                         (@inline ExitInline(..)),
+                        (ExitBlock(..)),
                     ],
-                    r#"<strong>foo</strong>"#,
+                    r#"<p><strong>a<x-preview-warn reason="dangling-lines-joint-mark">_</x-preview-warn></strong></p>"#,
                 ),
                 case!(
-                    "foo",
+                    @in_preview_mode,
+                    "['a\\']",
                     [
-                        (@inline EnterStrikethrough(..)),
-                        (Text(0..3)),
+                        (EnterParagraph(..)),
+                        (@inline EnterStrong(..)),
+                        (Text(2..4)),
                         (@inline ExitInline(..)),
+                        (ExitBlock(..)),
                     ],
-                    r#"<s>foo</s>"#,
+                    r#"<p><strong>a\</strong></p>"#,
                 ),
                 case!(
-                    "foo:0/bar:6/baz:12",
+                    @in_preview_mode,
+                    "[[a\\]]",
                     [
-                        (@inline EnterStrong(..)),
+                        (EnterParagraph(..)),
+                        (EnterWikiLink(2..4)),
+                        (Text(2..4)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><x-wiki-link address="a\"><span slot="content">a\</span></x-wiki-link></p>"#,
+                ),
+                case!(
+                    @in_preview_mode,
+                    "[~a_~]",
+                    [
+                        (EnterParagraph(..)),
                         (@inline EnterStrikethrough(..)),
-                        (Text(0..3)),
+                        (Text(2..4)),
                         (@inline ExitInline(..)),
-                        (@inline EnterStrong(..)),
-                        (Text(6..9)),
+                        (ExitBlock(..)),
+                    ],
+                    "<p><s>a_</s></p>",
+                ),
+                case!(
+                    @in_preview_mode,
+                    "[{#Foo|a_}]",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterInlineExtension(3..6)),
+                        (IndicateInlineExtensionSlot()),
+                        (Text(7..9)),
                         (@inline ExitInline(..)),
-                        (Text(12..15)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><x-preview-warn reason="unknown-extension"><x-inline-extension name="Foo"><span slot="content">a_</span></x-inline-extension></x-preview-warn></p>"#,
+                ),
+                case!(
+                    @in_preview_mode,
+                    "a_<`b`>",
+                    [
+                        (EnterParagraph(..)),
+                        (Text(0..2)),
+                        (VerbatimEscaping(4..5)),
+                        (ExitBlock(..)),
+                    ],
+                    "<p>a_b</p>",
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "被强制闭合的逐字转义与行内代码",
+            cases: vec![
+                case!(
+                    @in_preview_mode,
+                    "<`a",
+                    [
+                        (EnterParagraph(..)),
+                        (VerbatimEscaping(2..3, closed_forcedly)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><x-preview-warn reason="unclosed-verbatim-escaping">a</x-preview-warn></p>"#,
+                ),
+                case!(
+                    @in_preview_mode,
+                    "[`a\\",
+                    [
+                        (EnterParagraph(..)),
+                        (@inline EnterCodeSpan(..)),
+                        (Text(2..4)),
                         (@inline ExitInline(..)),
+                        (ExitBlock(..)),
                     ],
-                    r#"<strong><s>foo</s><strong>bar</strong>baz</strong>"#,
+                    r#"<p><x-preview-warn reason="unclosed-code-span"><code>a\</code></x-preview-warn></p>"#,
+                ),
+                case!(
+                    @in_preview_mode,
+                    "[`a `]",
+                    [
+                        (EnterParagraph(..)),
+                        (@inline EnterCodeSpan(..)),
+                        (Text(2..3)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    "<p><code>a</code></p>",
+                ),
+                case!(
+                    @in_preview_mode,
+                    "[``<` a `>``]",
+                    [
+                        (EnterParagraph(..)),
+                        (@inline EnterCodeSpan(..)),
+                        (VerbatimEscaping(6..7)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    "<p><code>a</code></p>",
+                ),
+                case!(
+                    @in_preview_mode,
+                    "[`a\n`]",
+                    [
+                        (EnterParagraph(..)),
+                        (@inline EnterCodeSpan(..)),
+                        (Text(2..3)),
+                        (NewLine(..)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    "<p><code>a<br></code></p>",
                 ),
             ],
         },
         GroupedCases {
-            group: "XSS",
-            cases: vec![case!(
-                r#"""#,
-                [(Dicexp(0..1)),],
-                r#"<x-dicexp code="&quot;"></x-dicexp>"#,
-            )],
+            group: "未知的扩展",
+            cases: vec![
+                case!(
+                    @in_preview_mode,
+                    "foo",
+                    [(EnterBlockExtension(0..3)), (ExitBlock(..)),],
+                    r#"<x-preview-warn reason="unknown-extension"><x-block-extension name="foo"></x-block-extension></x-preview-warn>"#,
+                ),
+                case!(
+                    @in_preview_mode,
+                    "foo",
+                    [
+                        (EnterParagraph(..)),
+                        (EnterInlineExtension(0..3)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    r#"<p><x-preview-warn reason="unknown-extension"><x-inline-extension name="foo"></x-inline-extension></x-preview-warn></p>"#,
+                ),
+                case!(
+                    @in_preview_mode,
+                    "NOTE",
+                    [(EnterBlockExtension(0..4)), (ExitBlock(..)),],
+                    "<x-callout-note></x-callout-note>",
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "发布模式",
+            cases: vec![
+                case!(
+                    "a\\",
+                    [(EnterParagraph(..)), (Text(0..2)), (ExitBlock(..)),],
+                    r#"<p>a\</p>"#,
+                ),
+                case!(
+                    "[`a",
+                    [
+                        (EnterParagraph(..)),
+                        (@inline EnterCodeSpan(..)),
+                        (Text(2..3)),
+                        (@inline ExitInline(..)),
+                        (ExitBlock(..)),
+                    ],
+                    "<p><code>a</code></p>",
+                ),
+                case!(
+                    "foo",
+                    [(EnterBlockExtension(0..3)), (ExitBlock(..)),],
+                    r#"<x-block-extension name="foo"></x-block-extension>"#,
+                ),
+            ],
         },
     ];
 
     run_cases(table);
 }
+
+#[test]
+fn it_resolves_wiki_links_with_page_index() {
+    let index = new_wiki_page_index();
+    let table: &[(&str, Option<WikiLinkResolution>)] = &[
+        (
+            "Syntax:通用概念#元素",
+            Some(WikiLinkResolution::Resolved {
+                href: "/wiki/语法参考/通用概念#元素".to_string(),
+            }),
+        ),
+        (
+            "s:通用概念#Foo Bar",
+            Some(WikiLinkResolution::Resolved {
+                href: "/wiki/语法参考/通用概念#foo-bar".to_string(),
+            }),
+        ),
+        ("main:不存在", Some(WikiLinkResolution::Missing)),
+        (
+            "# Foo  Bar!",
+            Some(WikiLinkResolution::Resolved {
+                href: "#foo-bar".to_string(),
+            }),
+        ),
+    ];
+
+    for (address, expected) in table {
+        assert_eq!(
+            *expected,
+            index.resolve(&WikiLinkAddress::parse(address)),
+            "address: {:?}",
+            address
+        );
+    }
+}
+
+#[test]
+fn it_adds_pages_through_namespace_aliases() {
+    let mut index = WikiPageIndex::new("Main", |namespace, page_name, _| {
+        format!("/wiki/{}/{}", namespace, page_name)
+    });
+    index
+        .add_namespace("Special", &["s"])
+        .add_page("s", "甲")
+        .add_page("SPECIAL", "乙")
+        .add_page("main", "Home");
+
+    let table: &[(&str, &str)] = &[
+        ("s:甲", "/wiki/Special/甲"),
+        ("Special:甲", "/wiki/Special/甲"),
+        ("special:乙", "/wiki/Special/乙"),
+        ("Home", "/wiki/Main/Home"),
+        ("MAIN:home", "/wiki/Main/Home"),
+    ];
+
+    for (address, expected) in table {
+        assert_eq!(
+            Some(WikiLinkResolution::Resolved {
+                href: expected.to_string()
+            }),
+            index.resolve(&WikiLinkAddress::parse(address)),
+            "address: {:?}",
+            address
+        );
+    }
+}
+
+fn new_wiki_page_index() -> WikiPageIndex<'static> {
+    let mut index = WikiPageIndex::new("main", |namespace, page_name, anchor| {
+        let mut href = format!("/wiki/{}/{}", namespace, page_name);
+        if let Some(anchor) = anchor {
+            href.push('#');
+            href.push_str(anchor);
+        }
+        href
+    });
+    index
+        .add_namespace("语法参考", &["s", "Syntax"])
+        .add_page("语法参考", "通用概念")
+        .add_page("main", "Rotext");
+    index
+}
+
+/// 只有 456 号帖子存在的论坛。
+struct ForumRefLinkResolver;
+impl RefLinkResolver for ForumRefLinkResolver {
+    fn resolve(
+        &self,
+        target: &RefLinkTarget,
+        context: &RefLinkContext,
+    ) -> Option<RefLinkResolution> {
+        let resolution = match *target {
+            RefLinkTarget::Floor(floor) => RefLinkResolution::Resolved {
+                href: format!("/t/{}#{}", context.current_thread_id?, floor),
+                label: None,
+            },
+            RefLinkTarget::Thread {
+                thread_id, floor, ..
+            } => RefLinkResolution::Resolved {
+                href: match floor {
+                    Some(floor) => format!("/t/{}#{}", thread_id, floor),
+                    None => format!("/t/{}", thread_id),
+                },
+                label: None,
+            },
+            RefLinkTarget::Post {
+                post_number: 456, ..
+            } => RefLinkResolution::Resolved {
+                href: "/p/456".to_string(),
+                label: Some("<No.456>".to_string()),
+            },
+            RefLinkTarget::Post { .. } => RefLinkResolution::Dangling,
+        };
+
+        Some(resolution)
+    }
+}
+
+fn new_registry_with_test_handlers() -> ExtensionRegistry<'static> {
+    let mut registry = ExtensionRegistry::with_built_ins();
+    registry.register(&Tabs).register(&Kbd).register(&Aside);
+    registry
+}
+
+struct Tabs;
+impl ExtensionHandler for Tabs {
+    fn spec(&self) -> ExtensionSpec<'_> {
+        ExtensionSpec {
+            kind: ExtensionKind::Block,
+            names: &["标签页", "Tabs"],
+            named_parameters: Some(&["默认"]),
+            max_unnamed_parameters: Some(0),
+            max_slots: Some(2),
+            slot_mode: SlotMode::Rendered,
+        }
+    }
+
+    fn render_opening(&self, w: &mut HtmlWriter, ctx: &ExtensionContext) {
+        w.write_opening_tag_start(b"x-tabs");
+        if let Some(value) = ctx.parameter("默认") {
+            w.write_attribute(b"default", &value.to_vec());
+        }
+        w.write_opening_tag_end();
+    }
+
+    fn render_slot_opening(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext, _index: usize) {
+        w.write_opening_tag_start(b"div");
+        w.write_attribute(b"slot", b"tab");
+        w.write_opening_tag_end();
+    }
+
+    fn render_slot_closing(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext, _index: usize) {
+        w.write_closing_tag(b"div");
+    }
+
+    fn render_closing(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext) {
+        w.write_closing_tag(b"x-tabs");
+    }
+}
+
+struct Kbd;
+impl ExtensionHandler for Kbd {
+    fn spec(&self) -> ExtensionSpec<'_> {
+        ExtensionSpec {
+            kind: ExtensionKind::Inline,
+            names: &["Kbd"],
+            named_parameters: None,
+            max_unnamed_parameters: None,
+            max_slots: Some(1),
+            slot_mode: SlotMode::Text,
+        }
+    }
+
+    fn render_opening(&self, w: &mut HtmlWriter, ctx: &ExtensionContext) {
+        w.write_opening_tag(b"kbd");
+        w.write_escaped_text(ctx.slot_text(0).unwrap_or_default());
+    }
+
+    fn render_closing(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext) {
+        w.write_closing_tag(b"kbd");
+    }
+}
+
+/// 覆盖内置的 “注”。
+struct Aside;
+impl ExtensionHandler for Aside {
+    fn spec(&self) -> ExtensionSpec<'_> {
+        ExtensionSpec {
+            kind: ExtensionKind::Block,
+            names: &["注"],
+            named_parameters: None,
+            max_unnamed_parameters: None,
+            max_slots: None,
+            slot_mode: SlotMode::Rendered,
+        }
+    }
+
+    fn render_opening(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext) {
+        w.write_opening_tag(b"aside");
+    }
+
+    fn render_closing(&self, w: &mut HtmlWriter, _ctx: &ExtensionContext) {
+        w.write_closing_tag(b"aside");
+    }
+}
@@ -1,4 +1,42 @@
 macro_rules! case {
+    (@with_extension_registry $registry:expr, $input:literal, [$($ev_tts:tt)*], $expected:expr,) => {{
+        let mut case = case!($input, [$($ev_tts)*], $expected,);
+        case.options.extension_registry = std::panic::AssertUnwindSafe($registry);
+        case
+    }};
+    (@with_dicexp_results [$($result:expr),*$(,)?], $input:literal, [$($ev_tts:tt)*], $expected:expr,) => {{
+        let mut case = case!($input, [$($ev_tts)*], $expected,);
+        let results: Vec<$crate::rendering::DicexpResult> = vec![$($result),*];
+        case.options.dicexp_result_provider =
+            std::panic::AssertUnwindSafe(Some(Box::leak(Box::new(results))));
+        case
+    }};
+    (@with_ref_link_resolver $resolver:expr, $context:expr, $input:literal, [$($ev_tts:tt)*], $expected:expr,) => {{
+        let mut case = case!($input, [$($ev_tts)*], $expected,);
+        case.options.ref_link_resolver = std::panic::AssertUnwindSafe(Some($resolver));
+        case.options.ref_link_context = $context;
+        case
+    }};
+    (@with_wiki_link_resolver $resolver:expr, $input:literal, [$($ev_tts:tt)*], $expected:expr,) => {{
+        let mut case = case!($input, [$($ev_tts)*], $expected,);
+        case.options.wiki_link_resolver = std::panic::AssertUnwindSafe(Some($resolver));
+        case
+    }};
+    (@with_heading_ids, $input:literal, [$($ev_tts:tt)*], $expected:expr,) => {{
+        let mut case = case!($input, [$($ev_tts)*], $expected,);
+        case.options.should_include_heading_ids = true;
+        case
+    }};
+    (@with_heading_level_offset $offset:literal, $input:literal, [$($ev_tts:tt)*], $expected:expr,) => {{
+        let mut case = case!($input, [$($ev_tts)*], $expected,);
+        case.options.heading_level_adjustment.offset = $offset;
+        case
+    }};
+    (@in_preview_mode, $input:literal, [$($ev_tts:tt)*], $expected:expr,) => {{
+        let mut case = case!($input, [$($ev_tts)*], $expected,);
+        case.options.render_mode = $crate::rendering::RenderMode::Preview;
+        case
+    }};
     (@with_id, $input:literal, [$($ev_tts:tt)*], $expected:expr,) => {
         case!(@__inner, $input, [$($ev_tts)*], $expected, {
             should_include_block_id: true,
@@ -19,6 +57,14 @@ macro_rules! case {
 
             options: $crate::rendering::tests::support::CaseOptions {
                 tag_name_map: Default::default(),
+                extension_registry: Default::default(),
+                dicexp_result_provider: std::panic::AssertUnwindSafe(None),
+                ref_link_resolver: std::panic::AssertUnwindSafe(None),
+                ref_link_context: Default::default(),
+                wiki_link_resolver: std::panic::AssertUnwindSafe(None),
+                should_include_heading_ids: false,
+                heading_level_adjustment: Default::default(),
+                render_mode: Default::default(),
                 #[cfg(feature = "block-id")]
                 should_include_block_id: $should_include_block_id,
             },
@@ -34,6 +80,13 @@ macro_rules! __event {
             line_after: $crate::types::LineNumber::new_invalid(),
         })
     };
+    (VerbatimEscaping ($start:literal..$end:literal, closed_forcedly)) => {
+        $crate::events::Event::VerbatimEscaping($crate::events::VerbatimEscaping {
+            content: $start..$end,
+            is_closed_forcedly: true,
+            line_after: $crate::types::LineNumber::new_invalid(),
+        })
+    };
 
     (NewLine (..)) => {
         $crate::events::Event::NewLine($crate::events::NewLine {
@@ -46,19 +99,85 @@ macro_rules! __event {
     };
 
     (IndicateCodeBlockCode ()) => {
-        $crate::events::Event::IndicateCodeBlockCode
+        $crate::events::Event::IndicateCodeBlockCode($crate::events::Indication {
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
     };
     (IndicateTableCaption ()) => {
-        $crate::events::Event::IndicateTableCaption
+        $crate::events::Event::IndicateTableCaption($crate::events::Indication {
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
     };
     (IndicateTableRow ()) => {
-        $crate::events::Event::IndicateTableRow
+        $crate::events::Event::IndicateTableRow($crate::events::Indication {
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
     };
     (IndicateTableHeaderCell ()) => {
-        $crate::events::Event::IndicateTableHeaderCell
+        $crate::events::Event::IndicateTableHeaderCell($crate::events::TableCell {
+            attributes: None,
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (IndicateTableHeaderCell (attributes = $start:literal..$end:literal)) => {
+        $crate::events::Event::IndicateTableHeaderCell($crate::events::TableCell {
+            attributes: Some($start..$end),
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
     };
     (IndicateTableDataCell ()) => {
-        $crate::events::Event::IndicateTableDataCell
+        $crate::events::Event::IndicateTableDataCell($crate::events::TableCell {
+            attributes: None,
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (IndicateTableDataCell (attributes = $start:literal..$end:literal)) => {
+        $crate::events::Event::IndicateTableDataCell($crate::events::TableCell {
+            attributes: Some($start..$end),
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (IndicateBlockExtensionSlot ()) => {
+        $crate::events::Event::IndicateBlockExtensionSlot($crate::events::Indication {
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (IndicateExtensionParameter ()) => {
+        $crate::events::Event::IndicateExtensionParameter($crate::events::ExtensionParameter {
+            name: None,
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (IndicateExtensionParameter ($start:literal..$end:literal)) => {
+        $crate::events::Event::IndicateExtensionParameter($crate::events::ExtensionParameter {
+            name: Some($start..$end),
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+
+    (EnterBlockExtension ($start:literal..$end:literal)) => {
+        $crate::events::Event::EnterBlockExtension($crate::events::BlockExtension {
+            id: $crate::types::BlockId::new_invalid(),
+            name: $start..$end,
+            attributes: None,
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (EnterBlockExtension ($start:literal..$end:literal, attributes = $a_start:literal..$a_end:literal)) => {
+        $crate::events::Event::EnterBlockExtension($crate::events::BlockExtension {
+            id: $crate::types::BlockId::new_invalid(),
+            name: $start..$end,
+            attributes: Some($a_start..$a_end),
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (EnterBlockExtension ($start:literal..$end:literal, id = $id:literal)) => {
+        $crate::events::Event::EnterBlockExtension($crate::events::BlockExtension {
+            id: $crate::types::BlockId::new($id),
+            name: $start..$end,
+            attributes: None,
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
     };
 
     (ExitBlock (..)) => {
@@ -66,6 +185,7 @@ macro_rules! __event {
             id: $crate::types::BlockId::new_invalid(),
             start_line: $crate::types::LineNumber::new_invalid(),
             end_line: $crate::types::LineNumber::new_invalid(),
+            span: $crate::types::SourceSpan::new_invalid(),
         })
     };
     (ExitBlock (.., id = $id:literal)) => {
@@ -73,6 +193,7 @@ macro_rules! __event {
             id: $crate::types::BlockId::new($id),
             start_line: $crate::types::LineNumber::new_invalid(),
             end_line: $crate::types::LineNumber::new_invalid(),
+            span: $crate::types::SourceSpan::new_invalid(),
         })
     };
     (ExitBlock (.., id = $id:literal, lns = $ln_s:literal..=$ln_e:literal)) => {
@@ -80,6 +201,7 @@ macro_rules! __event {
             id: $crate::types::BlockId::new($id),
             start_line: $crate::types::LineNumber::new($ln_s),
             end_line: $crate::types::LineNumber::new($ln_e),
+            span: $crate::types::SourceSpan::new_invalid(),
         })
     };
 
@@ -87,44 +209,122 @@ macro_rules! __event {
         $crate::events::Event::ThematicBreak($crate::events::ThematicBreak {
             id: $crate::types::BlockId::new_invalid(),
             line: $crate::types::LineNumber::new_invalid(),
+            attributes: None,
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (ThematicBreak (.., attributes = $start:literal..$end:literal)) => {
+        $crate::events::Event::ThematicBreak($crate::events::ThematicBreak {
+            id: $crate::types::BlockId::new_invalid(),
+            line: $crate::types::LineNumber::new_invalid(),
+            attributes: Some($start..$end),
+            span: $crate::types::SourceSpan::new_invalid(),
         })
     };
     (ThematicBreak (.., id = $id:literal)) => {
         $crate::events::Event::ThematicBreak($crate::events::ThematicBreak {
             id: $crate::types::BlockId::new($id),
             line: $crate::types::LineNumber::new_invalid(),
+            attributes: None,
+            span: $crate::types::SourceSpan::new_invalid(),
         })
     };
     (ThematicBreak (.., id = $id:literal, ln = $ln:literal)) => {
         $crate::events::Event::ThematicBreak($crate::events::ThematicBreak {
             id: $crate::types::BlockId::new($id),
             line: $crate::types::LineNumber::new($ln),
+            attributes: None,
+            span: $crate::types::SourceSpan::new_invalid(),
         })
     };
 
     (RefLink ($start:literal..$end:literal)) => {
         $crate::events::Event::RefLink($start..$end)
     };
-    (Dicexp ($start:literal..$end:literal)) => {
-        $crate::events::Event::Dicexp($start..$end)
+    (EnterDicexp (..)) => {
+        $crate::events::Event::EnterDicexp($crate::events::Dicexp {
+            assigning_to: None,
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (EnterDicexp (.., assigning_to = $a_start:literal..$a_end:literal)) => {
+        $crate::events::Event::EnterDicexp($crate::events::Dicexp {
+            assigning_to: Some($a_start..$a_end),
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
     };
 
     (EnterWikiLink ($start:literal..$end:literal)) => {
-        $crate::events::Event::EnterWikiLink($start..$end)
+        $crate::events::Event::EnterWikiLink($crate::events::WikiLink {
+            address: $start..$end,
+            attributes: None,
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (EnterWikiLink ($start:literal..$end:literal, attributes = $a_start:literal..$a_end:literal)) => {
+        $crate::events::Event::EnterWikiLink($crate::events::WikiLink {
+            address: $start..$end,
+            attributes: Some($a_start..$a_end),
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (EnterInlineExtension ($start:literal..$end:literal)) => {
+        $crate::events::Event::EnterInlineExtension($crate::events::InlineExtension {
+            name: $start..$end,
+            attributes: None,
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (EnterInlineExtension ($start:literal..$end:literal, attributes = $a_start:literal..$a_end:literal)) => {
+        $crate::events::Event::EnterInlineExtension($crate::events::InlineExtension {
+            name: $start..$end,
+            attributes: Some($a_start..$a_end),
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (IndicateInlineExtensionSlot ()) => {
+        $crate::events::Event::IndicateInlineExtensionSlot($crate::events::Indication {
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
     };
 
+    (@inline ExitInline (..)) => {
+        $crate::events::Event::ExitInline($crate::events::ExitInline {
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
     (@inline $v:tt (..)) => {
-        $crate::events::Event::$v
+        $crate::events::Event::$v($crate::events::InlineElement {
+            attributes: None,
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    (@inline $v:tt (.., attributes = $start:literal..$end:literal)) => {
+        $crate::events::Event::$v($crate::events::InlineElement {
+            attributes: Some($start..$end),
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
     };
 
     ($v:tt (..)) => {
         $crate::events::Event::$v($crate::events::BlockWithId {
             id: $crate::types::BlockId::new_invalid(),
+            attributes: None,
+            span: $crate::types::SourceSpan::new_invalid(),
         })
     };
     ($v:tt (.., id = $id:literal)) => {
         $crate::events::Event::$v($crate::events::BlockWithId {
             id: $crate::types::BlockId::new($id),
+            attributes: None,
+            span: $crate::types::SourceSpan::new_invalid(),
+        })
+    };
+    ($v:tt (.., attributes = $start:literal..$end:literal)) => {
+        $crate::events::Event::$v($crate::events::BlockWithId {
+            id: $crate::types::BlockId::new_invalid(),
+            attributes: Some($start..$end),
+            span: $crate::types::SourceSpan::new_invalid(),
         })
     };
 }
@@ -140,11 +340,22 @@ pub(super) use __event;
 pub(super) use case;
 pub(super) use events;
 
+use std::panic::AssertUnwindSafe;
+
 use super::*;
 
 #[allow(dead_code)]
 pub(super) struct CaseOptions<'a> {
     pub tag_name_map: TagNameMap<'a>,
+    /// 处理器未必是 `RefUnwindSafe` 的，但测试中的处理器不持有可变状态。
+    pub extension_registry: AssertUnwindSafe<ExtensionRegistry<'a>>,
+    pub dicexp_result_provider: AssertUnwindSafe<Option<&'a dyn DicexpResultProvider>>,
+    pub ref_link_resolver: AssertUnwindSafe<Option<&'a dyn RefLinkResolver>>,
+    pub ref_link_context: RefLinkContext<'a>,
+    pub wiki_link_resolver: AssertUnwindSafe<Option<&'a dyn WikiLinkResolver>>,
+    pub should_include_heading_ids: bool,
+    pub heading_level_adjustment: crate::outline::HeadingLevelAdjustment,
+    pub render_mode: RenderMode,
     #[cfg(feature = "block-id")]
     pub should_include_block_id: bool,
 }
@@ -159,11 +370,19 @@ pub(super) struct Case<'a> {
     #[allow(dead_code)]
     pub options: CaseOptions<'a>,
 }
-impl<'a> test_support::Case for Case<'a> {
+impl test_support::Case for Case<'_> {
     fn assert_ok(&self) {
         let opts = NewHtmlRendererOptions {
             tag_name_map: self.options.tag_name_map.clone(),
+            extension_registry: self.options.extension_registry.0.clone(),
+            dicexp_result_provider: self.options.dicexp_result_provider.0,
+            ref_link_resolver: self.options.ref_link_resolver.0,
+            ref_link_context: self.options.ref_link_context.clone(),
+            wiki_link_resolver: self.options.wiki_link_resolver.0,
             initial_output_string_capacity: 0,
+            should_include_heading_ids: self.options.should_include_heading_ids,
+            heading_level_adjustment: self.options.heading_level_adjustment,
+            render_mode: self.options.render_mode,
             #[cfg(feature = "block-id")]
             should_include_block_ids: self.options.should_include_block_id,
         };
@@ -0,0 +1,32 @@
+use crate::outline::Heading;
+
+use super::HtmlWriter;
+
+/// 将大纲渲染为作为目录的嵌套列表。各项链接至 `#<slug>`，与启用
+/// [super::NewHtmlRendererOptions::should_include_heading_ids] 时渲染出的标题
+/// 的 `id` 对应。大纲为空时返回空字符串。
+pub fn render_table_of_contents(headings: &[Heading]) -> String {
+    let mut result = vec![];
+    write_list(&mut HtmlWriter::new(&mut result), headings);
+
+    unsafe { String::from_utf8_unchecked(result) }
+}
+
+fn write_list(w: &mut HtmlWriter, headings: &[Heading]) {
+    if headings.is_empty() {
+        return;
+    }
+
+    w.write_opening_tag(b"ol");
+    for heading in headings {
+        w.write_opening_tag(b"li");
+        w.write_opening_tag_start(b"a");
+        w.write_attribute(b"href", format!("#{}", heading.slug).as_bytes());
+        w.write_opening_tag_end();
+        w.write_escaped_text(heading.title.as_bytes());
+        w.write_closing_tag(b"a");
+        write_list(w, &heading.children);
+        w.write_closing_tag(b"li");
+    }
+    w.write_closing_tag(b"ol");
+}
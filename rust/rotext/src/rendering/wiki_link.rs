@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use super::slugify;
+
+/// 拆分并规范化后的 Wiki 链接地址（`[<namespace>:]<page_name>[#<anchor>]`）。
+///
+/// 各部分两端的空白都会被去除。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WikiLinkAddress<'a> {
+    /// 名字空间（或其别名），如 `s:通用概念` 中的 `s`。没有时使用默认的名字空
+    /// 间。
+    pub namespace: Option<&'a str>,
+    /// 页面名称。地址只有锚点部分（如 `#元素`）时为空，代表当前页面。
+    pub page_name: &'a str,
+    pub anchor: Option<&'a str>,
+}
+
+impl<'a> WikiLinkAddress<'a> {
+    pub fn parse(address: &'a str) -> Self {
+        let (full_page_name, anchor) = match address.split_once('#') {
+            Some((full_page_name, anchor)) => (full_page_name, Some(anchor.trim())),
+            None => (address, None),
+        };
+        let (namespace, page_name) = match full_page_name.split_once(':') {
+            Some((namespace, page_name)) => (Some(namespace.trim()), page_name),
+            None => (None, full_page_name),
+        };
+
+        Self {
+            namespace,
+            page_name: page_name.trim(),
+            anchor,
+        }
+    }
+}
+
+/// Wiki 链接的解析结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WikiLinkResolution {
+    /// 页面存在。
+    Resolved { href: String },
+    /// 页面不存在（“红链”）。
+    Missing,
+}
+
+/// 将 Wiki 链接的地址解析为实际的链接。
+///
+/// 解析结果会写入 Wiki 链接元素：[WikiLinkResolution::Resolved] 写入 `href`
+/// 属性，[WikiLinkResolution::Missing] 写入 `missing` 属性。
+pub trait WikiLinkResolver {
+    /// 返回 `None` 时，Wiki 链接按照没有解析器时的方式渲染。
+    fn resolve(&self, address: &WikiLinkAddress) -> Option<WikiLinkResolution>;
+}
+
+/// 由名字空间、页面名称与锚点生成 `href`。锚点已被转换为 slug（见 [slugify]），
+/// 与目标页面中的标题的 `id` 对应。
+type MakeHref<'a> = dyn Fn(&str, &str, Option<&str>) -> String + 'a;
+
+/// 由调用者提供的 Wiki 页面索引，按照索引判断页面是否存在。
+///
+/// 只有锚点部分的地址被解析为 `#<slug>`（见 [slugify]），不检查锚点是否存在。
+/// 渲染器会自行处理这样的地址，不会将其交给解析器。
+///
+/// 名字空间（及其别名）与页面名称都不区分大小写。
+pub struct WikiPageIndex<'a> {
+    default_namespace: String,
+    /// 名字空间或其别名（小写） => 名字空间。
+    namespaces: HashMap<String, String>,
+    /// (名字空间, 页面名称（小写）) => 页面名称。
+    pages: HashMap<(String, String), String>,
+    make_href: Box<MakeHref<'a>>,
+}
+
+impl<'a> WikiPageIndex<'a> {
+    pub fn new(
+        default_namespace: impl Into<String>,
+        make_href: impl Fn(&str, &str, Option<&str>) -> String + 'a,
+    ) -> Self {
+        let default_namespace = default_namespace.into();
+        let mut namespaces = HashMap::new();
+        namespaces.insert(default_namespace.to_lowercase(), default_namespace.clone());
+
+        Self {
+            default_namespace,
+            namespaces,
+            pages: HashMap::new(),
+            make_href: Box::new(make_href),
+        }
+    }
+
+    /// 添加名字空间及其别名。
+    pub fn add_namespace(&mut self, namespace: &str, aliases: &[&str]) -> &mut Self {
+        for name in std::iter::once(&namespace).chain(aliases) {
+            self.namespaces
+                .insert(name.to_lowercase(), namespace.to_string());
+        }
+        self
+    }
+
+    /// 在名字空间 `namespace`（或以其别名指代的名字空间）中添加页面。名字空间不
+    /// 存在时会一并添加。
+    pub fn add_page(&mut self, namespace: &str, page_name: &str) -> &mut Self {
+        let namespace = self
+            .namespaces
+            .entry(namespace.to_lowercase())
+            .or_insert_with(|| namespace.to_string())
+            .clone();
+        self.pages
+            .insert((namespace, page_name.to_lowercase()), page_name.to_string());
+        self
+    }
+
+    /// 返回 `address` 所指页面的名字空间与页面名称。页面不存在时返回 `None`。
+    pub fn find(&self, address: &WikiLinkAddress) -> Option<(&str, &str)> {
+        let namespace = match address.namespace {
+            Some(namespace) => self.namespaces.get(&namespace.to_lowercase())?,
+            None => &self.default_namespace,
+        };
+        let page_name = self
+            .pages
+            .get(&(namespace.clone(), address.page_name.to_lowercase()))?;
+
+        Some((namespace, page_name))
+    }
+}
+
+impl WikiLinkResolver for WikiPageIndex<'_> {
+    fn resolve(&self, address: &WikiLinkAddress) -> Option<WikiLinkResolution> {
+        if let WikiLinkAddress {
+            namespace: None,
+            page_name: "",
+            anchor: Some(anchor),
+        } = address
+        {
+            // 指向当前页面的锚点，与渲染器为标题生成的 `id` 对应。
+            let href = format!("#{}", slugify(anchor));
+            return Some(WikiLinkResolution::Resolved { href });
+        }
+
+        let resolution = match self.find(address) {
+            Some((namespace, page_name)) => WikiLinkResolution::Resolved {
+                href: (self.make_href)(
+                    namespace,
+                    page_name,
+                    address.anchor.map(slugify).as_deref(),
+                ),
+            },
+            None => WikiLinkResolution::Missing,
+        };
+
+        Some(resolution)
+    }
+}
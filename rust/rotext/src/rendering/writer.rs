@@ -0,0 +1,145 @@
+use std::{borrow::Cow, ops::Range};
+
+use crate::attributes::{self, Attribute};
+
+/// 附加信息中，除类之外所有元素都允许的属性。
+const GLOBALLY_ALLOWED_ATTRIBUTES: &[&[u8]] = &[b"title", b"lang", b"dir"];
+
+/// 向渲染结果写入 HTML 的工具，供扩展的处理器使用。
+pub struct HtmlWriter<'r> {
+    result: &'r mut Vec<u8>,
+}
+
+impl<'r> HtmlWriter<'r> {
+    pub(super) fn new(result: &'r mut Vec<u8>) -> Self {
+        Self { result }
+    }
+
+    /// 原样写入 `html`，不做任何转义。
+    pub fn write_raw(&mut self, html: &[u8]) {
+        self.result.extend(html);
+    }
+
+    /// 写入作为文本内容的 `text`。
+    pub fn write_escaped_text(&mut self, text: &[u8]) {
+        for char in text {
+            match *char {
+                b'<' => self.result.extend(b"&lt;"),
+                b'&' => self.result.extend(b"&amp;"),
+                char => self.result.push(char),
+            }
+        }
+    }
+
+    /// 写入作为双引号包围的属性值的 `value`（不含双引号本身）。
+    pub fn write_escaped_double_quoted_attribute_value(&mut self, value: &[u8]) {
+        for char in value {
+            match *char {
+                b'"' => self.result.extend(b"&quot;"),
+                b'&' => self.result.extend(b"&amp;"),
+                char => self.result.push(char),
+            }
+        }
+    }
+
+    /// 写入 `<tag_name>`。
+    pub fn write_opening_tag(&mut self, tag_name: &[u8]) {
+        self.write_opening_tag_start(tag_name);
+        self.write_opening_tag_end();
+    }
+
+    /// 写入 `<tag_name`，之后可以继续写入属性，最后需要调用
+    /// [Self::write_opening_tag_end]。
+    pub fn write_opening_tag_start(&mut self, tag_name: &[u8]) {
+        self.result.push(b'<');
+        self.result.extend(tag_name);
+    }
+
+    /// 写入 ` name="value"`。
+    pub fn write_attribute(&mut self, name: &[u8], value: &[u8]) {
+        self.result.push(b' ');
+        self.result.extend(name);
+        self.result.extend(br#"=""#);
+        self.write_escaped_double_quoted_attribute_value(value);
+        self.result.push(b'"');
+    }
+
+    /// 写入 ` name`。
+    pub fn write_boolean_attribute(&mut self, name: &[u8]) {
+        self.result.push(b' ');
+        self.result.extend(name);
+    }
+
+    /// 若 `block_id` 存在，写入 ` data-block-id="…"`。
+    pub fn write_data_block_id_attribute(&mut self, block_id: Option<usize>) {
+        if let Some(block_id) = block_id {
+            let mut buffer = itoa::Buffer::new();
+            self.write_attribute(b"data-block-id", buffer.format(block_id).as_bytes());
+        }
+    }
+
+    /// 写入附着于元素 `tag_name` 的附加信息（位于 `input[range]`）中的类，以及该
+    /// 元素允许的属性。同名的属性只有第一个有效。属性值中的字符引用会先被解码。
+    pub(super) fn write_attached_attributes(
+        &mut self,
+        input: &[u8],
+        range: Range<usize>,
+        tag_name: &[u8],
+    ) {
+        let mut classes: Vec<Cow<[u8]>> = vec![];
+        let mut others: Vec<(&[u8], Cow<[u8]>)> = vec![];
+        attributes::for_each_item(input, range, |item| match item {
+            Attribute::Class(name) => classes.push(Cow::Borrowed(&input[name])),
+            Attribute::KeyValue { key, value } => {
+                let key = &input[key];
+                let value = attributes::decode_character_references(&input[value]);
+                if key.eq_ignore_ascii_case(b"class") {
+                    classes.extend(
+                        value
+                            .split(|char| char.is_ascii_whitespace())
+                            .filter(|class| !class.is_empty())
+                            .map(|class| Cow::Owned(class.to_vec())),
+                    );
+                } else if let Some(key) = find_allowed_attribute(tag_name, key) {
+                    if others.iter().all(|(other_key, _)| *other_key != key) {
+                        others.push((key, value));
+                    }
+                }
+            }
+        });
+
+        if !classes.is_empty() {
+            self.write_attribute(b"class", &classes.join(&b' '));
+        }
+        for (key, value) in others {
+            self.write_attribute(key, &value);
+        }
+    }
+
+    /// 写入 `>`。
+    pub fn write_opening_tag_end(&mut self) {
+        self.result.push(b'>');
+    }
+
+    /// 写入 `</tag_name>`。
+    pub fn write_closing_tag(&mut self, tag_name: &[u8]) {
+        self.result.extend(b"</");
+        self.result.extend(tag_name);
+        self.result.push(b'>');
+    }
+}
+
+/// 若元素 `tag_name` 允许名称为 `key` 的属性（忽略大小写），返回该属性的规范名
+/// 称。
+fn find_allowed_attribute(tag_name: &[u8], key: &[u8]) -> Option<&'static [u8]> {
+    let element_specific: &[&[u8]] = match tag_name {
+        b"ol" => &[b"start"],
+        _ => &[],
+    };
+
+    GLOBALLY_ALLOWED_ATTRIBUTES
+        .iter()
+        .chain(element_specific)
+        .copied()
+        .find(|allowed| allowed.eq_ignore_ascii_case(key))
+}
@@ -0,0 +1,76 @@
+use indoc::indoc;
+
+use crate::{
+    events::EventType,
+    test_suites::blend::support::{case, GroupedCases},
+};
+
+pub fn groups_dicexp() -> Vec<GroupedCases> {
+    vec![GroupedCases {
+        group: "骰子表达式>跨行",
+        cases: vec![
+            case!(
+                vec![indoc! {"
+                    共有 [@_balls=d100
+                    +d6] 颗"}],
+                vec![
+                    (EventType::EnterParagraph, None),
+                    (EventType::Text, Some("共有 ")),
+                    (EventType::EnterDicexp, Some("_balls")),
+                    (EventType::Text, Some("d100")),
+                    (EventType::NewLine, None),
+                    (EventType::Text, Some("+d6")),
+                    (EventType::ExitInline, None),
+                    (EventType::Text, Some(" 颗")),
+                    (EventType::ExitBlock, None),
+                ]
+            ),
+            case!(
+                vec![indoc! {"
+                    {|
+                    || [=d100
+                    +d6] || x
+                    |}"}],
+                vec![
+                    (EventType::EnterTable, None),
+                    (EventType::IndicateTableDataCell, None),
+                    (EventType::EnterParagraph, None),
+                    (EventType::EnterDicexp, None),
+                    (EventType::Text, Some("d100")),
+                    (EventType::NewLine, None),
+                    (EventType::Text, Some("+d6")),
+                    (EventType::ExitInline, None),
+                    (EventType::ExitBlock, None),
+                    (EventType::IndicateTableDataCell, None),
+                    (EventType::EnterParagraph, None),
+                    (EventType::Text, Some("x")),
+                    (EventType::ExitBlock, None),
+                    (EventType::ExitBlock, None),
+                ]
+            ),
+            case!(
+                vec![indoc! {"
+                    {|
+                    || [=d100
+                    +d6 || x
+                    |}"}],
+                vec![
+                    (EventType::EnterTable, None),
+                    (EventType::IndicateTableDataCell, None),
+                    (EventType::EnterParagraph, None),
+                    (EventType::EnterDicexp, None),
+                    (EventType::Text, Some("d100")),
+                    (EventType::NewLine, None),
+                    (EventType::Text, Some("+d6")),
+                    (EventType::ExitInline, None),
+                    (EventType::ExitBlock, None),
+                    (EventType::IndicateTableDataCell, None),
+                    (EventType::EnterParagraph, None),
+                    (EventType::Text, Some("x")),
+                    (EventType::ExitBlock, None),
+                    (EventType::ExitBlock, None),
+                ]
+            ),
+        ],
+    }]
+}
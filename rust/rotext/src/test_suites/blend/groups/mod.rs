@@ -1 +1,2 @@
+pub mod dicexp;
 pub mod regression;
@@ -20,6 +20,7 @@ pub fn run<TContext: Context + RefUnwindSafe>(ctx: &TContext) {
         let mut table: Vec<GroupedCases> = vec![];
 
         table.extend(groups::regression::groups_regression());
+        table.extend(groups::dicexp::groups_dicexp());
 
         table
     };
@@ -0,0 +1,427 @@
+use indoc::indoc;
+
+use crate::{
+    events::EventType,
+    test_suites::block::support::{case, GroupedCases},
+};
+
+pub fn groups_block_attributes() -> Vec<GroupedCases> {
+    vec![
+        GroupedCases {
+            group: "块级附加信息",
+            cases: vec![
+                case!(
+                    vec![
+                        "{! .foo}a",
+                        "{! .foo} a",
+                        indoc! {"
+                        {! .foo}
+                        a"},
+                    ],
+                    vec![
+                        (EventType::EnterParagraph, Some("{! .foo}")),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{!.foo .bar␠␠key=\"value\" key2='v a l'}a"],
+                    vec![
+                        (
+                            EventType::EnterParagraph,
+                            Some("{!.foo .bar  key=\"value\" key2='v a l'}")
+                        ),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        {! .foo
+                        key=\"value\"}
+                        a"}],
+                    vec![
+                        (EventType::EnterParagraph, Some("{! .foo\nkey=\"value\"}")),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{! .foo}{! .bar}a", "{! .foo}{! .bar} a"],
+                    vec![
+                        (EventType::EnterParagraph, Some("{! .foo}{! .bar}")),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        {! .foo} {! .bar}
+                        {! .baz}
+                        a"}],
+                    vec![
+                        (
+                            EventType::EnterParagraph,
+                            Some("{! .foo} {! .bar}\n{! .baz}")
+                        ),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(vec!["{! .foo}", "{! .foo}\n"], vec![]),
+            ],
+        },
+        GroupedCases {
+            group: "块级附加信息>空行",
+            cases: vec![
+                case!(
+                    vec![
+                        indoc! {"
+                        {! .foo}
+
+                        a"},
+                        "{! .foo}\n␠␠\na",
+                    ],
+                    vec![
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        {! .foo}
+
+                        {! .bar}
+                        a"}],
+                    vec![
+                        (EventType::EnterParagraph, Some("{! .bar}")),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        {! .foo}
+
+                        * a"}],
+                    vec![
+                        (EventType::EnterUnorderedList, None),
+                        (EventType::EnterListItem, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "块级附加信息>无效",
+            cases: vec![
+                case!(
+                    vec!["{! foo}"],
+                    vec![
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("{! foo}")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{! .foo"],
+                    vec![
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("{! .foo")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{! key=value}"],
+                    vec![
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("{! key=value}")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{! a=\"1\"b=\"2\"}"],
+                    vec![
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("{! a=\"1\"b=\"2\"}")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        a
+                        {! .foo} b"}],
+                    vec![
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::NewLine, None),
+                        (EventType::__Unparsed, Some("{! .foo} b")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "块级附加信息>附着于各种块级元素",
+            cases: vec![
+                case!(
+                    vec!["{! .foo} ---"],
+                    vec![(EventType::ThematicBreak, Some("{! .foo}"))]
+                ),
+                case!(
+                    vec!["{! .foo} = a ="],
+                    vec![
+                        (EventType::EnterHeading1, Some("{! .foo}")),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{! .foo} > a"],
+                    vec![
+                        (EventType::EnterBlockQuote, Some("{! .foo}")),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["> {! .foo} a"],
+                    vec![
+                        (EventType::EnterBlockQuote, None),
+                        (EventType::EnterParagraph, Some("{! .foo}")),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        {! .foo} ```
+                        code
+                        ```"}],
+                    vec![
+                        (EventType::EnterCodeBlock, Some("{! .foo}")),
+                        (EventType::IndicateCodeBlockCode, None),
+                        (EventType::Text, Some("code")),
+                        (EventType::NewLine, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{! .foo} {|\n|}"],
+                    vec![
+                        (EventType::EnterTable, Some("{! .foo}")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{! .foo}{{#Foo}}"],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        > a
+                        {! .foo} > b"}],
+                    vec![
+                        (EventType::EnterBlockQuote, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::NewLine, None),
+                        (EventType::__Unparsed, Some("b")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "块级附加信息>表格单元格",
+            cases: vec![
+                case!(
+                    vec![indoc! {"
+                        {|
+                        {! colspan=\"2\"} !! a
+                        |}"}],
+                    vec![
+                        (EventType::EnterTable, None),
+                        (
+                            EventType::IndicateTableHeaderCell,
+                            Some("{! colspan=\"2\"}")
+                        ),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        {|
+                        || a {! .foo} || b
+                        |}"}],
+                    vec![
+                        (EventType::EnterTable, None),
+                        (EventType::IndicateTableDataCell, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a {! .foo}")),
+                        (EventType::ExitBlock, None),
+                        (EventType::IndicateTableDataCell, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("b")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        {|
+                        || {! .foo} || b
+                        |}"}],
+                    vec![
+                        (EventType::EnterTable, None),
+                        (EventType::IndicateTableDataCell, None),
+                        (EventType::IndicateTableDataCell, Some("{! .foo}")),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("b")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        {|
+                        {! .foo} || {! .bar} b
+                        |}"}],
+                    vec![
+                        (EventType::EnterTable, None),
+                        (EventType::IndicateTableDataCell, Some("{! .foo}")),
+                        (EventType::EnterParagraph, Some("{! .bar}")),
+                        (EventType::__Unparsed, Some("b")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        {|
+                        {! .foo}
+                        |-
+                        || a
+                        |}"}],
+                    vec![
+                        (EventType::EnterTable, None),
+                        (EventType::IndicateTableRow, None),
+                        (EventType::IndicateTableDataCell, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "块级附加信息>列表",
+            cases: vec![
+                case!(
+                    vec!["{! .foo}* a", "{! .foo} * a"],
+                    vec![
+                        (EventType::EnterUnorderedList, None),
+                        (EventType::EnterListItem, Some("{! .foo}")),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        {! .foo}
+                        * a"}],
+                    vec![
+                        (EventType::EnterUnorderedList, Some("{! .foo}")),
+                        (EventType::EnterListItem, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        {! .foo}* a
+                        {! .bar
+                        }* b"}],
+                    vec![
+                        (EventType::EnterUnorderedList, None),
+                        (EventType::EnterListItem, Some("{! .foo}")),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::EnterListItem, Some("{! .bar\n}")),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("b")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        * a
+                        {! .bar}
+                        * b"}],
+                    vec![
+                        (EventType::EnterUnorderedList, None),
+                        (EventType::EnterListItem, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::EnterUnorderedList, Some("{! .bar}")),
+                        (EventType::EnterListItem, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("b")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                        {! .foo}
+                        ; a
+                        {! .bar} : b"}],
+                    vec![
+                        (EventType::EnterDescriptionList, Some("{! .foo}")),
+                        (EventType::EnterDescriptionTerm, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::EnterDescriptionDetails, Some("{! .bar}")),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("b")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+            ],
+        },
+    ]
+}
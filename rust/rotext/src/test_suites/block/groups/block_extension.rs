@@ -0,0 +1,437 @@
+use indoc::indoc;
+
+use crate::{
+    events::EventType,
+    test_suites::block::support::{case, GroupedCases},
+};
+
+pub fn groups_block_extension() -> Vec<GroupedCases> {
+    vec![
+        GroupedCases {
+            group: "块级扩展>无内容",
+            cases: vec![
+                case!(
+                    vec![
+                        "{{#Foo}}",
+                        "{{ #Foo }}",
+                        "{{#␠Foo␠}}",
+                        indoc! {"
+                        {{#Foo
+                        }}"},
+                    ],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{{#Foo||}}", "{{#Foo || }}", "{{#Foo||"],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{{#Foo Bar}}", "{{#␠Foo Bar␠}}"],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo Bar")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "块级扩展>非块级扩展",
+            cases: vec![
+                case!(
+                    vec!["{{Foo}}"],
+                    vec![
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("{{Foo}}")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{{#}}"],
+                    vec![
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("{{#}}")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{{#||foo}}"],
+                    vec![
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("{{#||foo}}")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "块级扩展>参数",
+            cases: vec![
+                case!(
+                    vec![
+                        "{{#Foo??bar}}",
+                        "{{#Foo ?? bar }}",
+                        indoc! {"
+                        {{#Foo
+                        ?? bar
+                        }}"},
+                    ],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, None),
+                        (EventType::Text, Some("bar")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![
+                        "{{#Foo??a=1??b=2}}",
+                        "{{#Foo ?? a = 1 ?? b = 2 }}",
+                        indoc! {"
+                        {{#Foo
+                        ?? a=1
+                        ?? b=2
+                        }}"},
+                    ],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, Some("a")),
+                        (EventType::Text, Some("1")),
+                        (EventType::IndicateExtensionParameter, Some("b")),
+                        (EventType::Text, Some("2")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{{#Foo??a b = c d||}}"],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, Some("a b")),
+                        (EventType::Text, Some("c d")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{{#Foo??a=??=b}}"],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, Some("a")),
+                        (EventType::IndicateExtensionParameter, None),
+                        (EventType::Text, Some("=b")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{{#Foo??a=x <`||`> y}}"],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, Some("a")),
+                        (EventType::Text, Some("x ")),
+                        (EventType::VerbatimEscaping, Some("||")),
+                        (EventType::Text, Some(" y")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{{#Foo??<`a=b`>}}"],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, None),
+                        (EventType::VerbatimEscaping, Some("a=b")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "块级扩展>子内容",
+            cases: vec![
+                case!(
+                    vec![
+                        "{{#Foo||bar}}",
+                        "{{#Foo || bar }}",
+                        indoc! {"
+                        {{#Foo||
+                        bar
+                        }}"},
+                        indoc! {"
+                        {{#Foo
+                        ||
+                        bar
+                        }}"},
+                        indoc! {"
+                        {{#Foo
+                        bar
+                        }}"},
+                    ],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("bar")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![
+                        "{{#Foo??a=1||bar||baz}}",
+                        indoc! {"
+                        {{#Foo ?? a=1 ||
+                        bar
+                        ||
+                        baz
+                        }}"},
+                    ],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, Some("a")),
+                        (EventType::Text, Some("1")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("bar")),
+                        (EventType::ExitBlock, None),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("baz")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                    {{#Foo||
+                    = bar =
+                    ---
+                    > baz
+                    }}"}],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterHeading1, None),
+                        (EventType::__Unparsed, Some("bar")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ThematicBreak, None),
+                        (EventType::EnterBlockQuote, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("baz")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{{#Foo||= bar = }}", "{{#Foo||= bar }}"],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterHeading1, None),
+                        (EventType::__Unparsed, Some("bar")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                    {{#Foo||
+                    ```
+                    }}
+                    ```
+                    }}"}],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterCodeBlock, None),
+                        (EventType::IndicateCodeBlockCode, None),
+                        (EventType::Text, Some("}}")),
+                        (EventType::NewLine, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                    {{#Foo||
+                    > bar || baz
+                    }}"}],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterBlockQuote, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("bar")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("baz")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "块级扩展>嵌套",
+            cases: vec![
+                case!(
+                    vec![
+                        "{{#Foo||{{#Bar||baz}}}}",
+                        "{{ #Foo || {{ #Bar || baz }} }}",
+                        indoc! {"
+                        {{#Foo||
+                        {{#Bar||
+                        baz
+                        }}
+                        }}"},
+                    ],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterBlockExtension, Some("Bar")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("baz")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["{{#Foo||{{#Bar||a}}||b}}"],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterBlockExtension, Some("Bar")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("b")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![
+                        "{|||{{#Foo||a||b}}||c|}",
+                        indoc! {"
+                        {|
+                        || {{#Foo || a || b }} || c
+                        |}"},
+                    ],
+                    vec![
+                        (EventType::EnterTable, None),
+                        (EventType::IndicateTableDataCell, None),
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("b")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::IndicateTableDataCell, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("c")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                    {{#Foo|| {|
+                    || a || b
+                    }}"}],
+                    vec![
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterTable, None),
+                        (EventType::IndicateTableDataCell, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::IndicateTableDataCell, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("b")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![
+                        "{|{{#Foo||a|-b|}",
+                        indoc! {"
+                        {|
+                        {{#Foo||a
+                        |-
+                        b
+                        |}"},
+                    ],
+                    vec![
+                        (EventType::EnterTable, None),
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::IndicateTableRow, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("b")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                    {|
+                    {{#Foo
+                    !! a
+                    |}"}],
+                    vec![
+                        (EventType::EnterTable, None),
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::IndicateTableHeaderCell, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                    > {{#Foo||a
+                    > b}}"}],
+                    vec![
+                        (EventType::EnterBlockQuote, None),
+                        (EventType::EnterBlockExtension, Some("Foo")),
+                        (EventType::IndicateBlockExtensionSlot, None),
+                        (EventType::EnterParagraph, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::NewLine, None),
+                        (EventType::__Unparsed, Some("b")),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+            ],
+        },
+    ]
+}
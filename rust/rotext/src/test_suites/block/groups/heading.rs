@@ -186,6 +186,28 @@ pub fn groups_heading() -> Vec<GroupedCases> {
                 ),
             ],
         },
+        GroupedCases {
+            group: "标题>标题与全局阶段语法的互动>注释",
+            cases: vec![
+                case!(
+                    vec!["== a<%c%>b ==", "== a<%c%>b"],
+                    vec![
+                        (EventType::EnterHeading2, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::__Unparsed, Some("b")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+                case!(
+                    vec!["== a<%c%> ==", "== a<%c", "== a<%c\nb%>"],
+                    vec![
+                        (EventType::EnterHeading2, None),
+                        (EventType::__Unparsed, Some("a")),
+                        (EventType::ExitBlock, None),
+                    ]
+                ),
+            ],
+        },
         GroupedCases {
             group: "标题>无内容",
             cases: vec![
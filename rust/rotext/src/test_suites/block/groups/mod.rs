@@ -1,3 +1,5 @@
+pub mod block_attributes;
+pub mod block_extension;
 pub mod block_quote;
 pub mod code_block;
 pub mod description_list;
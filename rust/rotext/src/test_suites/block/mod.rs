@@ -31,6 +31,8 @@ pub fn run<TContext: Context + RefUnwindSafe>(ctx: &TContext) {
         table.extend(groups::description_list::groups_description_list());
         table.extend(groups::code_block::groups_code_block());
         table.extend(groups::table::groups_table());
+        table.extend(groups::block_extension::groups_block_extension());
+        table.extend(groups::block_attributes::groups_block_attributes());
 
         table
     };
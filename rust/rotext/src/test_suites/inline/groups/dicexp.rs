@@ -1,30 +1,317 @@
+use indoc::indoc;
+
 use crate::{
     events::EventType,
     test_suites::inline::support::{case, GroupedCases},
 };
 
 pub fn groups_dicexp() -> Vec<GroupedCases> {
-    vec![GroupedCases {
-        group: "骰子表达式",
-        cases: vec![
-            case!(
-                vec!["[=d100]", "[=d100"],
-                vec![(EventType::Dicexp, Some("d100")),]
-            ),
-            case!(
-                vec!["abc[=d100]"],
-                vec![
-                    (EventType::Text, Some("abc")),
-                    (EventType::Dicexp, Some("d100")),
-                ]
-            ),
-            case!(
-                vec!["[=d100]abc"],
-                vec![
-                    (EventType::Dicexp, Some("d100")),
-                    (EventType::Text, Some("abc")),
-                ]
-            ),
-        ],
-    }]
+    vec![
+        GroupedCases {
+            group: "骰子表达式",
+            cases: vec![
+                case!(
+                    vec!["[=d100]", "[=d100"],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some("d100")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["abc[=d100]"],
+                    vec![
+                        (EventType::Text, Some("abc")),
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some("d100")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[=d100]abc"],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some("d100")),
+                        (EventType::ExitInline, None),
+                        (EventType::Text, Some("abc")),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "骰子表达式>中括号匹配",
+            cases: vec![
+                case!(
+                    vec!["[=[[d6]]]"],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some("[[d6]]")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec![r#"[="]"]"#, r#"[="]""#],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some(r#""]""#)),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec![r#"[="[" ]x"#],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some(r#""[" "#)),
+                        (EventType::ExitInline, None),
+                        (EventType::Text, Some("x")),
+                    ]
+                ),
+                case!(
+                    vec![r#"[="\"]"]x"#],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some(r#""\"]""#)),
+                        (EventType::ExitInline, None),
+                        (EventType::Text, Some("x")),
+                    ]
+                ),
+                case!(
+                    vec![r#"[="\\"]x"#],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some(r#""\\""#)),
+                        (EventType::ExitInline, None),
+                        (EventType::Text, Some("x")),
+                    ]
+                ),
+                case!(
+                    vec![r#"[=f("]", ["[", "\"["])]x"#],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some(r#"f("]", ["[", "\"["])"#)),
+                        (EventType::ExitInline, None),
+                        (EventType::Text, Some("x")),
+                    ]
+                ),
+                case!(
+                    vec![r#"[=[["]]]x"#],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some(r#"[["]]]x"#)),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec![r#"[="\"#],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some(r#""\"#)),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[=[[[[[[d6"],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some("[[[[[[d6")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec![r#"[@_x="]"]"#],
+                    vec![
+                        (EventType::EnterDicexp, Some("_x")),
+                        (EventType::Text, Some(r#""]""#)),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "骰子表达式>多行",
+            cases: vec![
+                case!(
+                    vec![
+                        indoc! {"
+                        [=d100
+                        +d6]"},
+                        indoc! {"
+                        [=d100
+                        +d6"},
+                    ],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some("d100")),
+                        (EventType::NewLine, None),
+                        (EventType::Text, Some("+d6")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                    a[@_x=
+                    d6]b"}],
+                    vec![
+                        (EventType::Text, Some("a")),
+                        (EventType::EnterDicexp, Some("_x")),
+                        (EventType::NewLine, None),
+                        (EventType::Text, Some("d6")),
+                        (EventType::ExitInline, None),
+                        (EventType::Text, Some("b")),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {r#"
+                    [=f(["
+                    ]",
+                    "]"])]x"#}],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some("f([\"")),
+                        (EventType::NewLine, None),
+                        (EventType::Text, Some(r#"]","#)),
+                        (EventType::NewLine, None),
+                        (EventType::Text, Some(r#""]"])"#)),
+                        (EventType::ExitInline, None),
+                        (EventType::Text, Some("x")),
+                    ]
+                ),
+                case!(
+                    vec![indoc! {"
+                    [=d6\\
+                    ]_
+                    x"}],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some("d6\\")),
+                        (EventType::NewLine, None),
+                        (EventType::ExitInline, None),
+                        (EventType::Text, Some("x")),
+                    ]
+                ),
+                case!(
+                    vec!["['[=d6\n]']"],
+                    vec![
+                        (EventType::EnterStrong, None),
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some("d6")),
+                        (EventType::NewLine, None),
+                        (EventType::ExitInline, None),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "骰子表达式>逐字转义",
+            cases: vec![
+                case!(
+                    vec!["[=d<`6`>]"],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some("d")),
+                        (EventType::ExitInline, None),
+                        (EventType::VerbatimEscaping, Some("6")),
+                        (EventType::Text, Some("]")),
+                    ]
+                ),
+                case!(
+                    vec![r#"[="<`]`>"]"#],
+                    vec![
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some(r#"""#)),
+                        (EventType::ExitInline, None),
+                        (EventType::VerbatimEscaping, Some("]")),
+                        (EventType::Text, Some(r#""]"#)),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "骰子表达式>赋值",
+            cases: vec![
+                case!(
+                    vec!["[@_balls=d100]", "[@_balls=d100"],
+                    vec![
+                        (EventType::EnterDicexp, Some("_balls")),
+                        (EventType::Text, Some("d100")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[@@x=d6]"],
+                    vec![
+                        (EventType::EnterDicexp, Some("@x")),
+                        (EventType::Text, Some("d6")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[@.x=d6]"],
+                    vec![
+                        (EventType::EnterDicexp, Some(".x")),
+                        (EventType::Text, Some("d6")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[@foo.bar=d6]"],
+                    vec![
+                        (EventType::EnterDicexp, Some("foo.bar")),
+                        (EventType::Text, Some("d6")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[@_.a.b=d6]"],
+                    vec![
+                        (EventType::EnterDicexp, Some("_.a.b")),
+                        (EventType::Text, Some("d6")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[@变量.值=d6]"],
+                    vec![
+                        (EventType::EnterDicexp, Some("变量.值")),
+                        (EventType::Text, Some("d6")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["a[@_x=[d6]]b"],
+                    vec![
+                        (EventType::Text, Some("a")),
+                        (EventType::EnterDicexp, Some("_x")),
+                        (EventType::Text, Some("[d6]")),
+                        (EventType::ExitInline, None),
+                        (EventType::Text, Some("b")),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "骰子表达式>赋值>无效",
+            cases: vec![
+                case!(
+                    vec!["[@balls=d100]"],
+                    vec![(EventType::Text, Some("[@balls=d100]"))]
+                ),
+                case!(vec!["[@=d100]"], vec![(EventType::Text, Some("[@=d100]"))]),
+                case!(
+                    vec!["[@_1x.=d100]"],
+                    vec![(EventType::Text, Some("[@_1x.=d100]"))]
+                ),
+                case!(
+                    vec!["[@1x.y=d100]"],
+                    vec![(EventType::Text, Some("[@1x.y=d100]"))]
+                ),
+                case!(
+                    vec!["[@_a b=d100]"],
+                    vec![(EventType::Text, Some("[@_a b=d100]"))]
+                ),
+                case!(vec!["[@_x]"], vec![(EventType::Text, Some("[@_x]"))]),
+            ],
+        },
+    ]
 }
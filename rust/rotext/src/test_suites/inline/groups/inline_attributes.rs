@@ -0,0 +1,130 @@
+use crate::{
+    events::EventType,
+    test_suites::inline::support::{case, GroupedCases},
+};
+
+pub fn groups_inline_attributes() -> Vec<GroupedCases> {
+    vec![
+        GroupedCases {
+            group: "行内附加信息",
+            cases: vec![
+                case!(
+                    vec!["[! .foo]['a']"],
+                    vec![
+                        (EventType::EnterStrong, Some("[! .foo]")),
+                        (EventType::Text, Some("a")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["x[! .foo][~a~]y"],
+                    vec![
+                        (EventType::Text, Some("x")),
+                        (EventType::EnterStrikethrough, Some("[! .foo]")),
+                        (EventType::Text, Some("a")),
+                        (EventType::ExitInline, None),
+                        (EventType::Text, Some("y")),
+                    ]
+                ),
+                case!(
+                    vec!["[! .foo][`a`]"],
+                    vec![
+                        (EventType::EnterCodeSpan, Some("[! .foo]")),
+                        (EventType::Text, Some("a")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[!.foo  key=\"]\" key2='v a l'][! .bar]['a']"],
+                    vec![
+                        (
+                            EventType::EnterStrong,
+                            Some("[!.foo  key=\"]\" key2='v a l'][! .bar]")
+                        ),
+                        (EventType::Text, Some("a")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[! .foo][[页面]]"],
+                    vec![
+                        (EventType::EnterWikiLink, Some("页面")),
+                        (EventType::Text, Some("页面")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["x[! .foo][[<`页面`>]]"],
+                    vec![
+                        (EventType::Text, Some("x")),
+                        (EventType::EnterWikiLink, Some("页面")),
+                        (EventType::VerbatimEscaping, Some("页面")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[! .foo][{#Foo}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["['[! .foo][~a~]']"],
+                    vec![
+                        (EventType::EnterStrong, None),
+                        (EventType::EnterStrikethrough, Some("[! .foo]")),
+                        (EventType::Text, Some("a")),
+                        (EventType::ExitInline, None),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "行内附加信息>无效",
+            cases: vec![
+                case!(vec!["[! .foo]"], vec![(EventType::Text, Some("[! .foo]"))]),
+                case!(
+                    vec!["[! .foo]a"],
+                    vec![(EventType::Text, Some("[! .foo]a"))]
+                ),
+                case!(
+                    vec!["[! .foo] ['a']"],
+                    vec![
+                        (EventType::Text, Some("[! .foo] ")),
+                        (EventType::EnterStrong, None),
+                        (EventType::Text, Some("a")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[! foo]['a']"],
+                    vec![
+                        (EventType::Text, Some("[! foo]")),
+                        (EventType::EnterStrong, None),
+                        (EventType::Text, Some("a")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[! .foo][=d100]"],
+                    vec![
+                        (EventType::Text, Some("[! .foo]")),
+                        (EventType::EnterDicexp, None),
+                        (EventType::Text, Some("d100")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[! .foo][[页面"],
+                    vec![(EventType::Text, Some("[! .foo][[页面"))]
+                ),
+                case!(
+                    vec!["[! .foo][{Foo}]"],
+                    vec![(EventType::Text, Some("[! .foo][{Foo}]"))]
+                ),
+            ],
+        },
+    ]
+}
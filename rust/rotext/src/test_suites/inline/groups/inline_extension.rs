@@ -0,0 +1,289 @@
+use crate::{
+    events::EventType,
+    test_suites::inline::support::{case, GroupedCases},
+};
+
+pub fn groups_inline_extension() -> Vec<GroupedCases> {
+    vec![
+        GroupedCases {
+            group: "行内扩展",
+            cases: vec![
+                case!(
+                    vec!["[{#Foo}]", "[{ #Foo}]", "[{#Foo }]", "[{#\tFoo\t}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo Bar}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo Bar")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["a[{#Foo}]b"],
+                    vec![
+                        (EventType::Text, Some("a")),
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::ExitInline, None),
+                        (EventType::Text, Some("b")),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "行内扩展>非行内扩展",
+            cases: vec![
+                case!(vec!["[{Foo}]"], vec![(EventType::Text, Some("[{Foo}]"))]),
+                case!(vec!["[{#}]"], vec![(EventType::Text, Some("[{#}]"))]),
+                case!(vec!["[{# |a}]"], vec![(EventType::Text, Some("[{# |a}]"))]),
+                case!(vec!["[{#Foo"], vec![(EventType::Text, Some("[{#Foo"))]),
+                case!(
+                    vec!["[{#Foo\n|bar}]"],
+                    vec![
+                        (EventType::Text, Some("[{#Foo")),
+                        (EventType::NewLine, None),
+                        (EventType::Text, Some("|bar}]")),
+                    ]
+                ),
+                case!(
+                    vec!["[{#<`Foo`>}]"],
+                    vec![
+                        (EventType::Text, Some("[{#")),
+                        (EventType::VerbatimEscaping, Some("Foo")),
+                        (EventType::Text, Some("}]")),
+                    ]
+                ),
+                case!(
+                    vec!["Foo|bar}]"],
+                    vec![(EventType::Text, Some("Foo|bar}]"))]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "行内扩展>参数",
+            cases: vec![
+                case!(
+                    vec!["[{#Foo?bar}]", "[{#Foo ? bar }]", "[{#Foo?bar"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, None),
+                        (EventType::Text, Some("bar")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo?a=1}]", "[{#Foo ? a = 1 }]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, Some("a")),
+                        (EventType::Text, Some("1")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo?a=1?2}]", "[{#Foo ? a=1 ? 2}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, Some("a")),
+                        (EventType::Text, Some("1")),
+                        (EventType::IndicateExtensionParameter, None),
+                        (EventType::Text, Some("2")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo??}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, None),
+                        (EventType::IndicateExtensionParameter, None),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo?=1}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, None),
+                        (EventType::Text, Some("=1")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo?['a']~]}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, None),
+                        (EventType::Text, Some("['a']~]")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo?a<`?|}]`>b}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, None),
+                        (EventType::Text, Some("a")),
+                        (EventType::VerbatimEscaping, Some("?|}]")),
+                        (EventType::Text, Some("b")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo?a\nb}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, None),
+                        (EventType::Text, Some("a")),
+                        (EventType::NewLine, None),
+                        (EventType::Text, Some("b")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "行内扩展>子内容",
+            cases: vec![
+                case!(
+                    vec!["[{#Foo|bar}]", "[{#Foo | bar }]", "[{#Foo|bar"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::Text, Some("bar")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo|a|b}]", "[{#Foo | a | b }]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::Text, Some("a")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::Text, Some("b")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo||}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo?a=1|b?c}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateExtensionParameter, Some("a")),
+                        (EventType::Text, Some("1")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::Text, Some("b?c")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo|a\nb}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::Text, Some("a")),
+                        (EventType::NewLine, None),
+                        (EventType::Text, Some("b")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo|['a|b']|c}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::EnterStrong, None),
+                        (EventType::Text, Some("a|b")),
+                        (EventType::ExitInline, None),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::Text, Some("c")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo|['a}]b"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::EnterStrong, None),
+                        (EventType::Text, Some("a")),
+                        (EventType::ExitInline, None),
+                        (EventType::ExitInline, None),
+                        (EventType::Text, Some("b")),
+                    ]
+                ),
+                case!(
+                    vec!["['a[{#Foo|b']c}]"],
+                    vec![
+                        (EventType::EnterStrong, None),
+                        (EventType::Text, Some("a")),
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::Text, Some("b")),
+                        (EventType::ExitInline, None),
+                        (EventType::ExitInline, None),
+                        (EventType::Text, Some("c}]")),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo|[[页面|a|b]]|c}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::EnterWikiLink, Some("页面")),
+                        (EventType::Text, Some("a|b")),
+                        (EventType::ExitInline, None),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::Text, Some("c")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+            ],
+        },
+        GroupedCases {
+            group: "行内扩展>嵌套",
+            cases: vec![
+                case!(
+                    vec!["[{#Foo|[{#Bar|a|b}]|c}]", "[{#Foo| [{#Bar| a | b}] |c}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::EnterInlineExtension, Some("Bar")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::Text, Some("a")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::Text, Some("b")),
+                        (EventType::ExitInline, None),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::Text, Some("c")),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+                case!(
+                    vec!["[{#Foo|[{#Bar|a}]"],
+                    vec![
+                        (EventType::EnterInlineExtension, Some("Foo")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::EnterInlineExtension, Some("Bar")),
+                        (EventType::IndicateInlineExtensionSlot, None),
+                        (EventType::Text, Some("a")),
+                        (EventType::ExitInline, None),
+                        (EventType::ExitInline, None),
+                    ]
+                ),
+            ],
+        },
+    ]
+}
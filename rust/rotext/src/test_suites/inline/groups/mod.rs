@@ -1,6 +1,8 @@
 pub mod basic;
 pub mod code_span;
 pub mod dicexp;
+pub mod inline_attributes;
+pub mod inline_extension;
 pub mod ref_link;
 pub mod strong_and_strikethrough;
 pub mod wiki_link;
@@ -26,6 +26,8 @@ pub fn run<TContext: Context + RefUnwindSafe>(ctx: &TContext) {
         table.extend(groups::code_span::groups_code_span());
         table.extend(groups::strong_and_strikethrough::groups_strong_and_strikethrough());
         table.extend(groups::wiki_link::groups_wiki_link());
+        table.extend(groups::inline_extension::groups_inline_extension());
+        table.extend(groups::inline_attributes::groups_inline_attributes());
 
         table
     };
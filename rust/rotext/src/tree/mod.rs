@@ -0,0 +1,542 @@
+//! 文档树，即由属于 `Blend` 分组的事件构建的、拥有所有权的语法树。
+//!
+//! 事件流只能从头到尾消费一遍，使用者需要自己维护栈来还原元素之间的嵌套关系
+//! （如 [crate::HtmlRenderer::render] 所做的那样）。[Tree] 预先完成这项工作，
+//! 之后可以任意地遍历、在父子节点之间跳转，也可以转换回事件流交给
+//! [crate::HtmlRenderer] 渲染。
+//!
+//! 节点保留了构建时所用事件中的全部信息，因此 [Tree::to_events] 得到的事件与构
+//! 建时所用的事件相同。对于事件流中由 “指示” 事件分隔的部分（如表格的行与单元
+//! 格、代码块的代码、扩展的参数与子内容），树中有对应的节点；隐式开启的表格行
+//! 与单元格也有对应的节点，只是其中不含指示标记的信息。
+
+#[cfg(test)]
+mod tests;
+
+use std::ops::Range;
+
+use crate::{
+    events::{
+        BlockExtension, BlockWithId, Dicexp, ExitBlock, ExitInline, ExtensionParameter, Indication,
+        InlineElement, InlineExtension, NewLine, TableCell, ThematicBreak, VerbatimEscaping,
+        WikiLink,
+    },
+    Event, HtmlRenderer,
+};
+
+/// 节点在其所属的 [Tree] 中的标识。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// 节点的种类，以及构建节点所用的事件中的信息。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeKind {
+    /// 根节点。
+    Document,
+
+    /// 原封不动地渲染至输出的内容。
+    Raw(Range<usize>),
+    Text(Range<usize>),
+    VerbatimEscaping(VerbatimEscaping),
+    NewLine(NewLine),
+    /// 引用链接，见 [Event::RefLink]。
+    RefLink(Range<usize>),
+    ThematicBreak(ThematicBreak),
+
+    Paragraph(BlockWithId),
+    /// `level` 是作者在文档中使用的级别，即 `=` 的数量。
+    Heading {
+        level: usize,
+        data: BlockWithId,
+    },
+    BlockQuote(BlockWithId),
+    List {
+        is_ordered: bool,
+        data: BlockWithId,
+    },
+    ListItem(BlockWithId),
+    DescriptionList(BlockWithId),
+    DescriptionTerm(BlockWithId),
+    DescriptionDetails(BlockWithId),
+    /// 子节点依次为 [NodeKind::CodeBlockInfo] 与 [NodeKind::CodeBlockCode]。
+    CodeBlock(BlockWithId),
+    /// 代码块的信息字符串。
+    CodeBlockInfo,
+    /// 代码块的代码。
+    CodeBlockCode(Indication),
+    /// 子节点是可选的 [NodeKind::TableCaption]，以及各 [NodeKind::TableRow]。
+    Table(BlockWithId),
+    TableCaption(Indication),
+    /// 隐式开启的行（如表格中第一个单元格之前没有 `|-`）没有指示标记，为
+    /// `None`。子节点是各 [NodeKind::TableCell]。
+    TableRow(Option<Indication>),
+    /// 隐式开启的单元格（表格开头或行开头的内容之前没有 `!!` 或 `||`）没有指示
+    /// 标记，为 `None`，且总是数据单元格。
+    TableCell {
+        is_header: bool,
+        data: Option<TableCell>,
+    },
+    /// 子节点是各 [NodeKind::ExtensionParameter] 与 [NodeKind::ExtensionSlot]。
+    BlockExtension(BlockExtension),
+
+    /// 骰子表达式。子节点中的 `Text` 与 `NewLine` 构成其代码。
+    Dicexp(Dicexp),
+    CodeSpan(InlineElement),
+    Strong(InlineElement),
+    Strikethrough(InlineElement),
+    WikiLink(WikiLink),
+    /// 子节点是各 [NodeKind::ExtensionParameter] 与 [NodeKind::ExtensionSlot]。
+    InlineExtension(InlineExtension),
+
+    /// 块级扩展或行内扩展的逐字参数。子节点构成参数的值。
+    ExtensionParameter(ExtensionParameter),
+    /// 块级扩展或行内扩展的子内容。
+    ExtensionSlot(Indication),
+}
+
+impl NodeKind {
+    /// 是否是由 “指示” 事件（或隐式地）分隔出的部分。这些节点没有对应的退出事
+    /// 件，在其所属的元素遇到下一个指示或退出事件时结束。
+    fn is_section(&self) -> bool {
+        matches!(
+            self,
+            NodeKind::CodeBlockInfo
+                | NodeKind::CodeBlockCode(_)
+                | NodeKind::TableCaption(_)
+                | NodeKind::TableRow(_)
+                | NodeKind::TableCell { .. }
+                | NodeKind::ExtensionParameter(_)
+                | NodeKind::ExtensionSlot(_)
+        )
+    }
+}
+
+/// 节点对应的退出事件中的信息。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeExit {
+    Block(ExitBlock),
+    Inline(ExitInline),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    kind: NodeKind,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    exit: Option<NodeExit>,
+}
+
+impl Node {
+    pub fn kind(&self) -> &NodeKind {
+        &self.kind
+    }
+
+    /// 根节点没有父节点。
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+
+    /// 对应的退出事件中的信息。叶子节点、根节点以及由 “指示” 事件分隔出的部分
+    /// 没有退出事件，为 `None`。
+    pub fn exit(&self) -> Option<&NodeExit> {
+        self.exit.as_ref()
+    }
+}
+
+/// 完整地解析 `input`，并由产出的事件构建文档树。
+pub fn parse(input: &[u8]) -> crate::Result<Tree> {
+    let events = crate::parse(input).collect::<crate::Result<Vec<_>>>()?;
+
+    Ok(Tree::from_events(events))
+}
+
+/// 文档树。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tree {
+    /// 第一项是根节点。
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    /// 由属于 `Blend` 分组的事件构建文档树。事件应是完整地解析一份输入所得的。
+    pub fn from_events(events: impl IntoIterator<Item = Event>) -> Self {
+        let mut builder = Builder {
+            tree: Tree {
+                nodes: vec![Node {
+                    kind: NodeKind::Document,
+                    parent: None,
+                    children: vec![],
+                    exit: None,
+                }],
+            },
+            stack: vec![NodeId(0)],
+        };
+        for ev in events {
+            builder.push(ev);
+        }
+        debug_assert_eq!(builder.stack.len(), 1);
+
+        builder.tree
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    pub fn kind(&self, id: NodeId) -> &NodeKind {
+        &self.node(id).kind
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).parent
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.node(id).children
+    }
+
+    /// 由近及远地返回 `id` 的祖先节点（不含其本身）。
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::successors(self.parent(id), |id| self.parent(*id))
+    }
+
+    pub fn previous_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let siblings = self.children(self.parent(id)?);
+        let index = siblings.iter().position(|x| *x == id)?;
+        siblings.get(index.checked_sub(1)?).copied()
+    }
+
+    pub fn next_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let siblings = self.children(self.parent(id)?);
+        let index = siblings.iter().position(|x| *x == id)?;
+        siblings.get(index + 1).copied()
+    }
+
+    /// 以前序遍历的顺序返回 `id` 的后代节点（不含其本身）。
+    pub fn descendants(&self, id: NodeId) -> Descendants<'_> {
+        Descendants {
+            tree: self,
+            stack: self.children(id).iter().rev().copied().collect(),
+        }
+    }
+
+    /// 以前序遍历的顺序返回全部节点，即在文档中出现的顺序。
+    pub fn iter(&self) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::once(self.root()).chain(self.descendants(self.root()))
+    }
+
+    /// 返回 `id` 之中的纯文本内容，由其中的 `Text` 与 `VerbatimEscaping` 的内容
+    /// 以及换行构成，不包括骰子表达式的代码。
+    pub fn text(&self, input: &[u8], id: NodeId) -> String {
+        let mut text = String::new();
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            match self.kind(id) {
+                NodeKind::Text(content)
+                | NodeKind::VerbatimEscaping(VerbatimEscaping { content, .. }) => {
+                    // SAFETY: 输入是有效的 UTF-8，文本在字符边界处截断。
+                    text.push_str(unsafe { std::str::from_utf8_unchecked(&input[content.clone()]) })
+                }
+                NodeKind::NewLine(_) => text.push('\n'),
+                NodeKind::Dicexp(_) => {}
+                _ => stack.extend(self.children(id).iter().rev()),
+            }
+        }
+
+        text
+    }
+
+    /// 将文档树转换回属于 `Blend` 分组的事件。
+    pub fn to_events(&self) -> Vec<Event> {
+        enum Step {
+            Enter(NodeId),
+            Exit(NodeId),
+        }
+
+        let mut events = vec![];
+        let mut steps = vec![Step::Enter(self.root())];
+        while let Some(step) = steps.pop() {
+            match step {
+                Step::Enter(id) => {
+                    if let Some(ev) = self.make_enter_event(id) {
+                        events.push(ev);
+                    }
+                    steps.push(Step::Exit(id));
+                    steps.extend(self.children(id).iter().rev().map(|id| Step::Enter(*id)));
+                }
+                Step::Exit(id) => match &self.node(id).exit {
+                    Some(NodeExit::Block(exit)) => events.push(Event::ExitBlock(exit.clone())),
+                    Some(NodeExit::Inline(exit)) => events.push(Event::ExitInline(exit.clone())),
+                    None => {}
+                },
+            }
+        }
+
+        events
+    }
+
+    /// 通过 `renderer` 渲染文档树。
+    pub fn render(&self, renderer: HtmlRenderer) -> String {
+        renderer.render(self.to_events().into_iter())
+    }
+
+    /// 返回节点对应的进入事件（对于叶子节点，即其本身对应的事件）。根节点与隐式
+    /// 开启的部分没有对应的事件。
+    fn make_enter_event(&self, id: NodeId) -> Option<Event> {
+        let ev = match self.kind(id).clone() {
+            NodeKind::Document | NodeKind::CodeBlockInfo => return None,
+            NodeKind::Raw(content) => Event::Raw(content),
+            NodeKind::Text(content) => Event::Text(content),
+            NodeKind::VerbatimEscaping(data) => Event::VerbatimEscaping(data),
+            NodeKind::NewLine(data) => Event::NewLine(data),
+            NodeKind::RefLink(content) => Event::RefLink(content),
+            NodeKind::ThematicBreak(data) => Event::ThematicBreak(data),
+            NodeKind::Paragraph(data) => Event::EnterParagraph(data),
+            NodeKind::Heading { level, data } => match level {
+                1 => Event::EnterHeading1(data),
+                2 => Event::EnterHeading2(data),
+                3 => Event::EnterHeading3(data),
+                4 => Event::EnterHeading4(data),
+                5 => Event::EnterHeading5(data),
+                6 => Event::EnterHeading6(data),
+                _ => unreachable!(),
+            },
+            NodeKind::BlockQuote(data) => Event::EnterBlockQuote(data),
+            NodeKind::List {
+                is_ordered: true,
+                data,
+            } => Event::EnterOrderedList(data),
+            NodeKind::List {
+                is_ordered: false,
+                data,
+            } => Event::EnterUnorderedList(data),
+            NodeKind::ListItem(data) => Event::EnterListItem(data),
+            NodeKind::DescriptionList(data) => Event::EnterDescriptionList(data),
+            NodeKind::DescriptionTerm(data) => Event::EnterDescriptionTerm(data),
+            NodeKind::DescriptionDetails(data) => Event::EnterDescriptionDetails(data),
+            NodeKind::CodeBlock(data) => Event::EnterCodeBlock(data),
+            NodeKind::CodeBlockCode(data) => Event::IndicateCodeBlockCode(data),
+            NodeKind::Table(data) => Event::EnterTable(data),
+            NodeKind::TableCaption(data) => Event::IndicateTableCaption(data),
+            NodeKind::TableRow(data) => Event::IndicateTableRow(data?),
+            NodeKind::TableCell {
+                is_header: true,
+                data,
+            } => Event::IndicateTableHeaderCell(data?),
+            NodeKind::TableCell {
+                is_header: false,
+                data,
+            } => Event::IndicateTableDataCell(data?),
+            NodeKind::BlockExtension(data) => Event::EnterBlockExtension(data),
+            NodeKind::Dicexp(data) => Event::EnterDicexp(data),
+            NodeKind::CodeSpan(data) => Event::EnterCodeSpan(data),
+            NodeKind::Strong(data) => Event::EnterStrong(data),
+            NodeKind::Strikethrough(data) => Event::EnterStrikethrough(data),
+            NodeKind::WikiLink(data) => Event::EnterWikiLink(data),
+            NodeKind::InlineExtension(data) => Event::EnterInlineExtension(data),
+            NodeKind::ExtensionParameter(data) => Event::IndicateExtensionParameter(data),
+            NodeKind::ExtensionSlot(data) => {
+                let parent = self.parent(id).unwrap();
+                match self.kind(parent) {
+                    NodeKind::BlockExtension(_) => Event::IndicateBlockExtensionSlot(data),
+                    NodeKind::InlineExtension(_) => Event::IndicateInlineExtensionSlot(data),
+                    _ => unreachable!(),
+                }
+            }
+        };
+
+        Some(ev)
+    }
+}
+
+/// 见 [Tree::descendants]。
+pub struct Descendants<'a> {
+    tree: &'a Tree,
+    stack: Vec<NodeId>,
+}
+
+impl Iterator for Descendants<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.stack.pop()?;
+        self.stack
+            .extend(self.tree.children(id).iter().rev().copied());
+        Some(id)
+    }
+}
+
+struct Builder {
+    tree: Tree,
+    /// 尚未结束的节点，第一项是根节点。
+    stack: Vec<NodeId>,
+}
+
+impl Builder {
+    fn top(&self) -> NodeId {
+        *self.stack.last().unwrap()
+    }
+
+    fn top_kind(&self) -> &NodeKind {
+        self.tree.kind(self.top())
+    }
+
+    /// 在栈顶的节点中加入子节点，并返回其标识。
+    fn add(&mut self, kind: NodeKind) -> NodeId {
+        let id = NodeId(self.tree.nodes.len());
+        let parent = self.top();
+        self.tree.nodes.push(Node {
+            kind,
+            parent: Some(parent),
+            children: vec![],
+            exit: None,
+        });
+        self.tree.nodes[parent.0].children.push(id);
+        id
+    }
+
+    /// 在栈顶的节点中加入子节点，并使其成为栈顶。
+    fn open(&mut self, kind: NodeKind) {
+        let id = self.add(kind);
+        self.stack.push(id);
+    }
+
+    /// 结束栈顶由 “指示” 事件分隔出的部分，直到 `should_stop` 对栈顶的节点返回
+    /// `true`，或者栈顶不再是这样的部分为止。
+    fn close_sections(&mut self, should_stop: impl Fn(&NodeKind) -> bool) {
+        while self.top_kind().is_section() && !should_stop(self.top_kind()) {
+            self.stack.pop();
+        }
+    }
+
+    /// 在加入内容之前，若栈顶是表格或表格行，隐式地开启表格行与单元格。这与
+    /// [crate::HtmlRenderer] 的处理一致。
+    fn prepare_for_content(&mut self) {
+        if matches!(self.top_kind(), NodeKind::Table(_)) {
+            self.open(NodeKind::TableRow(None));
+        }
+        if matches!(self.top_kind(), NodeKind::TableRow(_)) {
+            self.open(NodeKind::TableCell {
+                is_header: false,
+                data: None,
+            });
+        }
+    }
+
+    /// `ev` 属于 `Blend` 分组。
+    fn push(&mut self, ev: Event) {
+        #[rotext_internal_macros::ensure_cases_for_event(
+            prefix = Event,
+            group = Blend,
+        )]
+        match ev {
+            Event::Raw(content) => self.push_leaf(NodeKind::Raw(content)),
+            Event::Text(content) => self.push_leaf(NodeKind::Text(content)),
+            Event::VerbatimEscaping(data) => self.push_leaf(NodeKind::VerbatimEscaping(data)),
+            Event::NewLine(data) => self.push_leaf(NodeKind::NewLine(data)),
+            Event::RefLink(content) => self.push_leaf(NodeKind::RefLink(content)),
+            Event::ThematicBreak(data) => self.push_leaf(NodeKind::ThematicBreak(data)),
+
+            Event::EnterParagraph(data) => self.push_enter(NodeKind::Paragraph(data)),
+            Event::EnterHeading1(data) => self.push_heading(1, data),
+            Event::EnterHeading2(data) => self.push_heading(2, data),
+            Event::EnterHeading3(data) => self.push_heading(3, data),
+            Event::EnterHeading4(data) => self.push_heading(4, data),
+            Event::EnterHeading5(data) => self.push_heading(5, data),
+            Event::EnterHeading6(data) => self.push_heading(6, data),
+            Event::EnterBlockQuote(data) => self.push_enter(NodeKind::BlockQuote(data)),
+            Event::EnterOrderedList(data) => self.push_enter(NodeKind::List {
+                is_ordered: true,
+                data,
+            }),
+            Event::EnterUnorderedList(data) => self.push_enter(NodeKind::List {
+                is_ordered: false,
+                data,
+            }),
+            Event::EnterListItem(data) => self.push_enter(NodeKind::ListItem(data)),
+            Event::EnterDescriptionList(data) => self.push_enter(NodeKind::DescriptionList(data)),
+            Event::EnterDescriptionTerm(data) => self.push_enter(NodeKind::DescriptionTerm(data)),
+            Event::EnterDescriptionDetails(data) => {
+                self.push_enter(NodeKind::DescriptionDetails(data))
+            }
+            Event::EnterCodeBlock(data) => {
+                self.push_enter(NodeKind::CodeBlock(data));
+                self.open(NodeKind::CodeBlockInfo);
+            }
+            Event::EnterTable(data) => self.push_enter(NodeKind::Table(data)),
+            Event::EnterBlockExtension(data) => self.push_enter(NodeKind::BlockExtension(data)),
+            Event::EnterDicexp(data) => self.push_enter(NodeKind::Dicexp(data)),
+            Event::EnterCodeSpan(data) => self.push_enter(NodeKind::CodeSpan(data)),
+            Event::EnterStrong(data) => self.push_enter(NodeKind::Strong(data)),
+            Event::EnterStrikethrough(data) => self.push_enter(NodeKind::Strikethrough(data)),
+            Event::EnterWikiLink(data) => self.push_enter(NodeKind::WikiLink(data)),
+            Event::EnterInlineExtension(data) => self.push_enter(NodeKind::InlineExtension(data)),
+
+            Event::IndicateCodeBlockCode(data) => {
+                self.close_sections(|_| false);
+                self.open(NodeKind::CodeBlockCode(data));
+            }
+            Event::IndicateTableCaption(data) => {
+                self.close_sections(|_| false);
+                self.open(NodeKind::TableCaption(data));
+            }
+            Event::IndicateTableRow(data) => {
+                self.close_sections(|_| false);
+                self.open(NodeKind::TableRow(Some(data)));
+            }
+            Event::IndicateTableHeaderCell(data) => self.push_table_cell(true, data),
+            Event::IndicateTableDataCell(data) => self.push_table_cell(false, data),
+            Event::IndicateExtensionParameter(data) => {
+                self.close_sections(|_| false);
+                self.open(NodeKind::ExtensionParameter(data));
+            }
+            Event::IndicateBlockExtensionSlot(data) | Event::IndicateInlineExtensionSlot(data) => {
+                self.close_sections(|_| false);
+                self.open(NodeKind::ExtensionSlot(data));
+            }
+
+            Event::ExitBlock(data) => self.push_exit(NodeExit::Block(data)),
+            Event::ExitInline(data) => self.push_exit(NodeExit::Inline(data)),
+        }
+    }
+
+    fn push_leaf(&mut self, kind: NodeKind) {
+        self.prepare_for_content();
+        self.add(kind);
+    }
+
+    fn push_enter(&mut self, kind: NodeKind) {
+        self.prepare_for_content();
+        self.open(kind);
+    }
+
+    fn push_heading(&mut self, level: usize, data: BlockWithId) {
+        self.push_enter(NodeKind::Heading { level, data });
+    }
+
+    fn push_table_cell(&mut self, is_header: bool, data: TableCell) {
+        self.close_sections(|kind| matches!(kind, NodeKind::TableRow(_)));
+        if !matches!(self.top_kind(), NodeKind::TableRow(_)) {
+            self.open(NodeKind::TableRow(None));
+        }
+        self.open(NodeKind::TableCell {
+            is_header,
+            data: Some(data),
+        });
+    }
+
+    fn push_exit(&mut self, exit: NodeExit) {
+        self.close_sections(|_| false);
+        let id = self.stack.pop().unwrap();
+        debug_assert_ne!(id, NodeId(0));
+        self.tree.nodes[id.0].exit = Some(exit);
+    }
+}
@@ -0,0 +1,238 @@
+use indoc::indoc;
+
+use super::{parse, NodeId, NodeKind, Tree};
+use crate::{Event, HtmlRenderer, NewHtmlRendererOptions};
+
+fn parse_events(input: &str) -> Vec<Event> {
+    crate::parse(input.as_bytes())
+        .collect::<crate::Result<_>>()
+        .unwrap()
+}
+
+fn new_renderer(input: &str) -> HtmlRenderer {
+    HtmlRenderer::new(
+        input.as_bytes(),
+        NewHtmlRendererOptions {
+            tag_name_map: Default::default(),
+            extension_registry: Default::default(),
+            dicexp_result_provider: None,
+            ref_link_resolver: None,
+            ref_link_context: Default::default(),
+            wiki_link_resolver: None,
+            initial_output_string_capacity: 0,
+            should_include_heading_ids: true,
+            heading_level_adjustment: Default::default(),
+            render_mode: Default::default(),
+            #[cfg(feature = "block-id")]
+            should_include_block_ids: true,
+        },
+    )
+}
+
+/// 将文档树转换为 “缩进 节点” 构成的各行，便于比较。文本类的节点附带其内容，
+/// 隐式开启的部分附带 `*`。
+fn summarize(tree: &Tree, input: &str) -> Vec<String> {
+    let depth = |id: NodeId| tree.ancestors(id).count() - 1;
+    let content = |range: &std::ops::Range<usize>| &input[range.clone()];
+
+    tree.descendants(tree.root())
+        .map(|id| {
+            let description = match tree.kind(id) {
+                NodeKind::Text(range) => format!("Text {:?}", content(range)),
+                NodeKind::VerbatimEscaping(data) => {
+                    format!("VerbatimEscaping {:?}", content(&data.content))
+                }
+                NodeKind::Heading { level, .. } => format!("Heading {}", level),
+                NodeKind::List { is_ordered, .. } => {
+                    format!("List{}", if *is_ordered { " ordered" } else { "" })
+                }
+                NodeKind::TableRow(None) => "TableRow*".to_string(),
+                NodeKind::TableCell { is_header, data } => format!(
+                    "{}{}",
+                    if *is_header {
+                        "TableHeaderCell"
+                    } else {
+                        "TableDataCell"
+                    },
+                    if data.is_none() { "*" } else { "" }
+                ),
+                NodeKind::WikiLink(data) => format!("WikiLink {:?}", content(&data.address)),
+                kind => {
+                    let debug = format!("{:?}", kind);
+                    debug[..debug.find(['(', ' ']).unwrap_or(debug.len())].to_string()
+                }
+            };
+            format!("{}{}", "  ".repeat(depth(id)), description)
+        })
+        .collect()
+}
+
+#[test]
+fn it_works() {
+    let table: &[(&str, &[&str])] = &[
+        ("", &[]),
+        (
+            "= a ['b'] =",
+            &["Heading 1", "  Text \"a \"", "  Strong", "    Text \"b\""],
+        ),
+        (
+            indoc! {"
+            # # a
+            # b
+            * c"},
+            &[
+                "List ordered",
+                "  ListItem",
+                "    List ordered",
+                "      ListItem",
+                "        Paragraph",
+                "          Text \"a\"",
+                "  ListItem",
+                "    Paragraph",
+                "      Text \"b\"",
+                "List",
+                "  ListItem",
+                "    Paragraph",
+                "      Text \"c\"",
+            ],
+        ),
+        (
+            indoc! {"
+            ```js
+            a
+            ```"},
+            &[
+                "CodeBlock",
+                "  CodeBlockInfo",
+                "    Text \"js\"",
+                "  CodeBlockCode",
+                "    Text \"a\"",
+                "    NewLine",
+            ],
+        ),
+        (
+            indoc! {"
+            {|
+            |+ c
+            |-
+            !! h || d
+            |-
+            e
+            |}"},
+            &[
+                "Table",
+                "  TableCaption",
+                "    Paragraph",
+                "      Text \"c\"",
+                "  TableRow",
+                "    TableHeaderCell",
+                "      Paragraph",
+                "        Text \"h\"",
+                "    TableDataCell",
+                "      Paragraph",
+                "        Text \"d\"",
+                "  TableRow",
+                "    TableDataCell*",
+                "      Paragraph",
+                "        Text \"e\"",
+            ],
+        ),
+        (
+            "{|a||b|}",
+            &[
+                "Table",
+                "  TableRow*",
+                "    TableDataCell*",
+                "      Paragraph",
+                "        Text \"a\"",
+                "    TableDataCell",
+                "      Paragraph",
+                "        Text \"b\"",
+            ],
+        ),
+        (
+            "{{#Foo??x=1||[[a|<`b`>]] [{#Bar|c}]}}",
+            &[
+                "BlockExtension",
+                "  ExtensionParameter",
+                "    Text \"1\"",
+                "  ExtensionSlot",
+                "    Paragraph",
+                "      WikiLink \"a\"",
+                "        VerbatimEscaping \"b\"",
+                "      Text \" \"",
+                "      InlineExtension",
+                "        ExtensionSlot",
+                "          Text \"c\"",
+            ],
+        ),
+    ];
+
+    for (input, expected) in table {
+        let tree = parse(input.as_bytes()).unwrap();
+        assert_eq!(*expected, summarize(&tree, input), "input: {:?}", input);
+    }
+}
+
+#[test]
+fn it_converts_back_to_the_same_events() {
+    let inputs = [
+        "",
+        "a\nb <`c`> [=d6]",
+        "= a =\n== ['b'] [~c~] [`d`] ==",
+        "> a\n> > b\n---\n{!class=x} c",
+        "# # a\n# b\n* c\n; d\n: e",
+        "```js\na\n\nb\n```",
+        "{|\n|+ c\n|-\n!! h || d\n|-\ne\n|}\n{|a||b|}",
+        "{|\n{!rowspan=2}|| a\n|}",
+        "{{#Foo??x=1??<`y`>||a||{{#Bar}}}}",
+        ">>#1 [[a|b]] [[#c]] [{#E?p=1|s}] [@x=d4]",
+        "{{#Note\n* a\n{|\n||{{#Foo||b\n|-\nc",
+    ];
+
+    for input in inputs {
+        let events = parse_events(input);
+        let tree = Tree::from_events(events.clone());
+        assert_eq!(events, tree.to_events(), "input: {:?}", input);
+        assert_eq!(
+            new_renderer(input).render(events.into_iter()),
+            tree.render(new_renderer(input)),
+            "input: {:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn it_navigates_between_nodes() {
+    let input = "a\n\n> b ['c']\n\nd";
+    let tree = parse(input.as_bytes()).unwrap();
+    let root = tree.root();
+    assert_eq!(None, tree.parent(root));
+    assert_eq!(3, tree.children(root).len());
+
+    let [a, quote, d] = tree.children(root) else {
+        unreachable!()
+    };
+    assert_eq!(None, tree.previous_sibling(*a));
+    assert_eq!(Some(*quote), tree.next_sibling(*a));
+    assert_eq!(Some(*a), tree.previous_sibling(*quote));
+    assert_eq!(None, tree.next_sibling(*d));
+    assert_eq!("a", tree.text(input.as_bytes(), *a));
+    assert_eq!("b c", tree.text(input.as_bytes(), *quote));
+
+    let strong = tree
+        .descendants(*quote)
+        .find(|id| matches!(tree.kind(*id), NodeKind::Strong(_)))
+        .unwrap();
+    let ancestors: Vec<_> = tree.ancestors(strong).collect();
+    assert_eq!(3, ancestors.len());
+    assert!(matches!(tree.kind(ancestors[0]), NodeKind::Paragraph(_)));
+    assert_eq!(&[*quote, root], &ancestors[1..]);
+    assert_eq!(Some(ancestors[0]), tree.node(strong).parent());
+
+    let all: Vec<_> = tree.iter().collect();
+    assert_eq!(root, all[0]);
+    assert_eq!(tree.descendants(root).count() + 1, all.len());
+    assert!(all.iter().position(|id| id == quote) < all.iter().position(|id| *id == strong));
+}
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -86,6 +88,47 @@ impl LineNumber {
     }
 }
 
+/// 事件对应的标记（如开启部分、闭合部分、指示标记）在输入中的范围。只在启用了
+/// `source-span` 特性时记录实际的范围。
+///
+/// 对于没有对应标记的事件（如段落的进入与退出、隐式闭合的元素的退出），范围为
+/// 空，其位置是该事件在输入中发生的位置。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceSpan(
+    #[cfg(feature = "source-span")] usize,
+    #[cfg(feature = "source-span")] usize,
+);
+impl SourceSpan {
+    #[cfg(feature = "source-span")]
+    pub fn new(range: Range<usize>) -> Self {
+        Self(range.start, range.end)
+    }
+    #[cfg(not(feature = "source-span"))]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self()
+    }
+    pub fn new_universal(#[allow(unused_variables)] range: Range<usize>) -> Self {
+        #[cfg(feature = "source-span")]
+        {
+            Self::new(range)
+        }
+        #[cfg(not(feature = "source-span"))]
+        {
+            Self::new()
+        }
+    }
+    #[cfg(test)]
+    pub fn new_invalid() -> Self {
+        Self::new_universal(99999999..99999999)
+    }
+
+    #[cfg(feature = "source-span")]
+    pub fn range(&self) -> Range<usize> {
+        self.0..self.1
+    }
+}
+
 /// Tym = To Yield Mark. 用于确保代码执行过程中不会爆 `to_yield` 栈的辅助类型。
 pub struct Tym<const N: usize>;
 pub const TYM_UNIT: Tym<0> = Tym::<0> {};
@@ -34,18 +34,23 @@ static EVENT_TO_GROUP_RAW: &[(&str, &[&str])] = &[
     ("EnterDescriptionDetails", &["Block", "Blend"]),
     ("EnterCodeBlock", &["Block", "Blend"]),
     ("EnterTable", &["Block", "Blend"]),
+    ("EnterBlockExtension", &["Block", "Blend"]),
     ("IndicateCodeBlockCode", &["Block", "Blend"]),
     ("IndicateTableCaption", &["Block", "Blend"]),
     ("IndicateTableRow", &["Block", "Blend"]),
     ("IndicateTableHeaderCell", &["Block", "Blend"]),
     ("IndicateTableDataCell", &["Block", "Blend"]),
+    ("IndicateBlockExtensionSlot", &["Block", "Blend"]),
+    ("IndicateExtensionParameter", &["Block", "Inline", "Blend"]),
     ("ExitBlock", &["Block", "Blend"]),
     ("RefLink", &["Inline", "Blend"]),
-    ("Dicexp", &["Inline", "Blend"]),
+    ("EnterDicexp", &["Inline", "Blend"]),
     ("EnterCodeSpan", &["Inline", "Blend"]),
     ("EnterStrong", &["Inline", "Blend"]),
     ("EnterStrikethrough", &["Inline", "Blend"]),
     ("EnterWikiLink", &["Inline", "Blend"]),
+    ("EnterInlineExtension", &["Inline", "Blend"]),
+    ("IndicateInlineExtensionSlot", &["Inline", "Blend"]),
     ("ExitInline", &["Inline", "Blend"]),
 ];
 
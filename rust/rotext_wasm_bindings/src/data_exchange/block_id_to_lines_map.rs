@@ -4,7 +4,7 @@ pub fn create_block_id_to_lines_map(all_events: &[rotext::Event]) -> String {
     for ev in all_events.iter() {
         match ev {
             rotext::Event::ThematicBreak(data) => {
-                write_id_and_line_range(
+                write_id_and_range(
                     &mut result,
                     data.id.value(),
                     data.line.value(),
@@ -12,7 +12,7 @@ pub fn create_block_id_to_lines_map(all_events: &[rotext::Event]) -> String {
                 );
             }
             rotext::Event::ExitBlock(data) => {
-                write_id_and_line_range(
+                write_id_and_range(
                     &mut result,
                     data.id.value(),
                     data.start_line.value(),
@@ -32,7 +32,7 @@ pub fn create_block_id_to_lines_map(all_events: &[rotext::Event]) -> String {
     result
 }
 
-fn write_id_and_line_range(target: &mut String, id: usize, range_start: usize, range_end: usize) {
+pub fn write_id_and_range(target: &mut String, id: usize, range_start: usize, range_end: usize) {
     write_usize(target, id);
     target.push(':');
     write_usize(target, range_start);
@@ -0,0 +1,109 @@
+use std::ops::Range;
+
+use rotext::line_index::LineIndex;
+
+use super::block_id_to_lines_map::write_id_and_range;
+
+/// 与 [super::block_id_to_lines_map::create_block_id_to_lines_map] 类似，但记录的
+/// 是各块级元素在输入中的范围（从开启部分的开头到闭合部分的结尾），以 UTF-16 偏
+/// 移表示，以便 JavaScript 一侧直接使用。块级元素附着有块级附加信息（`{! … }`）
+/// 时，范围从附加信息的开头算起。
+pub fn create_block_id_to_utf16_ranges_map(input: &[u8], all_events: &[rotext::Event]) -> String {
+    let line_index = LineIndex::new(input);
+    let to_utf16 = |offset: usize| line_index.utf16_offset(offset).unwrap();
+
+    let mut result = String::new();
+    // 尚未退出的块级元素的开头。
+    let mut starts: Vec<usize> = vec![];
+
+    for ev in all_events.iter() {
+        match ev {
+            rotext::Event::EnterParagraph(data)
+            | rotext::Event::EnterHeading1(data)
+            | rotext::Event::EnterHeading2(data)
+            | rotext::Event::EnterHeading3(data)
+            | rotext::Event::EnterHeading4(data)
+            | rotext::Event::EnterHeading5(data)
+            | rotext::Event::EnterHeading6(data)
+            | rotext::Event::EnterBlockQuote(data)
+            | rotext::Event::EnterOrderedList(data)
+            | rotext::Event::EnterUnorderedList(data)
+            | rotext::Event::EnterListItem(data)
+            | rotext::Event::EnterDescriptionList(data)
+            | rotext::Event::EnterDescriptionTerm(data)
+            | rotext::Event::EnterDescriptionDetails(data)
+            | rotext::Event::EnterCodeBlock(data)
+            | rotext::Event::EnterTable(data) => {
+                starts.push(block_start(data.span.range(), &data.attributes));
+                continue;
+            }
+            rotext::Event::EnterBlockExtension(data) => {
+                starts.push(block_start(data.span.range(), &data.attributes));
+                continue;
+            }
+            rotext::Event::ThematicBreak(data) => {
+                let range = data.span.range();
+                write_id_and_range(
+                    &mut result,
+                    data.id.value(),
+                    to_utf16(block_start(range.clone(), &data.attributes)),
+                    to_utf16(range.end),
+                );
+            }
+            rotext::Event::ExitBlock(data) => {
+                let start = starts.pop().unwrap();
+                write_id_and_range(
+                    &mut result,
+                    data.id.value(),
+                    to_utf16(start),
+                    to_utf16(data.span.range().end),
+                );
+            }
+            _ => continue,
+        }
+
+        result.push(';');
+    }
+
+    if !result.is_empty() {
+        result.pop().unwrap();
+    }
+
+    result
+}
+
+/// 返回开启部分位于 `span` 的块级元素的开头，包括附着于其上的块级附加信息。
+fn block_start(span: Range<usize>, attributes: &Option<Range<usize>>) -> usize {
+    match attributes {
+        Some(attributes) => attributes.start.min(span.start),
+        None => span.start,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_includes_attributes_in_ranges() {
+        let table: &[(&str, &str)] = &[
+            ("一\n\n二", "1:0-1;2:3-4"),
+            ("{! .a}\n段落", "1:0-9"),
+            ("{! .a} ---", "1:0-10"),
+            ("{! .a}\n{{#Foo\n}}", "1:0-16"),
+            ("{! .a}\n* {! .b} 项", "3:9-17;2:7-17;1:0-17"),
+        ];
+
+        for (input, expected) in table {
+            let all_events: Vec<_> = rotext::parse(input.as_bytes())
+                .collect::<rotext::Result<_>>()
+                .unwrap();
+            assert_eq!(
+                *expected,
+                create_block_id_to_utf16_ranges_map(input.as_bytes(), &all_events),
+                "input: {:?}",
+                input
+            );
+        }
+    }
+}
@@ -4,14 +4,36 @@ pub fn new_tag_name_map_from_str(raw: &str) -> TagNameMap {
     let mut items = raw.as_bytes().split(|x| *x == 0);
 
     let code_block = items.next().unwrap();
+    let block_extension = items.next().unwrap();
+    let collapse = items.next().unwrap();
+    let callout_note = items.next().unwrap();
+    let callout_tip = items.next().unwrap();
+    let callout_important = items.next().unwrap();
+    let callout_warning = items.next().unwrap();
+    let callout_caution = items.next().unwrap();
     let ref_link = items.next().unwrap();
     let dicexp = items.next().unwrap();
     let wiki_link = items.next().unwrap();
+    let inline_extension = items.next().unwrap();
+    let scratch_off = items.next().unwrap();
+    let external_link = items.next().unwrap();
+    let preview_warn = items.next().unwrap();
 
     TagNameMap {
         code_block,
+        block_extension,
+        collapse,
+        callout_note,
+        callout_tip,
+        callout_important,
+        callout_warning,
+        callout_caution,
         ref_link,
         dicexp,
         wiki_link,
+        inline_extension,
+        scratch_off,
+        external_link,
+        preview_warn,
     }
 }
@@ -3,7 +3,9 @@ mod data_exchange;
 extern crate alloc;
 
 use data_exchange::{
-    block_id_to_lines_map::create_block_id_to_lines_map, tag_name_map::new_tag_name_map_from_str,
+    block_id_to_lines_map::create_block_id_to_lines_map,
+    block_id_to_utf16_ranges_map::create_block_id_to_utf16_ranges_map,
+    tag_name_map::new_tag_name_map_from_str,
 };
 
 #[cfg(debug_assertions)]
@@ -43,6 +45,7 @@ impl ParseAndRenderResult {
 pub struct ParseAndRenderOutput {
     html: String,
     block_id_to_lines_map: String,
+    block_id_to_utf16_ranges_map: String,
 
     #[cfg(debug_assertions)]
     dev_events_in_debug_format: String,
@@ -55,6 +58,9 @@ impl ParseAndRenderOutput {
     pub fn clone_block_id_to_lines_map(&self) -> String {
         self.block_id_to_lines_map.clone()
     }
+    pub fn clone_block_id_to_utf16_ranges_map(&self) -> String {
+        self.block_id_to_utf16_ranges_map.clone()
+    }
     #[cfg(debug_assertions)]
     pub fn clone_dev_events_in_debug_format(&self) -> String {
         self.dev_events_in_debug_format.clone()
@@ -92,18 +98,28 @@ pub fn parse_and_render(
         input,
         rotext::NewHtmlRendererOptions {
             tag_name_map,
+            extension_registry: Default::default(),
+            dicexp_result_provider: None,
+            ref_link_resolver: None,
+            ref_link_context: Default::default(),
+            wiki_link_resolver: None,
             initial_output_string_capacity: input.len() * 3,
+            should_include_heading_ids: false,
+            heading_level_adjustment: Default::default(),
+            render_mode: Default::default(),
             should_include_block_ids,
         },
     );
     let html: String = renderer.render(all_events.clone().into_iter());
 
     let block_id_to_lines_map = create_block_id_to_lines_map(&all_events);
+    let block_id_to_utf16_ranges_map = create_block_id_to_utf16_ranges_map(input, &all_events);
 
     #[allow(unused_mut)]
     let mut output = ParseAndRenderOutput {
         html,
         block_id_to_lines_map,
+        block_id_to_utf16_ranges_map,
         ..Default::default()
     };
 
@@ -4,7 +4,16 @@ export const TAG_NAME_MAP = {
   "dicexp": "x-dicexp",
   "collapse": "x-collapse",
   "code-block": "x-code-block",
+  "block-extension": "x-block-extension",
+  "callout-note": "x-callout-note",
+  "callout-tip": "x-callout-tip",
+  "callout-important": "x-callout-important",
+  "callout-warning": "x-callout-warning",
+  "callout-caution": "x-callout-caution",
   "wiki-link": "x-wiki-link",
+  "inline-extension": "x-inline-extension",
+  "external-link": "x-external-link",
+  "preview-warn": "x-preview-warn",
 };
 
 export const PROSE_CLASS = "tuan-prose";
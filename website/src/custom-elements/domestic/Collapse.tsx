@@ -19,7 +19,9 @@ function createCollapseComponent(): Component {
     });
 
     return (
-      <details class="mb-4 py-2 px-4 border border-[#444] rounded-lg bg-[#1e1e1e] text-gray-300">
+      <details
+        open={currentElement.hasAttribute("open")}
+        class="mb-4 py-2 px-4 border border-[#444] rounded-lg bg-[#1e1e1e] text-gray-300">
         <summary class="cursor-pointer font-bold text-white hover:text-blue-500 focus:outline-none">
           <slot name="title">折叠内容</slot>
         </summary>
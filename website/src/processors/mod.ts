@@ -7,9 +7,20 @@ export interface RotextProcessorProcessOptions {
 
 export interface TagNameMap {
   "code-block": string;
+  "block-extension": string;
+  "collapse": string;
+  "callout-note": string;
+  "callout-tip": string;
+  "callout-important": string;
+  "callout-warning": string;
+  "callout-caution": string;
   "ref-link": string;
   "dicexp": string;
   "wiki-link": string;
+  "inline-extension": string;
+  "scratch-off": string;
+  "external-link": string;
+  "preview-warn": string;
 }
 
 export interface RotextProcessor {